@@ -0,0 +1,134 @@
+//! `--write-baseline` and `--check-baseline`: a snapshot of the function
+//! inventory, to fail CI when functions appear, disappear or change
+//!
+//! A baseline records each function's qualified name, kind and signature,
+//! sorted by name, and nothing tied to where the function sits in its file,
+//! so edits that only move lines do not count as changes.
+
+use crate::FunctionInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Version of the baseline file, checked when reading one
+const BASELINE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub qualified_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Baseline {
+    pub baseline_version: u32,
+    pub functions: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    pub fn of(functions: &[FunctionInfo]) -> Self {
+        let mut entries: Vec<BaselineEntry> = functions
+            .iter()
+            .map(|f| BaselineEntry {
+                qualified_name: f.qualified_name.clone(),
+                kind: f.kind.clone(),
+                signature: f.signature.clone(),
+            })
+            .collect();
+        entries.sort();
+        entries.dedup();
+        Baseline {
+            baseline_version: BASELINE_VERSION,
+            functions: entries,
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let mut text = serde_json::to_string_pretty(self).unwrap();
+        text.push('\n');
+        fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let baseline: Baseline = serde_json::from_str(&text)
+            .map_err(|e| format!("{} is not a verus-parser baseline: {}", path.display(), e))?;
+        if baseline.baseline_version != BASELINE_VERSION {
+            return Err(format!(
+                "{} has baseline version {} but this verus-parser reads version {}; \
+                 regenerate it with --write-baseline",
+                path.display(),
+                baseline.baseline_version,
+                BASELINE_VERSION
+            ));
+        }
+        Ok(baseline)
+    }
+
+    fn by_name(&self) -> BTreeMap<&str, Vec<&BaselineEntry>> {
+        let mut by_name: BTreeMap<&str, Vec<&BaselineEntry>> = BTreeMap::new();
+        for entry in &self.functions {
+            by_name
+                .entry(&entry.qualified_name)
+                .or_default()
+                .push(entry);
+        }
+        by_name
+    }
+
+    /// The functions added (`+`), removed (`-`) and changed (`~`) since
+    /// `old`, one per line, empty when there are none
+    pub fn diff(&self, old: &Baseline) -> String {
+        let old = old.by_name();
+        let new = self.by_name();
+        let mut names: Vec<&str> = old.keys().chain(new.keys()).copied().collect();
+        names.sort();
+        names.dedup();
+
+        let mut out = String::new();
+        for name in names {
+            match (old.get(name), new.get(name)) {
+                (Some(before), Some(after)) if before == after => {}
+                (Some(before), Some(after)) => {
+                    writeln!(out, "~ {}", name).unwrap();
+                    for entry in before {
+                        writeln!(out, "    was {}", describe(entry)).unwrap();
+                    }
+                    for entry in after {
+                        writeln!(out, "    now {}", describe(entry)).unwrap();
+                    }
+                }
+                (Some(before), None) => {
+                    for entry in before {
+                        writeln!(out, "- {}", describe(entry)).unwrap();
+                    }
+                }
+                (None, Some(after)) => {
+                    for entry in after {
+                        writeln!(out, "+ {}", describe(entry)).unwrap();
+                    }
+                }
+                (None, None) => unreachable!("the name comes from one side"),
+            }
+        }
+        out
+    }
+}
+
+/// `name [kind]: signature`, with the parts known
+fn describe(entry: &BaselineEntry) -> String {
+    let mut text = entry.qualified_name.clone();
+    if let Some(ref kind) = entry.kind {
+        write!(text, " [{}]", kind).unwrap();
+    }
+    if let Some(ref signature) = entry.signature {
+        write!(text, ": {}", signature).unwrap();
+    }
+    text
+}
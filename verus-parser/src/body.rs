@@ -0,0 +1,317 @@
+//! Facts gathered by walking a function's body and signature
+//!
+//! The body of every extracted function (including bodies inside `verus!`,
+//! proof blocks and `requires`/`ensures` clauses) is visited once here and
+//! the results are attached to its `FunctionInfo`.
+
+use crate::pretty::pretty;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use verus_syn::punctuated::Punctuated;
+use verus_syn::visit::Visit;
+use verus_syn::{
+    Assume, AttrStyle, Attribute, Block, Expr, ExprCall, ExprLit, ExprMethodCall, Item, Lit, Macro,
+    Meta, PatIdent, RevealHide, Signature, Token,
+};
+
+/// A called function or method
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Callee {
+    /// Fully-qualified path when it can be resolved through the module's
+    /// imports (`backend::serial::u64::field::FieldElement51::reduce`),
+    /// otherwise the path as written
+    pub path: String,
+    /// Set for method calls on a receiver of unknown type, where `path` is
+    /// only the method name, and for names several glob imports could
+    /// provide; such a path may match functions of several types or modules
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ambiguous: bool,
+}
+
+/// Everything collected from one function
+#[derive(Debug, Default)]
+pub struct BodyFacts {
+    pub callees: BTreeSet<Callee>,
+    /// Functions referenced from the spec clauses of the signature
+    /// (`requires`, `ensures`, `decreases`, ...)
+    pub spec_deps: BTreeSet<Callee>,
+    /// Calls whose target cannot be named: closures, function pointers, ...
+    pub unresolved_calls: BTreeSet<String>,
+    /// Why the function is trusted rather than verified (`assume`, `admit`)
+    pub trusted: BTreeSet<String>,
+    /// Functions named by `reveal(f)` or `reveal_with_fuel(f, n)`
+    pub reveals: BTreeSet<String>,
+}
+
+/// Walk the signature (parameters and spec clauses) and the body of a
+/// function; `self_type` is the enclosing impl's type or trait, if any
+pub fn scan_function(sig: &Signature, body: Option<&Block>, self_type: Option<&str>) -> BodyFacts {
+    let mut visitor = BodyVisitor {
+        self_type: self_type.map(str::to_string),
+        ..Default::default()
+    };
+    // Parameters and a named return value are locals of the spec clauses
+    for arg in &sig.inputs {
+        visitor.visit_fn_arg(arg);
+    }
+    visitor.visit_return_type(&sig.output);
+    visitor.visit_signature_spec(&sig.spec);
+    visitor.facts.spec_deps = std::mem::take(&mut visitor.facts.callees);
+    // The proof function of `decreases .. via f` is verified as part of the
+    // termination proof, like a call from the body
+    if let Some((_, Expr::Path(p))) = sig.spec.decreases.as_ref().and_then(|d| d.via.as_ref()) {
+        let path = visitor.resolve_self(path_to_string(&p.path));
+        visitor.facts.callees.insert(Callee {
+            path,
+            ambiguous: false,
+        });
+    }
+    if let Some(block) = body {
+        visitor.visit_block(block);
+    }
+    visitor.facts
+}
+
+/// `a::b::c` for a path, ignoring generic arguments
+pub fn path_to_string(path: &verus_syn::Path) -> String {
+    let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    segments.join("::")
+}
+
+/// Outer attributes of an item as written (`#[verifier::rlimit(20)]`),
+/// leaving out doc comments
+pub fn attributes(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| matches!(attr.style, AttrStyle::Outer) && !attr.path().is_ident("doc"))
+        .map(|attr| format!("#[{}]", pretty(&attr.meta)))
+        .collect()
+}
+
+/// Doc comments of an item (`///`, `/** */` and `#[doc = "..."]`) joined
+/// with newlines, dropping the indentation all lines share and the ` * `
+/// margin of block comments; the lines of a block comment starting on the
+/// `/**` line lose the indentation they share apart from that first one.
+/// `None` without any documentation
+pub fn doc(attrs: &[Attribute]) -> Option<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for attr in attrs {
+        let Meta::NameValue(nv) = &attr.meta else {
+            continue;
+        };
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) = &nv.value
+        else {
+            continue;
+        };
+        if !nv.path.is_ident("doc") {
+            continue;
+        }
+        let value = s.value();
+        let mut block: Vec<&str> = value.split('\n').map(str::trim_end).collect();
+        if block.len() > 1 {
+            // The first line follows `/**` and has no margin of its own
+            let margin = block[1..]
+                .iter()
+                .filter(|line| !line.trim().is_empty())
+                .all(|line| line.trim_start().starts_with('*'));
+            if margin {
+                for line in &mut block[1..] {
+                    *line = line.trim_start().strip_prefix('*').unwrap_or(line);
+                }
+            }
+            if !block[0].trim().is_empty() {
+                block[0] = block[0].trim_start();
+                let indent = block[1..]
+                    .iter()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| line.len() - line.trim_start().len())
+                    .min()
+                    .unwrap_or(0);
+                for line in &mut block[1..] {
+                    *line = line.get(indent..).unwrap_or("");
+                }
+            }
+        }
+        lines.extend(block.into_iter().map(String::from));
+    }
+    let written = |line: &&String| !line.trim().is_empty();
+    let indent = lines
+        .iter()
+        .filter(written)
+        .map(|line| line.len() - line.trim_start().len())
+        .min()?;
+    let first = lines.iter().position(|line| written(&line))?;
+    let last = lines.iter().rposition(|line| written(&line))?;
+    let text: Vec<&str> = lines[first..=last]
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect();
+    Some(text.join("\n"))
+}
+
+/// Whether a function is marked as a test or benchmark: `#[test]`,
+/// `#[bench]` or a runner's own (`#[tokio::test]`)
+pub fn is_test_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "test" || s.ident == "bench")
+    })
+}
+
+/// Names of the verifier attributes on an item: `external_body` for both
+/// `#[verifier::external_body]` and `#[verifier(external_body)]`
+pub fn verifier_attrs(attrs: &[Attribute]) -> Vec<String> {
+    let mut names = Vec::new();
+    for attr in attrs {
+        match &attr.meta {
+            Meta::Path(path)
+                if path.segments.len() == 2 && path.segments[0].ident == "verifier" =>
+            {
+                names.push(path.segments[1].ident.to_string());
+            }
+            Meta::List(list) if list.path.is_ident("verifier") => {
+                let parser = Punctuated::<Meta, Token![,]>::parse_terminated;
+                if let Ok(inner) = list.parse_args_with(parser) {
+                    names.extend(inner.iter().map(|m| path_to_string(m.path())));
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// The argument of a verifier attribute, as written: `20` for both
+/// `#[verifier::rlimit(20)]` and `#[verifier(rlimit(20))]`
+pub fn verifier_attr_arg(attrs: &[Attribute], name: &str) -> Option<String> {
+    for attr in attrs {
+        let Meta::List(list) = &attr.meta else {
+            continue;
+        };
+        let segments = &list.path.segments;
+        if segments.len() == 2 && segments[0].ident == "verifier" && segments[1].ident == name {
+            return Some(pretty(&list.tokens));
+        }
+        if list.path.is_ident("verifier") {
+            let parser = Punctuated::<Meta, Token![,]>::parse_terminated;
+            let Ok(inner) = list.parse_args_with(parser) else {
+                continue;
+            };
+            for meta in inner {
+                if let Meta::List(arg) = meta {
+                    if arg.path.is_ident(name) {
+                        return Some(pretty(&arg.tokens));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Whether an expression is `self`, possibly dereferenced or borrowed
+fn is_self(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(p) => p.qself.is_none() && p.path.is_ident("self"),
+        Expr::Paren(p) => is_self(&p.expr),
+        Expr::Unary(u) => matches!(u.op, verus_syn::UnOp::Deref(_)) && is_self(&u.expr),
+        Expr::Reference(r) => is_self(&r.expr),
+        _ => false,
+    }
+}
+
+#[derive(Default)]
+struct BodyVisitor {
+    facts: BodyFacts,
+    self_type: Option<String>,
+    /// Names bound by parameters, `let`s, closures and patterns; calling one
+    /// of these is a call through a closure or function pointer
+    locals: BTreeSet<String>,
+}
+
+impl BodyVisitor {
+    /// `Self::f` as a path of the enclosing impl's type
+    fn resolve_self(&self, path: String) -> String {
+        match (path.strip_prefix("Self::"), &self.self_type) {
+            (Some(rest), Some(ty)) => format!("{}::{}", ty, rest),
+            _ => path,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for BodyVisitor {
+    fn visit_item(&mut self, _node: &'ast Item) {
+        // Nested items are reported as functions of their own
+    }
+
+    fn visit_pat_ident(&mut self, node: &'ast PatIdent) {
+        self.locals.insert(node.ident.to_string());
+        verus_syn::visit::visit_pat_ident(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast ExprCall) {
+        match &*node.func {
+            Expr::Path(p) if p.qself.is_none() => {
+                let path = path_to_string(&p.path);
+                if p.path.segments.last().is_some_and(|s| s.ident == "admit") {
+                    self.facts.trusted.insert("admit".to_string());
+                }
+                if p.path.segments.len() == 1 && self.locals.contains(&path) {
+                    self.facts.unresolved_calls.insert(path);
+                } else {
+                    self.facts.callees.insert(Callee {
+                        path: self.resolve_self(path),
+                        ambiguous: false,
+                    });
+                }
+            }
+            other => {
+                self.facts.unresolved_calls.insert(pretty(other));
+            }
+        }
+        verus_syn::visit::visit_expr_call(self, node);
+    }
+
+    fn visit_assume(&mut self, node: &'ast Assume) {
+        self.facts.trusted.insert("assume".to_string());
+        verus_syn::visit::visit_assume(self, node);
+    }
+
+    fn visit_reveal_hide(&mut self, node: &'ast RevealHide) {
+        if node.hide_token.is_none() && node.path.qself.is_none() {
+            self.facts.reveals.insert(path_to_string(&node.path.path));
+        }
+        verus_syn::visit::visit_reveal_hide(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        let method = node.method.to_string();
+        let callee = match &self.self_type {
+            Some(ty) if is_self(&node.receiver) => Callee {
+                path: format!("{}::{}", ty, method),
+                ambiguous: false,
+            },
+            _ => Callee {
+                path: method,
+                ambiguous: true,
+            },
+        };
+        self.facts.callees.insert(callee);
+        verus_syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast Macro) {
+        // Most expression macros (`assert_eq!`, `vec!`, ...) take a comma
+        // separated list of expressions; calls inside them count too
+        let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+        if let Ok(exprs) = node.parse_body_with(parser) {
+            for expr in &exprs {
+                self.visit_expr(expr);
+            }
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Call graph over the extracted functions
+//!
+//! Edges come from the `callees` recorded for each function, and optionally
+//! from its `spec_deps` (functions named in `requires`/`ensures`). A callee path
+//! (`field::mul`, `FieldElement51::reduce`, `pow2k`) resolves to every parsed
+//! function whose qualified name ends with that path; ambiguous method calls
+//! (`x.square()` on a receiver of unknown type) therefore resolve to every
+//! method of that name. The graph errs on the side of including too many
+//! dependencies rather than too few.
+//!
+//! Recursion detection uses only the definite edges: calls that are not
+//! ambiguous and resolve to exactly one function. Otherwise every method
+//! calling `clone()` on a field would look recursive.
+
+use crate::FunctionInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+/// How a function reaches itself through calls
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Recursion {
+    /// The function calls itself
+    Direct,
+    /// The function is part of a cycle through other functions
+    Mutual,
+}
+
+impl std::fmt::Display for Recursion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Recursion::Direct => write!(f, "direct"),
+            Recursion::Mutual => write!(f, "mutual"),
+        }
+    }
+}
+
+/// A call that did not resolve to any parsed function
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UnresolvedCallee {
+    /// Qualified name of the calling function
+    pub caller: String,
+    /// Callee as written in the source
+    pub callee: String,
+}
+
+/// Whether a function is defined in `module` or one of its submodules;
+/// `crate` (or the empty path) is the crate root
+pub fn in_module(func: &FunctionInfo, module: &str) -> bool {
+    module_within(&func.module_path, module)
+}
+
+/// Whether `module_path` is `module` or one of its submodules
+pub fn module_within(module_path: &str, module: &str) -> bool {
+    let module = module.strip_prefix("crate").unwrap_or(module);
+    let module = module.strip_prefix("::").unwrap_or(module);
+    module.is_empty()
+        || module_path == module
+        || module_path
+            .strip_prefix(module)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+pub struct CallGraph<'a> {
+    functions: &'a [FunctionInfo],
+    /// Qualified names of trait impl methods through their self type
+    self_type_paths: Vec<Option<String>>,
+    /// Function indices by their unqualified name
+    by_name: HashMap<&'a str, Vec<usize>>,
+    /// Resolved callees of each function
+    edges: Vec<BTreeSet<usize>>,
+    /// Callees that are certainly called (see module docs)
+    definite: Vec<BTreeSet<usize>>,
+    /// Callees of each function that matched no parsed function
+    unresolved: Vec<Vec<String>>,
+}
+
+impl<'a> CallGraph<'a> {
+    /// Build the graph; functions must have been extracted with calls
+    /// enabled. `spec_deps` adds edges for functions referenced from spec
+    /// clauses, which verifying a function also needs.
+    pub fn new(functions: &'a [FunctionInfo], spec_deps: bool) -> Self {
+        let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (idx, func) in functions.iter().enumerate() {
+            by_name.entry(func.name.as_str()).or_default().push(idx);
+        }
+
+        let mut graph = CallGraph {
+            functions,
+            self_type_paths: functions.iter().map(FunctionInfo::self_type_path).collect(),
+            by_name,
+            edges: Vec::with_capacity(functions.len()),
+            definite: Vec::with_capacity(functions.len()),
+            unresolved: Vec::with_capacity(functions.len()),
+        };
+
+        for func in functions {
+            let mut edges = BTreeSet::new();
+            let mut definite = BTreeSet::new();
+            let mut unresolved = Vec::new();
+            let spec = func.spec_deps.iter().flatten().filter(|_| spec_deps);
+            for callee in func.callees.iter().flatten().chain(spec) {
+                let targets = graph.resolve(&callee.path);
+                match targets.as_slice() {
+                    [] => unresolved.push(callee.path.clone()),
+                    [target] if !callee.ambiguous => {
+                        definite.insert(*target);
+                    }
+                    _ => {}
+                }
+                edges.extend(targets);
+            }
+            graph.edges.push(edges);
+            graph.definite.push(definite);
+            graph.unresolved.push(unresolved);
+        }
+
+        graph
+    }
+
+    /// Indices of all functions a path may refer to: the function with that
+    /// exact qualified name, or else all functions whose qualified name ends
+    /// with the path; trait impl methods also match through their self type
+    /// (`Fe::mul` for `<Fe as Mul>::mul`)
+    pub fn resolve(&self, path: &str) -> Vec<usize> {
+        let segments: Vec<&str> = path
+            .split("::")
+            .skip_while(|s| matches!(*s, "crate" | "self" | "super" | "Self"))
+            .collect();
+        let Some(name) = segments.last() else {
+            return Vec::new();
+        };
+        let suffix = segments.join("::");
+
+        let candidates = self.by_name.get(name).map(Vec::as_slice).unwrap_or(&[]);
+        let exact: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&idx| self.names(idx).any(|name| name == suffix))
+            .collect();
+        if !exact.is_empty() {
+            return exact;
+        }
+
+        let dotted = format!("::{}", suffix);
+        candidates
+            .iter()
+            .copied()
+            .filter(|&idx| self.names(idx).any(|name| name.ends_with(&dotted)))
+            .collect()
+    }
+
+    /// The qualified name of a function, and its path through its self type
+    fn names(&self, idx: usize) -> impl Iterator<Item = &str> {
+        std::iter::once(self.functions[idx].qualified_name.as_str())
+            .chain(self.self_type_paths[idx].as_deref())
+    }
+
+    /// All functions reachable from `roots`, including the roots themselves,
+    /// in breadth-first order
+    pub fn closure(&self, roots: &[usize]) -> Vec<usize> {
+        self.closure_with_depth(roots, None, None)
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Like `closure`, but only following at most `max_depth` calls from a
+    /// root, and paired with the depth at which each function is first
+    /// reached (0 for the roots). With `within`, functions outside that
+    /// module are listed but their callees are not followed.
+    pub fn closure_with_depth(
+        &self,
+        roots: &[usize],
+        max_depth: Option<usize>,
+        within: Option<&str>,
+    ) -> Vec<(usize, usize)> {
+        let mut seen = vec![false; self.functions.len()];
+        let mut order = Vec::new();
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+
+        for &root in roots {
+            if !seen[root] {
+                seen[root] = true;
+                queue.push_back((root, 0));
+            }
+        }
+        while let Some((idx, depth)) = queue.pop_front() {
+            order.push((idx, depth));
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            if depth > 0 && within.is_some_and(|m| !in_module(&self.functions[idx], m)) {
+                continue;
+            }
+            for &next in &self.edges[idx] {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+
+        order
+    }
+
+    /// All functions that call any of `roots`, directly or transitively up
+    /// to `max_depth` calls away (1 for direct callers), in breadth-first
+    /// order and paired with their depth. A root is only included when it
+    /// is itself a caller, i.e. it is recursive.
+    pub fn callers(&self, roots: &[usize], max_depth: Option<usize>) -> Vec<(usize, usize)> {
+        let mut reverse: Vec<Vec<usize>> = vec![Vec::new(); self.functions.len()];
+        for (caller, callees) in self.edges.iter().enumerate() {
+            for &callee in callees {
+                reverse[callee].push(caller);
+            }
+        }
+
+        let mut reached = vec![false; self.functions.len()];
+        let mut expanded = vec![false; self.functions.len()];
+        let mut order = Vec::new();
+        let mut queue: VecDeque<(usize, usize)> = roots.iter().map(|&root| (root, 0)).collect();
+        for &root in roots {
+            expanded[root] = true;
+        }
+
+        while let Some((idx, depth)) = queue.pop_front() {
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            for &caller in &reverse[idx] {
+                if !reached[caller] {
+                    reached[caller] = true;
+                    order.push((caller, depth + 1));
+                }
+                if !expanded[caller] {
+                    expanded[caller] = true;
+                    queue.push_back((caller, depth + 1));
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Resolved callees of a function
+    pub fn callees(&self, idx: usize) -> &BTreeSet<usize> {
+        &self.edges[idx]
+    }
+
+    /// Strongly connected components of the definite call graph, in
+    /// reverse topological order (callees before callers)
+    pub fn sccs(&self) -> Vec<Vec<usize>> {
+        // Iterative Tarjan, so deep call chains cannot overflow the stack
+        let n = self.functions.len();
+        let mut index = vec![usize::MAX; n];
+        let mut lowlink = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for start in 0..n {
+            if index[start] != usize::MAX {
+                continue;
+            }
+            // (node, iterator position over its successors)
+            let mut work: Vec<(usize, Vec<usize>, usize)> = Vec::new();
+            index[start] = next_index;
+            lowlink[start] = next_index;
+            next_index += 1;
+            stack.push(start);
+            on_stack[start] = true;
+            work.push((start, self.definite[start].iter().copied().collect(), 0));
+
+            while let Some((node, succs, pos)) = work.last_mut() {
+                let node = *node;
+                if let Some(&next) = succs.get(*pos) {
+                    *pos += 1;
+                    if index[next] == usize::MAX {
+                        index[next] = next_index;
+                        lowlink[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        work.push((next, self.definite[next].iter().copied().collect(), 0));
+                    } else if on_stack[next] {
+                        lowlink[node] = lowlink[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                work.pop();
+                if let Some((parent, _, _)) = work.last() {
+                    lowlink[*parent] = lowlink[*parent].min(lowlink[node]);
+                }
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// How each function is recursive, if it is
+    pub fn recursion(&self) -> Vec<Option<Recursion>> {
+        let mut recursion = vec![None; self.functions.len()];
+        for component in self.sccs() {
+            let kind = if component.len() > 1 {
+                Recursion::Mutual
+            } else if self.definite[component[0]].contains(&component[0]) {
+                Recursion::Direct
+            } else {
+                continue;
+            };
+            for idx in component {
+                recursion[idx] = Some(kind);
+            }
+        }
+        recursion
+    }
+
+    /// Whether each function is recursive, directly or through other functions
+    pub fn recursive_functions(&self) -> Vec<bool> {
+        self.recursion().iter().map(Option::is_some).collect()
+    }
+
+    /// Modules each module calls into, keyed by module path (`crate` for
+    /// the crate root); calls within a module are not included
+    pub fn module_dependencies(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let module_name = |idx: usize| {
+            let path = &self.functions[idx].module_path;
+            if path.is_empty() {
+                "crate".to_string()
+            } else {
+                path.clone()
+            }
+        };
+
+        let mut deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for (caller, callees) in self.edges.iter().enumerate() {
+            let from = module_name(caller);
+            let targets = deps.entry(from.clone()).or_default();
+            for &callee in callees {
+                let to = module_name(callee);
+                if to != from {
+                    targets.insert(to);
+                }
+            }
+        }
+        deps
+    }
+
+    /// Unresolved callees of the given functions
+    pub fn unresolved_callees(&self, indices: &[usize]) -> Vec<UnresolvedCallee> {
+        let mut result: Vec<UnresolvedCallee> = indices
+            .iter()
+            .flat_map(|&idx| {
+                self.unresolved[idx]
+                    .iter()
+                    .map(move |callee| UnresolvedCallee {
+                        caller: self.functions[idx].qualified_name.clone(),
+                        callee: callee.clone(),
+                    })
+            })
+            .collect();
+        result.sort();
+        result
+    }
+}
@@ -0,0 +1,125 @@
+//! `#[cfg(..)]` conditions of functions and their evaluation
+//!
+//! A function's conditions are those of its own attributes, of the modules,
+//! impls and traits around it (the `mod` declaration of its file included)
+//! and of the `cfg_if!` branch it comes from: the branch's condition and the
+//! negation of every earlier branch's. Each condition is kept as written
+//! (`feature = "u64_backend"`, `not(test)`); all of them must hold.
+
+use crate::body::path_to_string;
+use crate::pretty::pretty_tokens;
+use proc_macro2::TokenStream;
+use std::collections::BTreeSet;
+use verus_syn::parse::Parser;
+use verus_syn::punctuated::Punctuated;
+use verus_syn::{Attribute, Expr, ExprLit, Lit, Meta, Token};
+
+/// The predicate of a `cfg(..)` attribute
+fn condition(meta: &Meta) -> Option<String> {
+    match meta {
+        Meta::List(list) if list.path.is_ident("cfg") => {
+            Some(pretty_tokens(list.tokens.clone(), false))
+        }
+        _ => None,
+    }
+}
+
+/// The conditions of the `#[cfg(..)]` (and `#![cfg(..)]`) attributes
+pub fn conditions(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter_map(|attr| condition(&attr.meta))
+        .collect()
+}
+
+/// The condition of a `cfg_if!` branch, from the tokens inside `#[..]`
+pub fn branch_condition(tokens: TokenStream) -> Option<String> {
+    condition(&verus_syn::parse2::<Meta>(tokens).ok()?)
+}
+
+/// Whether the conditions include `test`, making the code test-only
+pub fn is_test(conditions: &[String]) -> bool {
+    conditions.iter().any(|c| c == "test")
+}
+
+/// The condition holding when `condition` does not
+pub fn negate(condition: &str) -> String {
+    format!("not({})", condition)
+}
+
+/// The options of one configuration: features, target flags and bare
+/// flags like `test`
+#[derive(Debug, Clone)]
+pub struct CfgSet {
+    options: BTreeSet<(String, Option<String>)>,
+}
+
+impl CfgSet {
+    /// Parse a comma-separated list of options as written in a condition:
+    /// `feature = "u64_backend", target_pointer_width = "64", test`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated
+            .parse_str(text)
+            .map_err(|e| format!("invalid cfg options: {}", e))?;
+        let mut options = BTreeSet::new();
+        for meta in &metas {
+            let option = match meta {
+                Meta::Path(path) => (path_to_string(path), None),
+                Meta::NameValue(nv) => match &nv.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(s), ..
+                    }) => (path_to_string(&nv.path), Some(s.value())),
+                    _ => {
+                        return Err(format!(
+                            "the value of {} must be a string",
+                            path_to_string(&nv.path)
+                        ))
+                    }
+                },
+                Meta::List(list) => {
+                    return Err(format!(
+                        "{}(..) is not an option",
+                        path_to_string(&list.path)
+                    ))
+                }
+            };
+            options.insert(option);
+        }
+        Ok(CfgSet { options })
+    }
+
+    /// Whether a condition holds for these options; conditions that do not
+    /// parse are kept as holding
+    pub fn satisfies(&self, condition: &str) -> bool {
+        verus_syn::parse_str::<Meta>(condition).map_or(true, |meta| self.eval(&meta))
+    }
+
+    fn eval(&self, meta: &Meta) -> bool {
+        match meta {
+            Meta::Path(path) => self.options.contains(&(path_to_string(path), None)),
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => self
+                    .options
+                    .contains(&(path_to_string(&nv.path), Some(s.value()))),
+                _ => false,
+            },
+            Meta::List(list) => {
+                let parser = Punctuated::<Meta, Token![,]>::parse_terminated;
+                let Ok(args) = list.parse_args_with(parser) else {
+                    return false;
+                };
+                if list.path.is_ident("all") {
+                    args.iter().all(|m| self.eval(m))
+                } else if list.path.is_ident("any") {
+                    args.iter().any(|m| self.eval(m))
+                } else if list.path.is_ident("not") && args.len() == 1 {
+                    !self.eval(&args[0])
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! `--format ctags`: a tags file for jumping to definitions in vim or emacs
+//!
+//! The file follows the extended format of Exuberant Ctags, sorted by tag
+//! name. Each entry locates its definition with a search pattern for the
+//! line naming it (`/^  pub fn name(x: u64) -> u64 {$/`), found again in
+//! the source, so the tags survive edits elsewhere in the file; the line is
+//! also given as a `line:` field. Functions in `verus!` blocks are tagged
+//! like any other, with a kind letter per mode so they can be told apart.
+
+use crate::{Declarations, FunctionInfo};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+
+/// Kind letters, with the names `!_TAG_KIND_DESCRIPTION` gives them
+const KINDS: [(char, &str); 15] = [
+    ('f', "exec function"),
+    ('x', "spec function"),
+    ('p', "proof function"),
+    ('b', "broadcast group"),
+    ('E', "external item"),
+    ('T', "state machine transition"),
+    ('M', "macro definition"),
+    ('s', "struct"),
+    ('g', "enum"),
+    ('u', "union"),
+    ('t', "type alias"),
+    ('i', "trait"),
+    ('C', "constant"),
+    ('v', "static"),
+    ('F', "function"),
+];
+
+/// A definition to tag: its name, file, line span, the keywords that may
+/// precede the name and its kind letter
+struct Definition<'a> {
+    name: &'a str,
+    file: Option<&'a str>,
+    start_line: usize,
+    end_line: usize,
+    keywords: &'static [&'static str],
+    kind: char,
+}
+
+/// The kind letter of a function; `F` when its mode was not extracted
+fn function_kind(func: &FunctionInfo) -> char {
+    if func.is_broadcast_group() {
+        return 'b';
+    }
+    if func.is_external() {
+        return 'E';
+    }
+    if func.is_macro_def() {
+        return 'M';
+    }
+    if func.is_transition() {
+        return 'T';
+    }
+    match func.mode() {
+        Some(mode) if mode.starts_with("spec") => 'x',
+        Some(mode) if mode.starts_with("proof") => 'p',
+        Some(_) => 'f',
+        None => 'F',
+    }
+}
+
+/// Whether a line names `name` right after one of `keywords`, or anywhere
+/// when `keywords` is empty
+fn names(line: &str, keywords: &[&str], name: &str) -> bool {
+    let words: Vec<&str> = line
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    if keywords.is_empty() {
+        return words.contains(&name);
+    }
+    words
+        .windows(2)
+        .any(|pair| pair[1] == name && keywords.contains(&pair[0]))
+}
+
+/// The line of a definition spanning `start..=end` that names it: the
+/// first after a keyword, else the first mentioning the name, else `start`
+fn definition_line(
+    lines: &[String],
+    start: usize,
+    end: usize,
+    keywords: &[&str],
+    name: &str,
+) -> usize {
+    let span = start.max(1)..=end.min(lines.len());
+    let find = |keywords: &[&str]| {
+        span.clone().find(|&n| {
+            !lines[n - 1].trim_start().starts_with("//") && names(&lines[n - 1], keywords, name)
+        })
+    };
+    find(keywords).or_else(|| find(&[])).unwrap_or(start)
+}
+
+/// A search pattern matching the whole line, `/` and `\` escaped
+fn pattern(line: &str) -> String {
+    let escaped = line.replace('\\', "\\\\").replace('/', "\\/");
+    format!("/^{}$/", escaped)
+}
+
+/// The keywords introducing the name of a function's definition
+fn function_keywords(func: &FunctionInfo) -> &'static [&'static str] {
+    if func.is_broadcast_group() {
+        &["group"]
+    } else if func.is_macro_def() {
+        &["macro_rules"]
+    } else if func.is_transition() {
+        &[]
+    } else {
+        &["fn", "struct", "enum", "union", "trait", "type"]
+    }
+}
+
+/// The tags of the functions and, when extracted, the types, traits,
+/// aliases and consts
+pub fn render(functions: &[FunctionInfo], declarations: &Declarations) -> String {
+    let mut definitions = Vec::new();
+    for func in functions {
+        definitions.push(Definition {
+            name: &func.name,
+            file: func.file.as_deref(),
+            start_line: func.start_line,
+            end_line: func.end_line,
+            keywords: function_keywords(func),
+            kind: function_kind(func),
+        });
+    }
+    for ty in &declarations.types {
+        let (keywords, kind): (&'static [&'static str], char) = match ty.kind.as_str() {
+            "enum" => (&["enum"], 'g'),
+            "union" => (&["union"], 'u'),
+            _ => (&["struct"], 's'),
+        };
+        definitions.push(Definition {
+            name: &ty.name,
+            file: ty.file.as_deref(),
+            start_line: ty.start_line,
+            end_line: ty.end_line,
+            keywords,
+            kind,
+        });
+    }
+    for alias in &declarations.type_aliases {
+        definitions.push(Definition {
+            name: &alias.name,
+            file: alias.file.as_deref(),
+            start_line: alias.start_line,
+            end_line: alias.end_line,
+            keywords: &["type"],
+            kind: 't',
+        });
+    }
+    for tr in &declarations.traits {
+        definitions.push(Definition {
+            name: &tr.name,
+            file: tr.file.as_deref(),
+            start_line: tr.start_line,
+            end_line: tr.end_line,
+            keywords: &["trait"],
+            kind: 'i',
+        });
+    }
+    for c in &declarations.consts {
+        let (keywords, kind): (&'static [&'static str], char) = if c.kind.starts_with("static") {
+            (&["static", "mut"], 'v')
+        } else {
+            (&["const"], 'C')
+        };
+        definitions.push(Definition {
+            name: &c.name,
+            file: c.file.as_deref(),
+            start_line: c.start_line,
+            end_line: c.end_line,
+            keywords,
+            kind,
+        });
+    }
+
+    // The sources are read again to find the lines the patterns match
+    let mut sources: HashMap<&str, Vec<String>> = HashMap::new();
+    let mut tags: Vec<(Definition, &str, usize, String)> = Vec::new();
+    for definition in definitions {
+        let Some(file) = definition.file else {
+            continue;
+        };
+        let lines = sources.entry(file).or_insert_with(|| {
+            fs::read_to_string(file)
+                .map(|source| source.lines().map(str::to_string).collect())
+                .unwrap_or_default()
+        });
+        let line = definition_line(
+            lines,
+            definition.start_line,
+            definition.end_line,
+            definition.keywords,
+            definition.name,
+        );
+        let text = line
+            .checked_sub(1)
+            .and_then(|n| lines.get(n))
+            .map_or("", String::as_str);
+        tags.push((definition, file, line, pattern(text)));
+    }
+    tags.sort_by(|(a, a_file, a_line, _), (b, b_file, b_line, _)| {
+        (a.name, a_file, a_line).cmp(&(b.name, b_file, b_line))
+    });
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "!_TAG_FILE_FORMAT\t2\t/extended format; --format=1 will not append ;\" to lines/"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/"
+    )
+    .unwrap();
+    writeln!(out, "!_TAG_PROGRAM_NAME\t{}\t//", env!("CARGO_PKG_NAME")).unwrap();
+    writeln!(
+        out,
+        "!_TAG_PROGRAM_VERSION\t{}\t//",
+        env!("CARGO_PKG_VERSION")
+    )
+    .unwrap();
+    for (letter, description) in KINDS {
+        writeln!(
+            out,
+            "!_TAG_KIND_DESCRIPTION!Rust\t{},{}\t/{}/",
+            letter,
+            description.replace(' ', "_"),
+            description
+        )
+        .unwrap();
+    }
+    for (definition, file, line, pattern) in &tags {
+        writeln!(
+            out,
+            "{}\t{}\t{};\"\t{}\tline:{}",
+            definition.name, file, pattern, definition.kind, line
+        )
+        .unwrap();
+    }
+    out
+}
@@ -0,0 +1,470 @@
+//! `diff`: what changed between the JSON outputs of two runs
+//!
+//! Functions are matched by qualified name, in order when a name occurs
+//! more than once. Those left over in a file git found renamed are then
+//! matched with those of the new file by their name within the module.
+//! The rest are paired when exactly one old and one new function have the
+//! same name, kind and signature: the function moved to another module
+//! rather than being removed and added. A matched function in another file
+//! is a move too; moves are listed apart from the changes of kind,
+//! signature or specs.
+
+use crate::{git, query, ExtractOptions, FunctionInfo, OutputFormat, SCHEMA_VERSION};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+
+/// Output formats of `diff`
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum DiffFormat {
+    /// The matched, added, removed and moved functions with a summary
+    Json,
+    /// A summary and a line per function changed
+    Text,
+}
+
+impl DiffFormat {
+    /// The output format it is written as
+    pub fn output_format(&self) -> &'static OutputFormat {
+        match self {
+            DiffFormat::Json => &OutputFormat::Json,
+            DiffFormat::Text => &OutputFormat::Text,
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DiffArgs {
+    /// The JSON written by `verus-parser PATH --format json` before and
+    /// after the changes, OLD then NEW; with `--git-old` and `--git-new`,
+    /// the PATH to parse at both revisions instead
+    #[arg(value_name = "FILE", num_args = 1..=2, required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Parse PATH at this git revision for the old functions, reading the
+    /// files from the repository rather than a checkout
+    #[arg(long, value_name = "REV", requires = "git_new")]
+    git_old: Option<String>,
+
+    /// Parse PATH at this git revision for the new functions
+    #[arg(long, value_name = "REV", requires = "git_old")]
+    git_new: Option<String>,
+
+    /// Output format: `json`, or `text` for a summary and the functions
+    /// changed
+    #[arg(short, long, value_enum, default_value = "json")]
+    pub(crate) format: DiffFormat,
+
+    /// Write JSON minified, on one line; the default unless stdout is a
+    /// terminal
+    #[arg(long, conflicts_with = "pretty")]
+    pub(crate) compact: bool,
+
+    /// Write JSON indented even when piped or written to a file
+    #[arg(long)]
+    pub(crate) pretty: bool,
+
+    /// Write the output to FILE instead of stdout, in place only once it is
+    /// complete
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    pub(crate) output_file: Option<PathBuf>,
+}
+
+/// A function only in one of the outputs
+#[derive(Debug, Serialize)]
+struct Listed {
+    qualified_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    start_line: usize,
+}
+
+impl Listed {
+    fn of(func: &FunctionInfo) -> Self {
+        Listed {
+            qualified_name: func.qualified_name.clone(),
+            kind: func.kind.clone(),
+            file: func.file.clone(),
+            start_line: func.start_line,
+        }
+    }
+}
+
+/// A function in another file, or under another qualified name after its
+/// module moved
+#[derive(Debug, Serialize)]
+struct Moved {
+    qualified_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_qualified_name: Option<String>,
+    old_file: Option<String>,
+    new_file: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Change {
+    field: &'static str,
+    old: Value,
+    new: Value,
+}
+
+/// A function whose kind, signature or specs changed
+#[derive(Debug, Serialize)]
+struct Modified {
+    qualified_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    changes: Vec<Change>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Summary {
+    added: usize,
+    removed: usize,
+    moved: usize,
+    modified: usize,
+    /// Functions added by kind, with kinds in both outputs
+    added_by_kind: BTreeMap<String, usize>,
+    removed_by_kind: BTreeMap<String, usize>,
+    /// Exec functions that had specs and have none now, with
+    /// `--show-specs` in both outputs
+    lost_specs: usize,
+    gained_specs: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Diff {
+    schema_version: u32,
+    summary: Summary,
+    added: Vec<Listed>,
+    removed: Vec<Listed>,
+    moved: Vec<Moved>,
+    modified: Vec<Modified>,
+}
+
+/// The kind, signature and specs of `new` that differ from `old`'s; the
+/// signature and specs only count when both outputs have them
+fn changes(old: &FunctionInfo, new: &FunctionInfo) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if old.kind != new.kind {
+        changes.push(Change {
+            field: "kind",
+            old: old.kind.clone().into(),
+            new: new.kind.clone().into(),
+        });
+    }
+    if let (Some(before), Some(after)) = (&old.signature, &new.signature) {
+        if before != after {
+            changes.push(Change {
+                field: "signature",
+                old: before.as_str().into(),
+                new: after.as_str().into(),
+            });
+        }
+    }
+    if let (Some(before), Some(after)) = (old.specified, new.specified) {
+        if before != after {
+            changes.push(Change {
+                field: "specified",
+                old: before.into(),
+                new: after.into(),
+            });
+        }
+    }
+    changes
+}
+
+/// Pair the old functions with the new ones, as described in the module
+/// docs; the rest are removed and added
+fn pair(
+    old: &[FunctionInfo],
+    new: &[FunctionInfo],
+    renames: &HashMap<String, String>,
+) -> (Vec<(usize, usize)>, Vec<usize>, Vec<usize>) {
+    let mut by_name: HashMap<&str, VecDeque<usize>> = HashMap::new();
+    for (idx, func) in new.iter().enumerate() {
+        by_name
+            .entry(&func.qualified_name)
+            .or_default()
+            .push_back(idx);
+    }
+    let mut pairs = Vec::new();
+    let mut removed = Vec::new();
+    for (idx, func) in old.iter().enumerate() {
+        match by_name
+            .get_mut(func.qualified_name.as_str())
+            .and_then(VecDeque::pop_front)
+        {
+            Some(new_idx) => pairs.push((idx, new_idx)),
+            None => removed.push(idx),
+        }
+    }
+    let mut added: Vec<usize> = by_name.into_values().flatten().collect();
+    added.sort();
+
+    // Renamed files, whose module may be renamed too
+    let local = |func: &FunctionInfo| {
+        func.qualified_name
+            .strip_prefix(&func.module_path)
+            .and_then(|rest| rest.strip_prefix("::"))
+            .unwrap_or(&func.qualified_name)
+            .to_string()
+    };
+    let mut by_file: HashMap<(&str, String), VecDeque<usize>> = HashMap::new();
+    for &idx in &added {
+        if let Some(ref file) = new[idx].file {
+            by_file
+                .entry((file, local(&new[idx])))
+                .or_default()
+                .push_back(idx);
+        }
+    }
+    let mut renamed = HashSet::new();
+    removed.retain(|&idx| {
+        let func = &old[idx];
+        let Some(to) = func.file.as_ref().and_then(|file| renames.get(file)) else {
+            return true;
+        };
+        match by_file
+            .get_mut(&(to.as_str(), local(func)))
+            .and_then(VecDeque::pop_front)
+        {
+            Some(new_idx) => {
+                pairs.push((idx, new_idx));
+                renamed.insert(new_idx);
+                false
+            }
+            None => true,
+        }
+    });
+    added.retain(|idx| !renamed.contains(idx));
+
+    // Moves between modules, when the match is unambiguous
+    let key = |func: &FunctionInfo| (func.name.clone(), func.kind.clone(), func.signature.clone());
+    let mut candidates: HashMap<_, (Vec<usize>, Vec<usize>)> = HashMap::new();
+    for &idx in &removed {
+        candidates.entry(key(&old[idx])).or_default().0.push(idx);
+    }
+    for &idx in &added {
+        candidates.entry(key(&new[idx])).or_default().1.push(idx);
+    }
+    let mut paired_old = HashSet::new();
+    let mut paired_new = HashSet::new();
+    for (olds, news) in candidates.into_values() {
+        if let ([old_idx], [new_idx]) = (olds.as_slice(), news.as_slice()) {
+            pairs.push((*old_idx, *new_idx));
+            paired_old.insert(*old_idx);
+            paired_new.insert(*new_idx);
+        }
+    }
+    removed.retain(|idx| !paired_old.contains(idx));
+    added.retain(|idx| !paired_new.contains(idx));
+    pairs.sort();
+    (pairs, removed, added)
+}
+
+impl Diff {
+    fn of(old: &[FunctionInfo], new: &[FunctionInfo], renames: &HashMap<String, String>) -> Self {
+        let (pairs, removed, added) = pair(old, new, renames);
+        let with_kinds = old.iter().chain(new).all(|f| f.kind.is_some());
+        let by_kind = |functions: &[FunctionInfo], indices: &[usize]| {
+            let mut by_kind = BTreeMap::new();
+            if with_kinds {
+                for &idx in indices {
+                    let kind = functions[idx]
+                        .kind
+                        .clone()
+                        .expect("every function has a kind");
+                    *by_kind.entry(kind).or_insert(0) += 1;
+                }
+            }
+            by_kind
+        };
+
+        let mut summary = Summary {
+            added: added.len(),
+            removed: removed.len(),
+            added_by_kind: by_kind(new, &added),
+            removed_by_kind: by_kind(old, &removed),
+            ..Summary::default()
+        };
+        let mut moved = Vec::new();
+        let mut modified = Vec::new();
+        for (old_idx, new_idx) in pairs {
+            let (before, after) = (&old[old_idx], &new[new_idx]);
+            if before.qualified_name != after.qualified_name || before.file != after.file {
+                moved.push(Moved {
+                    qualified_name: after.qualified_name.clone(),
+                    old_qualified_name: (before.qualified_name != after.qualified_name)
+                        .then(|| before.qualified_name.clone()),
+                    old_file: before.file.clone(),
+                    new_file: after.file.clone(),
+                });
+            }
+            let changes = changes(before, after);
+            if changes.is_empty() {
+                continue;
+            }
+            match (before.specified, after.specified) {
+                (Some(true), Some(false)) => summary.lost_specs += 1,
+                (Some(false), Some(true)) => summary.gained_specs += 1,
+                _ => {}
+            }
+            modified.push(Modified {
+                qualified_name: after.qualified_name.clone(),
+                file: after.file.clone(),
+                changes,
+            });
+        }
+        summary.moved = moved.len();
+        summary.modified = modified.len();
+
+        Diff {
+            schema_version: SCHEMA_VERSION,
+            summary,
+            added: added.iter().map(|&idx| Listed::of(&new[idx])).collect(),
+            removed: removed.iter().map(|&idx| Listed::of(&old[idx])).collect(),
+            moved,
+            modified,
+        }
+    }
+
+    /// The summary, then the functions under a heading per change
+    pub fn render(&self) -> String {
+        let summary = &self.summary;
+        let mut lines = vec![format!(
+            "{} added, {} removed, {} moved, {} modified",
+            summary.added, summary.removed, summary.moved, summary.modified
+        )];
+        for (kind, &count) in &summary.added_by_kind {
+            lines.push(format!("{} new {}", count, plural(kind, count)));
+        }
+        for (kind, &count) in &summary.removed_by_kind {
+            lines.push(format!("{} {} removed", count, plural(kind, count)));
+        }
+        if summary.lost_specs > 0 {
+            lines.push(format!(
+                "{} exec {} lost {} specs",
+                summary.lost_specs,
+                plural("fn", summary.lost_specs),
+                if summary.lost_specs == 1 {
+                    "its"
+                } else {
+                    "their"
+                }
+            ));
+        }
+        if summary.gained_specs > 0 {
+            lines.push(format!(
+                "{} exec {} gained specs",
+                summary.gained_specs,
+                plural("fn", summary.gained_specs)
+            ));
+        }
+
+        let listed = |func: &Listed| {
+            let mut line = func.qualified_name.clone();
+            if let Some(ref kind) = func.kind {
+                line.push_str(&format!(" [{}]", kind));
+            }
+            if let Some(ref file) = func.file {
+                line.push_str(&format!(" {}:{}", file, func.start_line));
+            }
+            line
+        };
+        if !self.added.is_empty() {
+            lines.push(String::new());
+            lines.push("Added:".to_string());
+            lines.extend(self.added.iter().map(|f| format!("+ {}", listed(f))));
+        }
+        if !self.removed.is_empty() {
+            lines.push(String::new());
+            lines.push("Removed:".to_string());
+            lines.extend(self.removed.iter().map(|f| format!("- {}", listed(f))));
+        }
+        if !self.moved.is_empty() {
+            lines.push(String::new());
+            lines.push("Moved:".to_string());
+            for func in &self.moved {
+                let name = match func.old_qualified_name {
+                    Some(ref old) => format!("{} -> {}", old, func.qualified_name),
+                    None => func.qualified_name.clone(),
+                };
+                lines.push(format!(
+                    "> {}: {} -> {}",
+                    name,
+                    func.old_file.as_deref().unwrap_or("?"),
+                    func.new_file.as_deref().unwrap_or("?")
+                ));
+            }
+        }
+        if !self.modified.is_empty() {
+            lines.push(String::new());
+            lines.push("Modified:".to_string());
+            for func in &self.modified {
+                lines.push(format!("~ {}", func.qualified_name));
+                for change in &func.changes {
+                    lines.push(format!(
+                        "    {} was: {}",
+                        change.field,
+                        display(&change.old)
+                    ));
+                    lines.push(format!(
+                        "    {} now: {}",
+                        change.field,
+                        display(&change.new)
+                    ));
+                }
+            }
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+/// `count` of `noun`, e.g. `proof fns`
+fn plural(noun: &str, count: usize) -> String {
+    if count == 1 {
+        noun.to_string()
+    } else {
+        format!("{}s", noun)
+    }
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "none".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// The differences between the saved outputs `args` names
+pub fn compare(args: &DiffArgs) -> Result<Diff, String> {
+    match (&args.git_old, &args.git_new, args.inputs.as_slice()) {
+        (Some(old_rev), Some(new_rev), [path]) => {
+            // What a run with `--show-kind --show-signature` lists, with
+            // whether exec functions have specs
+            let options = ExtractOptions {
+                include_verus_constructs: true,
+                include_methods: true,
+                show_kind: true,
+                show_signature: true,
+                show_spec_status: true,
+                ..Default::default()
+            };
+            let revisions = git::revisions(path, old_rev, new_rev, &options)?;
+            Ok(Diff::of(&revisions.old, &revisions.new, &revisions.renames))
+        }
+        (None, None, [old, new]) => {
+            let old = query::load(old)?;
+            let new = query::load(new)?;
+            Ok(Diff::of(&old.functions, &new.functions, &HashMap::new()))
+        }
+        (Some(_), _, _) => Err("--git-old and --git-new take a single PATH".to_string()),
+        (None, _, _) => Err("diff takes the OLD and NEW files to compare".to_string()),
+    }
+}
@@ -0,0 +1,102 @@
+//! GraphViz output for the call graph and the module dependency graph
+//!
+//! Function nodes are labeled by qualified name and filled by mode (spec,
+//! proof, exec). Callees that did not resolve to a parsed function are drawn
+//! as dashed "external" boxes so missing dependencies stand out.
+
+use crate::callgraph::CallGraph;
+use crate::FunctionInfo;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Fill color for a function mode (`spec fn`, `proof fn`, `exec fn`, ...)
+fn kind_color(kind: Option<&str>) -> &'static str {
+    match kind {
+        Some(k) if k.starts_with("spec") => "lightblue",
+        Some(k) if k.starts_with("proof") => "palegreen",
+        Some("group" | "external" | "external_spec" | "macro_def") => "lightgray",
+        Some(_) => "lightgoldenrod",
+        None => "white",
+    }
+}
+
+/// Quote a string as a DOT identifier
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Render the subgraph induced by `indices`, plus external nodes for the
+/// unresolved callees of those functions
+pub fn render(functions: &[FunctionInfo], graph: &CallGraph, indices: &[usize]) -> String {
+    let included: BTreeSet<usize> = indices.iter().copied().collect();
+    let mut out = String::new();
+
+    writeln!(out, "digraph calls {{").unwrap();
+    writeln!(out, "    rankdir=LR;").unwrap();
+    writeln!(out, "    node [shape=ellipse, style=filled];").unwrap();
+
+    let mut nodes = BTreeSet::new();
+    for &idx in &included {
+        let func = &functions[idx];
+        let line = format!(
+            "    {} [label={}, fillcolor={}];",
+            quote(&func.qualified_name),
+            quote(&func.qualified_name),
+            kind_color(func.mode())
+        );
+        nodes.insert(line);
+    }
+    for node in &nodes {
+        writeln!(out, "{}", node).unwrap();
+    }
+
+    let unresolved = graph.unresolved_callees(indices);
+    let externals: BTreeSet<&str> = unresolved.iter().map(|u| u.callee.as_str()).collect();
+    for external in &externals {
+        writeln!(
+            out,
+            "    {} [label={}, shape=box, style=dashed];",
+            quote(&format!("external:{}", external)),
+            quote(external)
+        )
+        .unwrap();
+    }
+
+    let mut edges = BTreeSet::new();
+    for &idx in &included {
+        let caller = &functions[idx].qualified_name;
+        for &callee in graph.callees(idx) {
+            if included.contains(&callee) {
+                edges.insert((caller.clone(), functions[callee].qualified_name.clone()));
+            }
+        }
+    }
+    for u in &unresolved {
+        edges.insert((u.caller.clone(), format!("external:{}", u.callee)));
+    }
+    for (caller, callee) in &edges {
+        writeln!(out, "    {} -> {};", quote(caller), quote(callee)).unwrap();
+    }
+
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+/// Render module dependencies as returned by `CallGraph::module_dependencies`
+pub fn render_modules(deps: &BTreeMap<String, BTreeSet<String>>) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "digraph modules {{").unwrap();
+    writeln!(out, "    rankdir=LR;").unwrap();
+    writeln!(out, "    node [shape=box];").unwrap();
+    for module in deps.keys() {
+        writeln!(out, "    {};", quote(module)).unwrap();
+    }
+    for (from, targets) in deps {
+        for to in targets {
+            writeln!(out, "    {} -> {};", quote(from), quote(to)).unwrap();
+        }
+    }
+    writeln!(out, "}}").unwrap();
+    out
+}
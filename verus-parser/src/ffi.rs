@@ -0,0 +1,49 @@
+//! The FFI boundary: foreign ABIs and exported symbols
+//!
+//! A function crosses the boundary when its signature names an ABI
+//! (`extern "C" fn`), when it is declared in an `extern "C" { .. }` block,
+//! or when it is exported under a fixed symbol with `#[no_mangle]` or
+//! `#[export_name = ".."]` (also in the `#[unsafe(no_mangle)]` form).
+
+use verus_syn::{Abi, Attribute, Expr, ExprLit, Lit, Meta};
+
+/// The ABI string of `extern "C"`; a bare `extern` is `C`
+pub fn abi(abi: &Abi) -> String {
+    abi.name
+        .as_ref()
+        .map_or_else(|| "C".to_string(), |name| name.value())
+}
+
+/// The attributes themselves, looking inside `#[unsafe(..)]`
+fn metas(attrs: &[Attribute]) -> Vec<Meta> {
+    let mut metas = Vec::new();
+    for attr in attrs {
+        match &attr.meta {
+            Meta::List(list) if list.path.is_ident("unsafe") => {
+                metas.extend(list.parse_args::<Meta>());
+            }
+            meta => metas.push(meta.clone()),
+        }
+    }
+    metas
+}
+
+/// Whether the function is `#[no_mangle]`
+pub fn no_mangle(attrs: &[Attribute]) -> bool {
+    metas(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("no_mangle")))
+}
+
+/// The symbol of `#[export_name = ".."]`
+pub fn export_name(attrs: &[Attribute]) -> Option<String> {
+    metas(attrs).into_iter().find_map(|meta| match meta {
+        Meta::NameValue(nv) if nv.path.is_ident("export_name") => match nv.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(s), ..
+            }) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
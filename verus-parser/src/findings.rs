@@ -0,0 +1,136 @@
+//! Findings of the report modes, shared by `--format sarif` and
+//! `--format github`
+//!
+//! A finding is a rule reported for one function, with a message and the
+//! function's file and line span. Each rule has a fixed level: `error` for
+//! what Verus rejects, `warning` for gaps in what is verified, `note` for
+//! what is worth a look.
+
+use crate::FunctionInfo;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+}
+
+/// What a finding reports
+pub struct Rule {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub level: Level,
+}
+
+pub const UNSPECIFIED: Rule = Rule {
+    id: "unspecified-exec-fn",
+    description: "Exec function without requires, ensures or returns",
+    level: Level::Warning,
+};
+
+pub const ASSUME: Rule = Rule {
+    id: "assume-or-admit",
+    description: "Function body relying on assume or admit()",
+    level: Level::Warning,
+};
+
+pub const TERMINATION_UNCHECKED: Rule = Rule {
+    id: "termination-unchecked",
+    description: "Exec function whose termination is not proven",
+    level: Level::Warning,
+};
+
+pub const MISSING_DECREASES: Rule = Rule {
+    id: "missing-decreases",
+    description: "Recursive function without a decreases clause",
+    level: Level::Error,
+};
+
+pub const UNUSED_LEMMA: Rule = Rule {
+    id: "unused-lemma",
+    description: "Proof function not reachable from any exec function",
+    level: Level::Note,
+};
+
+pub const NEVER_REVEALED: Rule = Rule {
+    id: "opaque-never-revealed",
+    description: "Opaque function that no function reveals",
+    level: Level::Warning,
+};
+
+pub const HIGH_RLIMIT: Rule = Rule {
+    id: "high-rlimit",
+    description: "Function verified with a raised rlimit",
+    level: Level::Note,
+};
+
+pub const DEAD_FUNCTION: Rule = Rule {
+    id: "dead-function",
+    description: "Function not reachable from any root",
+    level: Level::Warning,
+};
+
+pub const TRUSTED: Rule = Rule {
+    id: "trusted-function",
+    description: "Function trusted rather than verified",
+    level: Level::Note,
+};
+
+/// The rules of the default mode, see [`function_findings`]
+pub const FUNCTION_RULES: [&Rule; 3] = [&UNSPECIFIED, &ASSUME, &TERMINATION_UNCHECKED];
+
+/// A rule reported for one function
+pub struct Finding {
+    pub rule: &'static Rule,
+    pub message: String,
+    pub qualified_name: String,
+    pub file: Option<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl Finding {
+    pub fn new(rule: &'static Rule, func: &FunctionInfo, message: String) -> Self {
+        Finding {
+            rule,
+            message,
+            qualified_name: func.qualified_name.clone(),
+            file: func.file.clone(),
+            start_line: func.start_line,
+            end_line: func.end_line,
+        }
+    }
+}
+
+/// The findings of the listed functions: exec functions without a spec
+/// (leaving out `external_body` and `external` ones), `assume`s and
+/// `admit()`s, and unchecked termination
+pub fn function_findings(functions: &[FunctionInfo]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for func in functions {
+        let name = &func.qualified_name;
+        if func.specified == Some(false) && !func.is_intentionally_unspecified() {
+            let message = format!("`{}` has no requires, ensures or returns clause", name);
+            findings.push(Finding::new(&UNSPECIFIED, func, message));
+        }
+        if func.proof_debt().unwrap_or(0) > 0 {
+            let message = format!(
+                "`{}` contains {} assume and {} admit()",
+                name,
+                func.assume_count.unwrap_or(0),
+                func.admit_count.unwrap_or(0)
+            );
+            findings.push(Finding::new(&ASSUME, func, message));
+        }
+        if func.termination_unchecked {
+            let message = format!(
+                "`{}` is covered by exec_allows_no_decreases_clause, so its termination is not proven",
+                name
+            );
+            findings.push(Finding::new(&TERMINATION_UNCHECKED, func, message));
+        }
+    }
+    findings
+}
@@ -0,0 +1,195 @@
+//! Ghost and tracked parameters and return values of a signature
+//!
+//! A parameter is ghost when its type is `Ghost<T>` and tracked when its
+//! type is `Tracked<T>` (possibly behind `&`/`&mut`) or it is declared in
+//! `tracked x: T` mode. Exec functions taking these need ghost arguments at
+//! every call site.
+//!
+//! Exec functions also use the wrappers anywhere in their parameter and
+//! return types (`Vec<Tracked<PointsTo<T>>>`), in `let ghost` and
+//! `let tracked` bindings, or through type aliases of the crate. Aliases are
+//! matched by their simple name, which is all a signature names.
+
+use crate::pretty::pretty;
+use std::collections::{BTreeMap, BTreeSet};
+use verus_syn::visit::Visit;
+use verus_syn::{
+    Block, Fields, FnArgKind, Item, Local, Pat, ReturnType, Signature, Type, TypePath,
+};
+
+/// The wrapper (`Ghost` or `Tracked`) of a type, looking through references
+fn wrapper(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Reference(r) => wrapper(&r.elem),
+        Type::Paren(p) => wrapper(&p.elem),
+        Type::Path(p) if p.qself.is_none() => {
+            let last = p.path.segments.last()?.ident.to_string();
+            matches!(last.as_str(), "Ghost" | "Tracked").then_some(last)
+        }
+        _ => None,
+    }
+}
+
+/// The ghost and the tracked parameters of a signature, by name (the
+/// pattern as written for destructuring parameters like `Tracked(perm)`)
+pub fn params(sig: &Signature) -> (Vec<String>, Vec<String>) {
+    let mut ghost = Vec::new();
+    let mut tracked = Vec::new();
+    for arg in &sig.inputs {
+        let (name, ty) = match &arg.kind {
+            FnArgKind::Receiver(r) => ("self".to_string(), &*r.ty),
+            FnArgKind::Typed(t) => (pretty(&*t.pat), &*t.ty),
+        };
+        match wrapper(ty).as_deref() {
+            _ if arg.tracked.is_some() => tracked.push(name),
+            Some("Tracked") => tracked.push(name),
+            Some(_) => ghost.push(name),
+            None => {}
+        }
+    }
+    (ghost, tracked)
+}
+
+/// The fields of type `Ghost<T>` or `Tracked<T>`, by name (position for
+/// tuple fields), each preceded by `prefix`
+pub fn fields(fields: &Fields, prefix: &str) -> Vec<String> {
+    fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| wrapper(&field.ty).is_some())
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => format!("{}{}", prefix, ident),
+            None => format!("{}{}", prefix, i),
+        })
+        .collect()
+}
+
+/// Whether the return value is ghost: `Ghost<T>`, `Tracked<T>` or declared
+/// `-> (tracked r: T)`
+pub fn returns_ghost(sig: &Signature) -> bool {
+    match &sig.output {
+        ReturnType::Default => false,
+        ReturnType::Type(_, tracked, _, ty) => tracked.is_some() || wrapper(ty).is_some(),
+    }
+}
+
+/// Names of the types a type is built from, at any depth: `Vec`,
+/// `Tracked` and `PointsTo` for `Vec<Tracked<PointsTo<T>>>`; the last
+/// segment of each path
+pub fn type_names(ty: &Type) -> BTreeSet<String> {
+    let mut visitor = TypeNameVisitor::default();
+    visitor.visit_type(ty);
+    visitor.names
+}
+
+/// Names of the types of a signature's parameters and return value
+pub fn signature_type_names(sig: &Signature) -> BTreeSet<String> {
+    let mut visitor = TypeNameVisitor::default();
+    for arg in &sig.inputs {
+        match &arg.kind {
+            FnArgKind::Receiver(r) => visitor.visit_type(&r.ty),
+            FnArgKind::Typed(t) => visitor.visit_type(&t.ty),
+        }
+    }
+    if let ReturnType::Type(_, _, _, ty) = &sig.output {
+        visitor.visit_type(ty);
+    }
+    visitor.names
+}
+
+/// Whether the names include a wrapper
+pub fn names_wrapper(names: &BTreeSet<String>) -> bool {
+    names.contains("Ghost") || names.contains("Tracked")
+}
+
+/// The aliases whose type uses a wrapper, directly or through other
+/// aliases, given the type names of each alias
+pub fn wrapping_aliases(aliases: &BTreeMap<String, BTreeSet<String>>) -> BTreeSet<String> {
+    let mut wrapping: BTreeSet<String> = BTreeSet::new();
+    loop {
+        let found: Vec<&String> = aliases
+            .iter()
+            .filter(|(name, names)| {
+                !wrapping.contains(*name)
+                    && (names_wrapper(names) || names.iter().any(|n| wrapping.contains(n)))
+            })
+            .map(|(name, _)| name)
+            .collect();
+        if found.is_empty() {
+            return wrapping;
+        }
+        wrapping.extend(found.into_iter().cloned());
+    }
+}
+
+#[derive(Default)]
+struct TypeNameVisitor {
+    names: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for TypeNameVisitor {
+    fn visit_type_path(&mut self, node: &'ast TypePath) {
+        if let Some(last) = node.path.segments.last() {
+            self.names.insert(last.ident.to_string());
+        }
+        verus_syn::visit::visit_type_path(self, node);
+    }
+}
+
+/// The ghost and tracked local bindings of a body
+#[derive(Default)]
+pub struct Locals {
+    /// `let ghost x` and `let x: Ghost<T>` bindings
+    pub ghost: usize,
+    /// `let tracked x` and `let x: Tracked<T>` bindings
+    pub tracked: usize,
+    /// Names of the types the bindings are declared with
+    pub type_names: BTreeSet<String>,
+}
+
+/// The ghost and tracked bindings of a body, closures included; those of
+/// nested items belong to those items
+pub fn locals(body: &Block) -> Locals {
+    let mut visitor = LocalVisitor::default();
+    visitor.visit_block(body);
+    visitor.locals
+}
+
+#[derive(Default)]
+struct LocalVisitor {
+    locals: Locals,
+}
+
+impl<'ast> Visit<'ast> for LocalVisitor {
+    fn visit_item(&mut self, _node: &'ast Item) {
+        // Nested items are reported as functions of their own
+    }
+
+    fn visit_local(&mut self, node: &'ast Local) {
+        let declared = match &node.pat {
+            Pat::Type(pt) => {
+                let mut names = TypeNameVisitor::default();
+                names.visit_type(&pt.ty);
+                self.locals.type_names.extend(names.names);
+                wrapper(&pt.ty)
+            }
+            _ => None,
+        };
+        if node.tracked.is_some() || declared.as_deref() == Some("Tracked") {
+            self.locals.tracked += 1;
+        } else if node.ghost.is_some() || declared.is_some() {
+            self.locals.ghost += 1;
+        }
+        verus_syn::visit::visit_local(self, node);
+    }
+}
+
+/// Whether the return value is `Tracked<T>` or declared tracked
+pub fn returns_tracked(sig: &Signature) -> bool {
+    match &sig.output {
+        ReturnType::Default => false,
+        ReturnType::Type(_, tracked, _, ty) => {
+            tracked.is_some() || wrapper(ty).as_deref() == Some("Tracked")
+        }
+    }
+}
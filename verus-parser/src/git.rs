@@ -0,0 +1,386 @@
+//! `diff --git-old REV --git-new REV PATH`: the functions of PATH at two
+//! revisions, read from the git objects rather than from checkouts
+//!
+//! The `.rs` files and manifests of each revision are written to a scratch
+//! directory, so that modules, `include!`s and targets resolve as they do
+//! in a working tree, but only the files whose functions can differ are
+//! parsed for them: those git reports as changed (renames included, with
+//! `-M`), those whose module changed with another file's `mod`
+//! declarations, and those using `include!`. Every other file has the same
+//! functions at both revisions.
+
+use crate::{file_module, modules, parse_file, ExtractOptions, FunctionInfo, SortKey};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::thread;
+
+/// The functions of the files that differ between two revisions
+pub struct Revisions {
+    pub old: Vec<FunctionInfo>,
+    pub new: Vec<FunctionInfo>,
+    /// The files git found renamed, old name to new, as the functions'
+    /// `file` gives them
+    pub renames: HashMap<String, String>,
+}
+
+/// Run git in `dir`, returning what it prints
+fn git(dir: &Path, args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// The one line git prints, without its newline
+fn git_line(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = git(dir, args)?;
+    Ok(String::from_utf8_lossy(&output)
+        .trim_end_matches('\n')
+        .to_string())
+}
+
+/// The commit `rev` names
+fn resolve(repo: &Path, rev: &str) -> Result<String, String> {
+    let commit = format!("{}^{{commit}}", rev);
+    git_line(
+        repo,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            "--end-of-options",
+            &commit,
+        ],
+    )
+    .map_err(|_| format!("{} is not a git revision of {}", rev, repo.display()))
+}
+
+/// The git pathspec of the directory at `prefix` from the repository root
+fn pathspec(prefix: &str) -> &str {
+    if prefix.is_empty() {
+        "."
+    } else {
+        prefix
+    }
+}
+
+/// Whether a file is needed to extract functions: sources, and manifests
+/// for the targets
+fn is_needed(path: &str) -> bool {
+    path.ends_with(".rs") || path == "Cargo.toml" || path.ends_with("/Cargo.toml")
+}
+
+/// The needed files under `prefix` at `rev`, by path from the repository
+/// root, with their blob ids
+fn tree(repo: &Path, rev: &str, prefix: &str) -> Result<BTreeMap<String, String>, String> {
+    let listing = git(repo, &["ls-tree", "-r", "-z", rev, "--", pathspec(prefix)])?;
+    let mut files = BTreeMap::new();
+    for entry in listing.split(|&b| b == 0).filter(|e| !e.is_empty()) {
+        // `<mode> <type> <oid>\t<path>`
+        let entry = String::from_utf8_lossy(entry);
+        let Some((meta, path)) = entry.split_once('\t') else {
+            continue;
+        };
+        let mut meta = meta.split(' ');
+        let (Some(mode), Some("blob"), Some(oid)) = (meta.next(), meta.next(), meta.next()) else {
+            continue;
+        };
+        // The blob of a symlink holds its target
+        if mode != "120000" && is_needed(path) {
+            files.insert(path.to_string(), oid.to_string());
+        }
+    }
+    Ok(files)
+}
+
+/// What git reports as differing between two revisions
+#[derive(Default)]
+struct Changes {
+    /// Paths at the old revision that were modified, deleted or renamed
+    old: HashSet<String>,
+    /// Paths at the new revision that were modified, added or renamed to
+    new: HashSet<String>,
+    renames: Vec<(String, String)>,
+}
+
+fn changes(repo: &Path, old: &str, new: &str, prefix: &str) -> Result<Changes, String> {
+    let output = git(
+        repo,
+        &[
+            "diff",
+            "-z",
+            "--name-status",
+            "-M",
+            old,
+            new,
+            "--",
+            pathspec(prefix),
+        ],
+    )?;
+    let mut fields = output
+        .split(|&b| b == 0)
+        .map(|field| String::from_utf8_lossy(field).to_string());
+    let mut changes = Changes::default();
+    let truncated = || "Unexpected git diff output".to_string();
+    while let Some(status) = fields.next() {
+        match status.chars().next() {
+            None => {}
+            // `R<score>`, then the old and new paths
+            Some('R') => {
+                let from = fields.next().ok_or_else(truncated)?;
+                let to = fields.next().ok_or_else(truncated)?;
+                changes.old.insert(from.clone());
+                changes.new.insert(to.clone());
+                changes.renames.push((from, to));
+            }
+            Some('A') => {
+                changes.new.insert(fields.next().ok_or_else(truncated)?);
+            }
+            Some('D') => {
+                changes.old.insert(fields.next().ok_or_else(truncated)?);
+            }
+            Some(_) => {
+                let path = fields.next().ok_or_else(truncated)?;
+                changes.old.insert(path.clone());
+                changes.new.insert(path);
+            }
+        }
+    }
+    Ok(changes)
+}
+
+/// The contents of the blobs, read with one `git cat-file --batch`
+fn read_blobs(repo: &Path, oids: &BTreeSet<&str>) -> Result<HashMap<String, Vec<u8>>, String> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let request: String = oids.iter().map(|oid| format!("{}\n", oid)).collect();
+    // Written from another thread, so that git is never blocked on a full
+    // pipe while this one is
+    let writer = thread::spawn(move || stdin.write_all(request.as_bytes()));
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let failed = |e: std::io::Error| format!("Failed to read from git cat-file: {}", e);
+
+    let mut blobs = HashMap::new();
+    for oid in oids {
+        // `<oid> blob <size>`, the content, and a newline
+        let mut header = String::new();
+        stdout.read_line(&mut header).map_err(failed)?;
+        let size: usize = header
+            .trim_end()
+            .rsplit(' ')
+            .next()
+            .and_then(|size| size.parse().ok())
+            .ok_or_else(|| format!("Unexpected git cat-file output: {}", header.trim_end()))?;
+        let mut content = vec![0; size + 1];
+        stdout.read_exact(&mut content).map_err(failed)?;
+        content.pop();
+        blobs.insert(oid.to_string(), content);
+    }
+    writer
+        .join()
+        .expect("the writer does not panic")
+        .map_err(|e| format!("Failed to write to git cat-file: {}", e))?;
+    child
+        .wait()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    Ok(blobs)
+}
+
+/// A directory removed with everything in it when dropped
+struct Scratch(PathBuf);
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// One revision, written out under `dir`
+struct Side {
+    files: BTreeMap<String, String>,
+    dir: PathBuf,
+    /// `dir` joined with the prefix, standing for PATH
+    root: PathBuf,
+    module_map: modules::ModuleMap,
+}
+
+impl Side {
+    fn write(
+        dir: PathBuf,
+        prefix: &str,
+        files: BTreeMap<String, String>,
+        blobs: &HashMap<String, Vec<u8>>,
+    ) -> Result<Self, String> {
+        let mut sources = Vec::new();
+        for (path, oid) in &files {
+            let file = dir.join(path);
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            fs::write(&file, &blobs[oid])
+                .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+            if path.ends_with(".rs") {
+                sources.push(file);
+            }
+        }
+        let module_map = modules::build(&modules::crate_roots(&sources));
+        Ok(Side {
+            files,
+            root: dir.join(prefix),
+            dir,
+            module_map,
+        })
+    }
+
+    fn module(&self, path: &str) -> modules::FileModule {
+        file_module(&self.module_map, &self.dir.join(path), &self.root).0
+    }
+
+    /// The name PATH gives the file at `path` from the repository root
+    fn file_name(&self, path: &Path, base: &Path) -> String {
+        match path.strip_prefix(&self.root) {
+            Ok(relative) => base.join(relative).to_string_lossy().to_string(),
+            Err(_) => path.to_string_lossy().to_string(),
+        }
+    }
+
+    /// The functions of the files at `paths`, named as under `base`
+    fn functions(
+        &self,
+        paths: &[String],
+        base: &Path,
+        options: &ExtractOptions,
+    ) -> Vec<FunctionInfo> {
+        let root = self.root.to_string_lossy().to_string();
+        let parsed: Vec<_> = paths
+            .iter()
+            .filter_map(|path| {
+                let file = self.dir.join(path);
+                let (module, _) = file_module(&self.module_map, &file, &self.root);
+                match parse_file(&file, module, options) {
+                    Ok(parsed) => Some((file, parsed)),
+                    Err(e) => {
+                        let base = base.to_string_lossy();
+                        eprintln!("Warning: {}", e.replace(&root, &base));
+                        None
+                    }
+                }
+            })
+            .collect();
+        // Files pulled in with `include!` belong to the including module
+        let included: HashSet<PathBuf> = parsed
+            .iter()
+            .flat_map(|(_, parsed)| &parsed.included_files)
+            .map(|path| modules::canonical(path))
+            .collect();
+
+        let mut functions: Vec<FunctionInfo> = parsed
+            .into_iter()
+            .filter(|(file, _)| !included.contains(&modules::canonical(file)))
+            .flat_map(|(_, parsed)| parsed.functions)
+            .collect();
+        for func in &mut functions {
+            if let Some(ref file) = func.file {
+                func.file = Some(self.file_name(Path::new(file), base));
+            }
+        }
+        functions.sort_by(|a, b| SortKey::File.compare(a, b));
+        functions
+    }
+
+    /// Whether the file at `path` uses `include!`
+    fn includes(&self, path: &str) -> bool {
+        fs::read_to_string(self.dir.join(path)).is_ok_and(|text| text.contains("include!"))
+    }
+}
+
+/// The functions of `path` that can differ between revisions `old_rev`
+/// and `new_rev` of its repository
+pub fn revisions(
+    path: &Path,
+    old_rev: &str,
+    new_rev: &str,
+    options: &ExtractOptions,
+) -> Result<Revisions, String> {
+    if !path.is_dir() {
+        return Err(format!("{} is not a directory", path.display()));
+    }
+    let repo = git_line(path, &["rev-parse", "--show-toplevel"])
+        .map(PathBuf::from)
+        .map_err(|_| format!("{} is not in a git repository", path.display()))?;
+    let prefix = git_line(path, &["rev-parse", "--show-prefix"])?;
+    let old_rev = resolve(&repo, old_rev)?;
+    let new_rev = resolve(&repo, new_rev)?;
+    let old_files = tree(&repo, &old_rev, &prefix)?;
+    let new_files = tree(&repo, &new_rev, &prefix)?;
+    let changes = changes(&repo, &old_rev, &new_rev, &prefix)?;
+    let oids: BTreeSet<&str> = old_files
+        .values()
+        .chain(new_files.values())
+        .map(String::as_str)
+        .collect();
+    let blobs = read_blobs(&repo, &oids)?;
+
+    let scratch = Scratch(env::temp_dir().join(format!("verus-parser-diff-{}", process::id())));
+    // Left over by a run of the same id that did not finish
+    let _ = fs::remove_dir_all(&scratch.0);
+    let old = Side::write(scratch.0.join("old"), &prefix, old_files, &blobs)?;
+    let new = Side::write(scratch.0.join("new"), &prefix, new_files, &blobs)?;
+
+    // A file in both revisions unchanged, in the same module, without
+    // `include!`, has the same functions in both
+    let differs = |side: &Side, other: &Side, changed: &HashSet<String>, path: &str| {
+        changed.contains(path)
+            || !other.files.contains_key(path)
+            || side.module(path) != other.module(path)
+            || side.includes(path)
+    };
+    let sources = |side: &Side, other: &Side, changed: &HashSet<String>| -> Vec<String> {
+        side.files
+            .keys()
+            .filter(|path| path.ends_with(".rs") && differs(side, other, changed, path))
+            .cloned()
+            .collect()
+    };
+    let old_paths = sources(&old, &new, &changes.old);
+    let new_paths = sources(&new, &old, &changes.new);
+
+    let renames = changes
+        .renames
+        .iter()
+        .filter(|(from, to)| from.ends_with(".rs") && to.ends_with(".rs"))
+        .map(|(from, to)| {
+            (
+                old.file_name(&old.dir.join(from), path),
+                new.file_name(&new.dir.join(to), path),
+            )
+        })
+        .collect();
+    Ok(Revisions {
+        old: old.functions(&old_paths, path, options),
+        new: new.functions(&new_paths, path, options),
+        renames,
+    })
+}
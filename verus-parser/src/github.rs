@@ -0,0 +1,95 @@
+//! GitHub Actions annotations of the findings of a report
+//!
+//! Each finding is printed as an `::error`, `::warning` or `::notice`
+//! workflow command, after its level, with the function's file and line
+//! span, which GitHub shows inline on the changed lines of a pull request.
+//! The paths must be relative to the repository root, so absolute ones
+//! under `$GITHUB_WORKSPACE` are made relative to it.
+
+use crate::findings::{Finding, Level};
+use crate::FunctionInfo;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Escape the message of a workflow command
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property of a workflow command, where `:` and `,` separate
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// The path of `file` relative to the checkout, when it is inside it
+fn workspace_path(file: &str) -> String {
+    match std::env::var("GITHUB_WORKSPACE") {
+        Ok(workspace) if !workspace.is_empty() => Path::new(file)
+            .strip_prefix(&workspace)
+            .map_or_else(|_| file.to_string(), |p| p.display().to_string()),
+        _ => file.to_string(),
+    }
+}
+
+/// One workflow command annotating the lines of a function
+fn annotation(
+    level: Level,
+    title: &str,
+    func: (Option<&str>, usize, usize),
+    message: &str,
+) -> String {
+    let command = match level {
+        Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note => "notice",
+    };
+    let mut line = format!("::{} ", command);
+    let (file, start_line, end_line) = func;
+    if let Some(file) = file {
+        write!(
+            line,
+            "file={},line={},endLine={},",
+            escape_property(&workspace_path(file)),
+            start_line,
+            end_line
+        )
+        .unwrap();
+    }
+    write!(
+        line,
+        "title={}::{}",
+        escape_property(title),
+        escape_data(message)
+    )
+    .unwrap();
+    line
+}
+
+/// An annotation per finding, titled with its rule
+pub fn render(findings: &[Finding]) -> String {
+    let mut out = String::new();
+    for finding in findings {
+        let location = (
+            finding.file.as_deref(),
+            finding.start_line,
+            finding.end_line,
+        );
+        let line = annotation(
+            finding.rule.level,
+            finding.rule.id,
+            location,
+            &finding.message,
+        );
+        writeln!(out, "{}", line).unwrap();
+    }
+    out
+}
+
+/// A notice for a function listed by a report without findings, titled
+/// with the report
+pub fn notice(report: &str, func: &FunctionInfo, message: &str) -> String {
+    let location = (func.file.as_deref(), func.start_line, func.end_line);
+    annotation(Level::Note, report, location, message)
+}
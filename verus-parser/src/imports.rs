@@ -0,0 +1,269 @@
+//! `use` declarations per module, used to turn callee paths as written
+//! (`mul`, `field::mul`, `constants::EDWARDS_D`) into fully-qualified names
+//!
+//! Imports are collected while visiting each file and callees are resolved
+//! once every file has been parsed, since resolving glob imports needs the
+//! complete set of known functions.
+
+use crate::body::Callee;
+use crate::FunctionInfo;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use verus_syn::UseTree;
+
+/// Imports of one module
+#[derive(Debug, Default, Clone)]
+pub struct ModuleImports {
+    /// Name brought into scope -> the path it refers to
+    names: HashMap<String, ImportPath>,
+    /// Modules whose contents are glob-imported (`use super::*`)
+    globs: Vec<ImportPath>,
+}
+
+/// Imports by module path (`backend::serial::u64::field`, empty for the
+/// crate root)
+pub type ImportTable = HashMap<String, ModuleImports>;
+
+#[derive(Debug, Clone)]
+struct ImportPath {
+    segments: Vec<String>,
+    /// Whether `segments` is relative to the crate root (`crate::`, `self::`
+    /// and `super::` paths); otherwise the path may name a child module or
+    /// an item of another crate
+    absolute: bool,
+}
+
+impl ImportPath {
+    /// Normalize a path used in module `module`, making `crate::`, `self::`
+    /// and `super::` paths absolute
+    fn new(segments: Vec<String>, module: &[String]) -> Self {
+        let mut base: Vec<String> = module.to_vec();
+        let mut absolute = false;
+        let mut rest = segments.as_slice();
+
+        while let Some((first, tail)) = rest.split_first() {
+            match first.as_str() {
+                "crate" if !absolute => {
+                    base.clear();
+                    absolute = true;
+                }
+                "self" if !absolute => absolute = true,
+                "super" => {
+                    base.pop();
+                    absolute = true;
+                }
+                _ => break,
+            }
+            rest = tail;
+        }
+
+        if absolute {
+            base.extend(rest.iter().cloned());
+            ImportPath {
+                segments: base,
+                absolute,
+            }
+        } else {
+            ImportPath {
+                segments: rest.to_vec(),
+                absolute,
+            }
+        }
+    }
+
+    /// Absolute paths this may refer to when used in `module`, most local first
+    fn candidates(&self, module: &[String]) -> Vec<Vec<String>> {
+        if self.absolute {
+            vec![self.segments.clone()]
+        } else {
+            let mut local = module.to_vec();
+            local.extend(self.segments.iter().cloned());
+            vec![local, self.segments.clone()]
+        }
+    }
+}
+
+impl ModuleImports {
+    /// Record a `use` tree declared in `module`
+    pub fn add_use(&mut self, tree: &UseTree, module: &[String]) {
+        self.add_tree(tree, Vec::new(), module);
+    }
+
+    fn add_tree(&mut self, tree: &UseTree, mut prefix: Vec<String>, module: &[String]) {
+        match tree {
+            UseTree::Path(p) => {
+                prefix.push(p.ident.to_string());
+                self.add_tree(&p.tree, prefix, module);
+            }
+            UseTree::Name(n) => {
+                let name = n.ident.to_string();
+                if name == "self" {
+                    // `use a::b::{self}` imports `b`
+                    if let Some(last) = prefix.last().cloned() {
+                        self.names.insert(last, ImportPath::new(prefix, module));
+                    }
+                } else {
+                    prefix.push(name.clone());
+                    self.names.insert(name, ImportPath::new(prefix, module));
+                }
+            }
+            UseTree::Rename(r) => {
+                let rename = r.rename.to_string();
+                if rename != "_" {
+                    if r.ident != "self" {
+                        prefix.push(r.ident.to_string());
+                    }
+                    self.names.insert(rename, ImportPath::new(prefix, module));
+                }
+            }
+            UseTree::Glob(_) => self.globs.push(ImportPath::new(prefix, module)),
+            UseTree::Group(g) => {
+                for item in &g.items {
+                    self.add_tree(item, prefix.clone(), module);
+                }
+            }
+        }
+    }
+
+    fn merge(&mut self, other: ModuleImports) {
+        self.names.extend(other.names);
+        self.globs.extend(other.globs);
+    }
+}
+
+/// Merge the imports of another file into `table`
+pub fn merge_tables(table: &mut ImportTable, other: ImportTable) {
+    for (module, imports) in other {
+        table.entry(module).or_default().merge(imports);
+    }
+}
+
+/// Rewrite every function's callees, spec dependencies and revealed
+/// functions, and the members of broadcast groups, to fully-qualified names using the imports of its
+/// module. Paths that only a glob import can explain, and
+/// that several globs explain, are left as written and marked ambiguous.
+pub fn resolve_callees(functions: &mut [FunctionInfo], table: &ImportTable) {
+    let known = known_paths(functions);
+    let empty = ModuleImports::default();
+
+    for func in functions.iter_mut() {
+        let module: Vec<String> = if func.module_path.is_empty() {
+            Vec::new()
+        } else {
+            func.module_path.split("::").map(str::to_string).collect()
+        };
+        let imports = table.get(&func.module_path).unwrap_or(&empty);
+
+        let resolve_all = |callees: Vec<Callee>| -> Vec<Callee> {
+            let resolved: BTreeSet<_> = callees
+                .into_iter()
+                .map(|mut callee| {
+                    if !callee.ambiguous {
+                        let (path, ambiguous) =
+                            resolve_path(&callee.path, &module, imports, &known);
+                        callee.path = path;
+                        callee.ambiguous = ambiguous;
+                    }
+                    callee
+                })
+                .collect();
+            resolved.into_iter().collect()
+        };
+        func.callees = func.callees.take().map(resolve_all);
+        func.spec_deps = func.spec_deps.take().map(resolve_all);
+        let members = func.group_members.iter_mut().flatten();
+        for path in members.chain(func.reveals.iter_mut().flatten()) {
+            *path = resolve_path(path, &module, imports, &known).0;
+        }
+    }
+}
+
+/// Resolve paths used outside function bodies (`broadcast use`), each
+/// paired with the path of the module it appears in
+pub fn resolve_paths<'a>(
+    paths: impl Iterator<Item = (&'a str, &'a mut String)>,
+    functions: &[FunctionInfo],
+    table: &ImportTable,
+) {
+    let known = known_paths(functions);
+    let empty = ModuleImports::default();
+    for (module_path, path) in paths {
+        let module: Vec<String> = if module_path.is_empty() {
+            Vec::new()
+        } else {
+            module_path.split("::").map(str::to_string).collect()
+        };
+        let imports = table.get(module_path).unwrap_or(&empty);
+        *path = resolve_path(path, &module, imports, &known).0;
+    }
+}
+
+/// The paths naming the functions: their qualified names, and those of
+/// trait impl methods through their self type
+fn known_paths(functions: &[FunctionInfo]) -> HashSet<String> {
+    functions
+        .iter()
+        .flat_map(|f| std::iter::once(f.qualified_name.clone()).chain(f.self_type_path()))
+        .collect()
+}
+
+/// Resolve one path used in `module`: returns the fully-qualified path (or
+/// the path as written when it cannot be resolved) and whether it is an
+/// ambiguous glob import
+fn resolve_path(
+    path: &str,
+    module: &[String],
+    imports: &ModuleImports,
+    known: &HashSet<String>,
+) -> (String, bool) {
+    let segments: Vec<String> = path.split("::").map(str::to_string).collect();
+    let is_known = |candidate: &[String]| known.contains(&candidate.join("::"));
+
+    if matches!(segments[0].as_str(), "crate" | "self" | "super") {
+        return (ImportPath::new(segments, module).segments.join("::"), false);
+    }
+
+    // The first segment names an item imported by name
+    if let Some(import) = imports.names.get(&segments[0]) {
+        let candidates: Vec<Vec<String>> = import
+            .candidates(module)
+            .into_iter()
+            .map(|mut c| {
+                c.extend(segments[1..].iter().cloned());
+                c
+            })
+            .collect();
+        let chosen = candidates
+            .iter()
+            .find(|c| is_known(c))
+            .unwrap_or(candidates.last().unwrap());
+        return (chosen.join("::"), false);
+    }
+
+    // Defined in this module (or a child module), or relative to the crate root
+    let local = ImportPath {
+        segments: segments.clone(),
+        absolute: false,
+    };
+    if let Some(found) = local.candidates(module).into_iter().find(|c| is_known(c)) {
+        return (found.join("::"), false);
+    }
+
+    // Brought in by a glob import
+    let mut matches: Vec<Vec<String>> = imports
+        .globs
+        .iter()
+        .flat_map(|glob| glob.candidates(module))
+        .map(|mut c| {
+            c.extend(segments.iter().cloned());
+            c
+        })
+        .filter(|c| is_known(c))
+        .collect();
+    matches.sort();
+    matches.dedup();
+    match matches.len() {
+        0 => (path.to_string(), false),
+        1 => (matches[0].join("::"), false),
+        _ => (path.to_string(), true),
+    }
+}
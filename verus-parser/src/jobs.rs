@@ -0,0 +1,128 @@
+//! `plan`: verification jobs for a CI matrix, grouped by estimated cost
+//!
+//! A function's cost is its length in lines, plus 2 per `assert`, 10 per
+//! quantifier and 25 per `by (nonlinear_arith)`, scaled up by its rlimit
+//! over the default of 10 (`infinity` counting as 100). Functions are
+//! packed into jobs of at most `--job-cost` in the strategy's order; a
+//! function costing more has a job of its own.
+
+use crate::verus_args::verus_args;
+use crate::FunctionInfo;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::cmp::Reverse;
+
+/// Verus's default rlimit
+const DEFAULT_RLIMIT: f64 = 10.0;
+
+/// How functions are grouped into jobs (`--strategy`)
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Strategy {
+    /// Unspecified functions first, then the others, cheapest first, for
+    /// quick feedback
+    CheapFirst,
+    /// A job of its own for each function using `nonlinear_arith` or
+    /// costing more than `--job-cost`, the dearest first, then the other
+    /// functions by file, so that jobs cover few modules
+    IsolateHeavy,
+}
+
+/// The estimated cost of verifying `func`
+pub fn cost(func: &FunctionInfo) -> usize {
+    let lines = func.end_line + 1 - func.start_line;
+    let nonlinear = nonlinear_arith(func);
+    let base = lines
+        + 2 * func.assert_count.unwrap_or(0)
+        + 10 * func.quantifier_count.unwrap_or(0)
+        + 25 * nonlinear;
+    let rlimit = match func.rlimit.as_deref() {
+        Some("infinity") => 10.0 * DEFAULT_RLIMIT,
+        Some(n) => n.parse().unwrap_or(DEFAULT_RLIMIT),
+        None => DEFAULT_RLIMIT,
+    };
+    (base as f64 * (rlimit / DEFAULT_RLIMIT).max(1.0)).ceil() as usize
+}
+
+fn nonlinear_arith(func: &FunctionInfo) -> usize {
+    func.provers
+        .as_ref()
+        .and_then(|p| p.get("nonlinear_arith"))
+        .copied()
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobFunction {
+    pub qualified_name: String,
+    pub cost: usize,
+}
+
+/// A job: functions to verify together and the Verus arguments selecting
+/// them
+#[derive(Debug, Serialize)]
+pub struct Job {
+    pub job: usize,
+    pub cost: usize,
+    pub functions: Vec<JobFunction>,
+    pub verus_args: Vec<String>,
+}
+
+/// Group `functions`, given by file and line, into jobs
+pub fn plan(functions: &[&FunctionInfo], strategy: Strategy, job_cost: usize) -> Vec<Job> {
+    let costed: Vec<(&FunctionInfo, usize)> = functions.iter().map(|&f| (f, cost(f))).collect();
+    let mut groups: Vec<Vec<(&FunctionInfo, usize)>> = Vec::new();
+    match strategy {
+        Strategy::CheapFirst => {
+            let mut ordered = costed;
+            ordered.sort_by_key(|&(f, cost)| (f.specified != Some(false), cost));
+            pack(&mut groups, ordered, job_cost);
+        }
+        Strategy::IsolateHeavy => {
+            let (mut heavy, rest): (Vec<_>, Vec<_>) = costed
+                .into_iter()
+                .partition(|&(f, cost)| nonlinear_arith(f) > 0 || cost > job_cost);
+            heavy.sort_by_key(|&(_, cost)| Reverse(cost));
+            groups.extend(heavy.into_iter().map(|entry| vec![entry]));
+            pack(&mut groups, rest, job_cost);
+        }
+    }
+
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(job, group)| Job {
+            job,
+            cost: group.iter().map(|&(_, cost)| cost).sum(),
+            verus_args: verus_args(group.iter().map(|&(f, _)| f)),
+            functions: group
+                .iter()
+                .map(|&(f, cost)| JobFunction {
+                    qualified_name: f.qualified_name.clone(),
+                    cost,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Fill jobs in order, starting a new one when the next function would
+/// take the current one over `job_cost`
+fn pack<'a>(
+    groups: &mut Vec<Vec<(&'a FunctionInfo, usize)>>,
+    entries: Vec<(&'a FunctionInfo, usize)>,
+    job_cost: usize,
+) {
+    let mut current: Vec<(&FunctionInfo, usize)> = Vec::new();
+    let mut total = 0;
+    for (func, cost) in entries {
+        if !current.is_empty() && total + cost > job_cost {
+            groups.push(std::mem::take(&mut current));
+            total = 0;
+        }
+        current.push((func, cost));
+        total += cost;
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+}
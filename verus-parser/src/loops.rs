@@ -0,0 +1,128 @@
+//! Loops of a function body and the invariants attached to them
+//!
+//! `while`, `loop` and `for` loops are collected wherever they appear in
+//! the body, including inside `proof { ... }` blocks; loops of nested items
+//! belong to those items.
+
+use crate::body::verifier_attr_arg;
+use crate::specs::clauses;
+use serde::{Deserialize, Serialize};
+use verus_syn::spanned::Spanned;
+use verus_syn::visit::Visit;
+use verus_syn::{Attribute, Block, ExprForLoop, ExprLoop, ExprWhile, Item};
+
+/// The setting of a `#[verifier::loop_isolation(false)]` (or
+/// `#[verifier(loop_isolation(false))]`) attribute, if present
+pub fn isolation(attrs: &[Attribute]) -> Option<bool> {
+    match verifier_attr_arg(attrs, "loop_isolation")?.as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// A loop and its specification clauses, pretty-printed one per clause
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoopInfo {
+    /// `while`, `loop` or `for`
+    pub kind: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub invariant: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub invariant_except_break: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub invariant_ensures: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ensures: Vec<String>,
+}
+
+impl LoopInfo {
+    pub fn has_invariants(&self) -> bool {
+        !self.invariant.is_empty()
+            || !self.invariant_except_break.is_empty()
+            || !self.invariant_ensures.is_empty()
+    }
+}
+
+/// How many of a function's loops carry invariants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoopSummary {
+    pub with_invariants: usize,
+    pub without_invariants: usize,
+}
+
+impl LoopSummary {
+    pub fn of(loops: &[LoopInfo]) -> Self {
+        let with_invariants = loops.iter().filter(|l| l.has_invariants()).count();
+        LoopSummary {
+            with_invariants,
+            without_invariants: loops.len() - with_invariants,
+        }
+    }
+}
+
+/// Every loop in a function body, in source order
+pub fn loops(body: Option<&Block>) -> Vec<LoopInfo> {
+    let mut visitor = LoopVisitor { loops: Vec::new() };
+    if let Some(block) = body {
+        visitor.visit_block(block);
+    }
+    visitor.loops
+}
+
+struct LoopVisitor {
+    loops: Vec<LoopInfo>,
+}
+
+/// A loop without clauses
+fn bare(kind: &str, span: proc_macro2::Span) -> LoopInfo {
+    LoopInfo {
+        kind: kind.to_string(),
+        start_line: span.start().line,
+        end_line: span.end().line,
+        invariant: Vec::new(),
+        invariant_except_break: Vec::new(),
+        invariant_ensures: Vec::new(),
+        ensures: Vec::new(),
+    }
+}
+
+impl<'ast> Visit<'ast> for LoopVisitor {
+    fn visit_item(&mut self, _node: &'ast Item) {
+        // Nested items are reported as functions of their own
+    }
+
+    fn visit_expr_while(&mut self, node: &'ast ExprWhile) {
+        self.loops.push(LoopInfo {
+            invariant: clauses(node.invariant.as_ref().map(|i| &i.exprs)),
+            invariant_except_break: clauses(node.invariant_except_break.as_ref().map(|i| &i.exprs)),
+            invariant_ensures: clauses(node.invariant_ensures.as_ref().map(|i| &i.exprs)),
+            ensures: clauses(node.ensures.as_ref().map(|e| &e.exprs)),
+            ..bare("while", node.span())
+        });
+        verus_syn::visit::visit_expr_while(self, node);
+    }
+
+    fn visit_expr_loop(&mut self, node: &'ast ExprLoop) {
+        self.loops.push(LoopInfo {
+            invariant: clauses(node.invariant.as_ref().map(|i| &i.exprs)),
+            invariant_except_break: clauses(node.invariant_except_break.as_ref().map(|i| &i.exprs)),
+            invariant_ensures: clauses(node.invariant_ensures.as_ref().map(|i| &i.exprs)),
+            ensures: clauses(node.ensures.as_ref().map(|e| &e.exprs)),
+            ..bare("loop", node.span())
+        });
+        verus_syn::visit::visit_expr_loop(self, node);
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast ExprForLoop) {
+        self.loops.push(LoopInfo {
+            invariant: clauses(node.invariant.as_ref().map(|i| &i.exprs)),
+            invariant_except_break: clauses(node.invariant_except_break.as_ref().map(|i| &i.exprs)),
+            ensures: clauses(node.ensures.as_ref().map(|e| &e.exprs)),
+            ..bare("for", node.span())
+        });
+        verus_syn::visit::visit_expr_for_loop(self, node);
+    }
+}
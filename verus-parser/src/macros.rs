@@ -0,0 +1,107 @@
+//! `macro_rules!` definitions and the macros a function invokes
+//!
+//! Macros are inventoried without expanding them: a definition is counted
+//! by its rules (`matcher => transcriber`), and an invocation is any
+//! `name!` in the body, including those among the tokens of another macro.
+//! Nested items belong to those items.
+
+use proc_macro2::{Spacing, TokenStream, TokenTree};
+use std::collections::BTreeSet;
+use verus_syn::visit::Visit;
+use verus_syn::{Block, Item, Macro};
+
+/// Number of rules in the body of a `macro_rules!` definition
+pub fn rule_count(tokens: TokenStream) -> usize {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    tokens
+        .windows(2)
+        .filter(|pair| match pair {
+            [TokenTree::Punct(eq), TokenTree::Punct(gt)] => {
+                eq.as_char() == '=' && eq.spacing() == Spacing::Joint && gt.as_char() == '>'
+            }
+            _ => false,
+        })
+        .count()
+}
+
+/// Number of functions a block macro like `proptest!` defines: the `fn`s
+/// at the top level of its tokens
+pub fn fn_count(tokens: TokenStream) -> usize {
+    tokens
+        .into_iter()
+        .filter(|token| matches!(token, TokenTree::Ident(ident) if ident == "fn"))
+        .count()
+}
+
+/// The bodies of the `verus! { .. }` invocations among the tokens of
+/// another macro, at any depth; those nested in a `verus!` are left to its
+/// body
+pub fn verus_blocks(tokens: TokenStream) -> Vec<TokenStream> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match (&tokens[i], tokens.get(i + 1), tokens.get(i + 2)) {
+            (
+                TokenTree::Ident(ident),
+                Some(TokenTree::Punct(bang)),
+                Some(TokenTree::Group(body)),
+            ) if ident == "verus" && bang.as_char() == '!' => {
+                blocks.push(body.stream());
+                i += 3;
+                continue;
+            }
+            (TokenTree::Group(group), _, _) => blocks.extend(verus_blocks(group.stream())),
+            _ => {}
+        }
+        i += 1;
+    }
+    blocks
+}
+
+/// Names of the macros invoked in a function body, without the path
+pub fn invoked(body: &Block) -> BTreeSet<String> {
+    let mut visitor = MacroVisitor::default();
+    visitor.visit_block(body);
+    visitor.names
+}
+
+/// Record the name of every `name!` among `tokens`
+fn invoked_in(tokens: TokenStream, names: &mut BTreeSet<String>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) => {
+                if matches!(tokens.get(i + 1), Some(TokenTree::Punct(p)) if p.as_char() == '!') {
+                    names.insert(ident.to_string());
+                }
+            }
+            TokenTree::Group(group) => invoked_in(group.stream(), names),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct MacroVisitor {
+    names: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for MacroVisitor {
+    fn visit_item(&mut self, node: &'ast Item) {
+        // Nested items are reported as functions of their own, but a macro
+        // invoked in item position is still part of the body
+        if let Item::Macro(m) = node {
+            if m.ident.is_none() {
+                self.visit_macro(&m.mac);
+            }
+        }
+    }
+
+    fn visit_macro(&mut self, node: &'ast Macro) {
+        if let Some(last) = node.path.segments.last() {
+            self.names.insert(last.ident.to_string());
+        }
+        invoked_in(node.tokens.clone(), &mut self.names);
+    }
+}
@@ -3,10 +3,52 @@
 //! This tool parses Verus/Rust code to extract function information,
 //! replacing the adhoc regex-based approach in find_verus_functions.py
 
-use clap::{Parser, ValueEnum};
+mod baseline;
+mod body;
+mod callgraph;
+mod cfg;
+mod ctags;
+mod diff;
+mod dot;
+mod ffi;
+mod findings;
+mod ghost;
+mod git;
+mod github;
+mod imports;
+mod jobs;
+mod loops;
+mod macros;
+mod markdown;
+mod modules;
+mod mutation;
+#[macro_use]
+mod output;
+mod plan;
+mod pretty;
+mod proofs;
+mod quantifiers;
+mod query;
+mod reports;
+mod sarif;
+mod shards;
+mod shell;
+mod signature;
+mod specs;
+mod sqlite;
+mod state_machines;
+mod stats;
+mod targets;
+mod verus_args;
+
+use callgraph::{CallGraph, UnresolvedCallee};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use imports::ImportTable;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
+use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
 use verus_syn::spanned::Spanned;
 use verus_syn::visit::Visit;
@@ -15,6 +57,376 @@ use walkdir::WalkDir;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    /// Print the JSON Schema of the `--format json` output and exit.
+    ///
+    /// The output carries a `schema_version`, bumped whenever its shape
+    /// changes: a field added, removed, renamed or given another type.
+    /// Scripts reading the output should check it; `query` refuses files
+    /// of another version.
+    #[arg(long, exclusive = true)]
+    print_schema: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Options for the default mode (listing functions)
+    #[command(flatten)]
+    args: Option<Args>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List a function and every function it transitively calls
+    Closure {
+        /// Function to start from (name, or qualified name such as
+        /// `backend::serial::u64::field::FieldElement51::pow2k`)
+        #[arg(long, required_unless_present_any = ["roots_mode", "roots_file"])]
+        function: Option<String>,
+
+        #[command(flatten)]
+        roots: RootArgs,
+
+        /// Only follow calls from function bodies, not functions referenced
+        /// from `requires`/`ensures`/`decreases` clauses
+        #[arg(long)]
+        no_spec_deps: bool,
+
+        /// Follow at most N calls from the function (0 lists only the
+        /// function itself, 1 adds its direct callees)
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+
+        /// Do not follow calls out of this module (e.g.
+        /// `backend::serial::u64::field_verus`); the functions called
+        /// outside it are listed as `external_deps`
+        #[arg(long, value_name = "MODULE")]
+        within_module: Option<String>,
+
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List every function that calls a function, directly or transitively
+    Callers {
+        /// Function whose callers to list (name or qualified name)
+        #[arg(long)]
+        function: String,
+
+        /// Only list direct callers (same as `--depth 1`)
+        #[arg(long)]
+        direct_only: bool,
+
+        /// List callers at most N calls away (1 for direct callers)
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// Print the call graph: the resolved callees of every function
+    CallGraph {
+        /// Instead, list the strongly connected components (mutually
+        /// recursive groups) with more than one member
+        #[arg(long)]
+        sccs: bool,
+
+        /// Only include the closure of this function (name or qualified name)
+        #[arg(long)]
+        root: Option<String>,
+
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List the modules containing a function's closure, with how many of
+    /// each module's functions are needed
+    ModuleCover {
+        /// Function to start from (name or qualified name)
+        #[arg(long)]
+        function: String,
+
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// Query the JSON output of an earlier run instead of parsing sources
+    Query {
+        #[command(flatten)]
+        args: query::QueryArgs,
+    },
+
+    /// Compare the JSON outputs of two runs: the functions added, removed,
+    /// moved or changed in kind, signature or specs
+    Diff {
+        #[command(flatten)]
+        args: diff::DiffArgs,
+    },
+
+    /// Print which modules call into which other modules
+    ModuleGraph {
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List proof functions that are not reachable from any exec function
+    UnusedLemmas {
+        /// Lemma to leave out of the report (name, qualified name or regex;
+        /// may be repeated)
+        #[arg(long, value_name = "PATTERN")]
+        allow: Vec<String>,
+
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List the trusted functions (`assume`, `admit`, `external_body`,
+    /// `termination_unchecked`) each function transitively depends on
+    Trusted {
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List recursive functions that have no `decreases` clause
+    MissingDecreases {
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List `#[verifier::opaque]` functions with the functions that reveal
+    /// them, flagging those never revealed
+    OpaqueReveals {
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List the functions using quantifiers, those with the most first
+    Quantifiers {
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List the functions using `by (nonlinear_arith)`, those with the most
+    /// first, to isolate them with `--verify-function`
+    NonlinearArith {
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List the functions whose `#[verifier::rlimit(..)]` exceeds a limit,
+    /// the highest first
+    Rlimits {
+        /// Only list rlimits above N (Verus defaults to 10)
+        #[arg(long, value_name = "N", default_value = "10")]
+        above: f64,
+
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// List functions not reachable from any of the given roots, by file
+    DeadFunctions {
+        /// Root function (qualified name or regex; may be repeated)
+        #[arg(
+            long = "root",
+            value_name = "PATTERN",
+            required_unless_present_any = ["roots_mode", "roots_file"]
+        )]
+        roots: Vec<String>,
+
+        #[command(flatten)]
+        root_args: RootArgs,
+
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// Write a shell script or a Makefile running a command per function,
+    /// to verify them one at a time or in parallel with `make -j`
+    EmitPlan {
+        /// Command for each function, e.g. `cargo verus verify --
+        /// {module_args} --verify-function {function}`; `{module}`,
+        /// `{function}`, `{qualified}` and `{file}` are replaced by the
+        /// function's, quoted for the shell, and `{module_args}` by
+        /// `--verify-root` or `--verify-only-module` and the module
+        #[arg(long)]
+        template: String,
+
+        /// Write a shell script or a Makefile
+        #[arg(long, value_enum, default_value = "shell")]
+        style: plan::PlanStyle,
+
+        #[command(flatten)]
+        args: Args,
+    },
+
+    /// Group the functions into verification jobs by estimated cost, each
+    /// with the Verus arguments selecting its functions
+    Plan {
+        /// Order and grouping of the jobs
+        #[arg(long, value_enum, default_value = "cheap-first")]
+        strategy: jobs::Strategy,
+
+        /// Largest estimated cost of a job, unless one function costs more
+        #[arg(long, value_name = "COST", default_value = "500")]
+        job_cost: usize,
+
+        #[command(flatten)]
+        args: Args,
+    },
+}
+
+impl Cli {
+    /// The format, `--output` and JSON layout of the command
+    fn output_target(&self) -> (&OutputFormat, Option<&Path>, JsonLayout) {
+        let args = match (&self.command, &self.args) {
+            (Some(Command::Query { args }), _) => {
+                let layout = JsonLayout {
+                    compact: args.compact,
+                    pretty: args.pretty,
+                };
+                return (&args.format, args.output_file.as_deref(), layout);
+            }
+            (Some(Command::Diff { args }), _) => {
+                let layout = JsonLayout {
+                    compact: args.compact,
+                    pretty: args.pretty,
+                };
+                return (
+                    args.format.output_format(),
+                    args.output_file.as_deref(),
+                    layout,
+                );
+            }
+            (Some(command), _) => command.args(),
+            (None, Some(args)) => args,
+            (None, None) => unreachable!("clap requires PATH without a subcommand"),
+        };
+        let layout = JsonLayout {
+            compact: args.compact,
+            pretty: args.pretty,
+        };
+        (&args.format, args.output_file.as_deref(), layout)
+    }
+
+    /// Whether `--print0` is given, to a command printing names or Verus
+    /// arguments
+    fn print0(&self) -> bool {
+        match (&self.command, &self.args) {
+            (Some(Command::Query { args }), _) => args.print0,
+            (Some(Command::Diff { .. }), _) => false,
+            (Some(command), _) => command.args().print0,
+            (None, Some(args)) => args.print0,
+            (None, None) => false,
+        }
+    }
+}
+
+impl Command {
+    /// The options shared by the commands that parse sources, which all but
+    /// `query` and `diff` do
+    fn args(&self) -> &Args {
+        match self {
+            Command::Closure { args, .. }
+            | Command::Callers { args, .. }
+            | Command::CallGraph { args, .. }
+            | Command::ModuleCover { args, .. }
+            | Command::ModuleGraph { args }
+            | Command::UnusedLemmas { args, .. }
+            | Command::Trusted { args }
+            | Command::MissingDecreases { args }
+            | Command::OpaqueReveals { args }
+            | Command::Quantifiers { args }
+            | Command::NonlinearArith { args }
+            | Command::Rlimits { args, .. }
+            | Command::DeadFunctions { args, .. }
+            | Command::EmitPlan { args, .. }
+            | Command::Plan { args, .. } => args,
+            Command::Query { .. } | Command::Diff { .. } => {
+                unreachable!("query and diff read saved output")
+            }
+        }
+    }
+}
+
+/// Roots of a reachability query besides those named one by one
+#[derive(clap::Args, Debug)]
+struct RootArgs {
+    /// `auto` adds the entry points: `fn main`, `#[no_mangle]` and
+    /// `#[export_name]` functions, and the `pub` functions at the library
+    /// crate root
+    #[arg(long = "roots", value_enum, value_name = "MODE")]
+    roots_mode: Option<RootsMode>,
+
+    /// File of root patterns (qualified names or regexes), one per line;
+    /// blank lines and lines starting with `#` are skipped
+    #[arg(long, value_name = "FILE")]
+    roots_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum RootsMode {
+    Auto,
+}
+
+/// `--compact` and `--pretty`
+#[derive(Debug, Clone, Copy)]
+struct JsonLayout {
+    compact: bool,
+    pretty: bool,
+}
+
+impl JsonLayout {
+    /// Whether to indent JSON, given whether it goes to a file
+    fn pretty(&self, to_file: bool) -> bool {
+        self.pretty || (!self.compact && !to_file && io::stdout().is_terminal())
+    }
+}
+
+impl RootArgs {
+    /// The functions matching `patterns` or the roots file, plus the entry
+    /// points with `--roots auto`, in the order of `functions`
+    fn select(&self, functions: &[FunctionInfo], patterns: &[String]) -> Vec<usize> {
+        let mut patterns = patterns.to_vec();
+        if let Some(ref path) = self.roots_file {
+            let listed = fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("Error: cannot read {}: {}", path.display(), e);
+                output::exit(1);
+            });
+            patterns.extend(
+                listed
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_string),
+            );
+        }
+        let patterns = reports::FunctionPatterns::new(&patterns, "--root").unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            output::exit(1);
+        });
+        let auto = matches!(self.roots_mode, Some(RootsMode::Auto));
+        (0..functions.len())
+            .filter(|&idx| {
+                patterns.matches(&functions[idx])
+                    || (auto && reports::is_entry_point(&functions[idx]))
+            })
+            .collect()
+    }
+
+    /// Extract functions with calls, and with the visibility `--roots auto`
+    /// needs
+    fn extract(&self, args: &Args) -> Extraction {
+        let mut options = ExtractOptions::from_args(args);
+        options.show_calls = true;
+        options.show_visibility |= self.roots_mode.is_some();
+        extract(args, &options)
+    }
+}
+
+#[derive(clap::Args, Debug)]
 struct Args {
     /// Path to search (file or directory)
     #[arg(value_name = "PATH")]
@@ -24,12 +436,29 @@ struct Args {
     #[arg(short, long, value_enum, default_value = "json")]
     format: OutputFormat,
 
+    /// Write JSON minified, on one line; the default unless stdout is a
+    /// terminal
+    #[arg(long, conflicts_with = "pretty")]
+    compact: bool,
+
+    /// Write JSON indented, as it is on a terminal, even when piped or
+    /// written to a file
+    #[arg(long)]
+    pretty: bool,
+
     /// Include Verus-specific constructs (spec, proof, exec functions)
     #[arg(long, default_value = "true")]
     include_verus_constructs: bool,
 
-    /// Include trait and impl method functions
-    #[arg(long, default_value = "true")]
+    /// Include trait and impl method functions (`--include-methods=false` to
+    /// leave them out)
+    #[arg(
+        long,
+        default_value = "true",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        action = clap::ArgAction::Set
+    )]
     include_methods: bool,
 
     /// Show function visibility (pub/private)
@@ -39,96 +468,1668 @@ struct Args {
     /// Show function kind (fn, spec fn, proof fn, exec fn, const fn)
     #[arg(long)]
     show_kind: bool,
+
+    /// Show the functions and methods each function calls
+    #[arg(long)]
+    show_calls: bool,
+
+    /// Show the return type and the full signature text of each function
+    #[arg(long)]
+    show_signature: bool,
+
+    /// With `--show-signature`, leave the spec clauses out of `signature`
+    #[arg(long)]
+    elide_specs: bool,
+
+    /// Show the `requires`, `recommends`, `ensures` and `decreases` clauses
+    /// of each function, the loops of its body with their invariants, its
+    /// `assert`s and quantifiers, and the proof blocks of exec functions
+    #[arg(long)]
+    show_specs: bool,
+
+    /// Only include functions declaring `opens_invariants`
+    #[arg(long)]
+    has_opens_invariants: bool,
+
+    /// Only include trusted functions: `external_body`, with unchecked
+    /// termination, or using `assume` or `admit`
+    #[arg(long)]
+    only_trusted: bool,
+
+    /// Only include exec functions with neither `requires` nor `ensures`,
+    /// leaving out `external_body` and `external` ones; the summary gives
+    /// the share of specified exec functions per module
+    #[arg(long)]
+    unspecified_only: bool,
+
+    /// Only include exec functions without a `no_unwind` clause
+    #[arg(long)]
+    unwinding_allowed: bool,
+
+    /// Only include functions whose loops are verified without isolation:
+    /// `#[verifier::loop_isolation(false)]` on the function or an enclosing
+    /// impl, trait or module
+    #[arg(long)]
+    loop_isolation_disabled: bool,
+
+    /// Include the outer attributes of each function (`#[inline]`,
+    /// `#[verifier::rlimit(20)]`, `#[cfg(..)]`)
+    #[arg(long)]
+    show_attributes: bool,
+
+    /// Include the assertion proved by each `assert ... by` sub-proof
+    #[arg(long)]
+    show_by_asserts: bool,
+
+    /// Also list the trait definitions with their methods, as `traits`
+    #[arg(long)]
+    include_traits: bool,
+
+    /// Also list the `const` and `static` items, associated consts
+    /// included, as `consts`
+    #[arg(long)]
+    include_consts: bool,
+
+    /// Also list the structs, enums and unions, as `types`, and the type
+    /// aliases and associated types, as `type_aliases`
+    #[arg(long)]
+    include_types: bool,
+
+    /// Include the doc comment of each function
+    #[arg(long)]
+    show_docs: bool,
+
+    /// Only include functions with tracked parameters or a tracked return
+    /// value (`Tracked<T>` or `tracked` mode)
+    #[arg(long)]
+    has_tracked: bool,
+
+    /// Only include generic functions: with type or const parameters of
+    /// their own or of the enclosing impl or trait
+    #[arg(long, conflicts_with = "only_monomorphic")]
+    only_generic: bool,
+
+    /// Only include functions that are not generic (see `--only-generic`)
+    #[arg(long)]
+    only_monomorphic: bool,
+
+    /// Leave out `async` functions, whose bodies Verus does not verify
+    #[arg(long)]
+    exclude_async: bool,
+
+    /// Only include the files of one cargo target kind
+    #[arg(long, value_name = "KIND", value_parser = ["lib", "bin", "test", "example", "bench", "build"])]
+    only_target: Option<String>,
+
+    /// Leave out test code: `#[cfg(test)]` modules entirely, `#[test]` and
+    /// `#[bench]` functions and `proptest!` blocks
+    #[arg(long)]
+    exclude_tests: bool,
+
+    /// Only include functions that mutate state: taking a `&mut`
+    /// parameter or receiver, or using `old(..)` in their `ensures`
+    #[arg(long)]
+    only_mutating: bool,
+
+    /// Only include `unsafe` functions
+    #[arg(long)]
+    only_unsafe: bool,
+
+    /// Only include functions on the FFI boundary: with a foreign ABI
+    /// (`extern "C"`, foreign blocks included), `#[no_mangle]` or
+    /// `#[export_name]`
+    #[arg(long)]
+    only_ffi: bool,
+
+    /// Only include functions inside a `verus!` block
+    #[arg(long, conflicts_with = "only_plain")]
+    only_verus: bool,
+
+    /// Only include functions outside any `verus!` block
+    #[arg(long)]
+    only_plain: bool,
+
+    /// Only include functions calling `reveal_with_fuel` with a fuel of at
+    /// least N
+    #[arg(long, value_name = "N")]
+    min_fuel: Option<u64>,
+
+    /// Only include functions with at least N `assert`s in their body
+    #[arg(long, value_name = "N")]
+    min_asserts: Option<usize>,
+
+    /// Only include functions whose `#[cfg]` conditions hold for these
+    /// options: `feature = "u64_backend", target_pointer_width = "64"`
+    #[arg(long, value_name = "OPTIONS", value_parser = cfg::CfgSet::parse)]
+    cfg_filter: Option<cfg::CfgSet>,
+
+    /// Print the item-position macro invocations that were not expanded
+    /// (other than `verus!` and `cfg_if!`) to stderr, since the items they
+    /// generate are missing from the output
+    #[arg(long)]
+    warn_unparsed_macros: bool,
+
+    /// Exit with an error, listing the functions responsible, when the
+    /// bodies contain more than N `assume`s and `admit()`s in total
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "0")]
+    fail_if_assumes: Option<usize>,
+
+    /// Exit with an error, listing them, when more than N exec functions
+    /// are covered by `#[verifier::exec_allows_no_decreases_clause]`
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "0")]
+    fail_if_termination_unchecked: Option<usize>,
+
+    /// Only list the `assume_specification` declarations, no functions
+    #[arg(long)]
+    only_assumed_specs: bool,
+
+    /// Also write a baseline of the functions to FILE: the qualified name,
+    /// kind and signature of each, with `--show-kind` and
+    /// `--show-signature` implied
+    #[arg(long, value_name = "FILE")]
+    write_baseline: Option<PathBuf>,
+
+    /// Exit with an error, printing the differences, when functions were
+    /// added, removed, or changed kind or signature since the baseline FILE
+    /// was written; functions that only moved do not count
+    #[arg(long, value_name = "FILE")]
+    check_baseline: Option<PathBuf>,
+
+    /// Only include functions of this kind (`spec`, `proof fn`, `closed
+    /// spec`, ...), with `--show-kind` implied
+    #[arg(long)]
+    kind: Option<String>,
+
+    /// Print only the number of functions and files, and of functions of
+    /// each kind with `--show-kind`, as a JSON object or a line per count
+    /// with `--format text`; the functions are counted file by file, not
+    /// kept
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "fail_if_assumes",
+            "fail_if_termination_unchecked",
+            "write_baseline",
+            "check_baseline"
+        ]
+    )]
+    count_only: bool,
+
+    /// Print statistics instead of the functions: a histogram and
+    /// percentiles of their sizes in lines, their number by kind, by
+    /// visibility and by module with the largest of each, and with
+    /// `--show-specs` the distributions of assert and quantifier counts
+    #[arg(long, conflicts_with = "count_only")]
+    stats: bool,
+
+    /// Split the functions into N shards of about the same cost and list
+    /// only the one of `--shard-index`; the same sources always give the
+    /// same shards
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "shard_index",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    shard_count: Option<u32>,
+
+    /// Shard to list, from 0 to N - 1
+    #[arg(long, value_name = "I", requires = "shard_count")]
+    shard_index: Option<u32>,
+
+    /// Balance the shards by the functions' length in lines or by their
+    /// number
+    #[arg(long, value_enum, value_name = "COST", default_value = "lines")]
+    shard_by: shards::ShardCost,
+
+    /// With `--format text`, print bare function names instead of qualified
+    /// names, warning about names shared by several functions
+    #[arg(long)]
+    bare_names: bool,
+
+    /// Print `context` as the bare `impl`, `trait` or `standalone` string
+    /// of schema version 1 instead of an object
+    #[arg(long)]
+    context_string: bool,
+
+    /// With `--format text`, terminate each name with a NUL character
+    /// instead of a newline, and with `--format verus-args` each argument
+    /// instead of joining them with spaces (for `xargs -0`); other formats
+    /// are rejected
+    #[arg(long, alias = "null")]
+    print0: bool,
+
+    /// Order the functions by name, file (then line), size in lines or
+    /// kind, ties broken by qualified name; unless given, by file, the
+    /// text format by name, and graph queries and reports in their own
+    /// order
+    #[arg(long, value_enum, value_name = "KEY")]
+    sort: Option<SortKey>,
+
+    /// Reverse the order, e.g. `--sort size --reverse` for the largest
+    /// functions first
+    #[arg(long)]
+    reverse: bool,
+
+    /// Group the functions by module or by file: JSON lists them in
+    /// `functions_by_module` or `functions_by_file` besides `functions`, and
+    /// `--format markdown` makes a table per module (the default) or file
+    #[arg(long, value_enum)]
+    group_by: Option<GroupBy>,
+
+    /// Write the output to FILE instead of stdout, in place only once it is
+    /// complete; with a directory, write the functions of each input file
+    /// to a file of their own under it (in the default mode).
+    ///
+    /// With `--format sqlite`, the database to write, e.g.:
+    ///
+    ///   sqlite3 funcs.db "SELECT file, COUNT(*) FROM functions GROUP BY file"
+    ///   sqlite3 funcs.db "SELECT qualified_name FROM functions WHERE specified = 0"
+    ///   sqlite3 funcs.db "SELECT callee, COUNT(*) FROM calls GROUP BY callee ORDER BY 2 DESC"
+    #[arg(
+        short = 'o',
+        long = "output",
+        value_name = "FILE",
+        required_if_eq("format", "sqlite"),
+        verbatim_doc_comment
+    )]
+    output_file: Option<PathBuf>,
+
+    /// With `--format sqlite`, keep the runs already in the database and add
+    /// this one under the next `run_id`, instead of replacing the tables
+    #[arg(long)]
+    append: bool,
+}
+
+impl Args {
+    fn output(&self) -> OutputOptions {
+        OutputOptions {
+            format: self.format.clone(),
+            bare_names: self.bare_names,
+            print0: self.print0,
+            group_by: self.group_by.clone(),
+            sort: self.sort,
+            reverse: self.reverse,
+            database: self.output_file.clone(),
+            append: self.append,
+            listing: None,
+        }
+    }
+}
+
+/// How functions are printed (`--format`, `--bare-names`, `--print0`,
+/// `--group-by`, `--sort`, `--reverse`, `--output`, `--append`)
+struct OutputOptions {
+    format: OutputFormat,
+    bare_names: bool,
+    print0: bool,
+    group_by: Option<GroupBy>,
+    sort: Option<SortKey>,
+    reverse: bool,
+    database: Option<PathBuf>,
+    append: bool,
+    /// The subcommand listing the functions, whose `--format github` prints
+    /// a notice per function titled with it; `None` for the findings of the
+    /// default run
+    listing: Option<&'static str>,
+}
+
+/// Orders of the functions listed (`--sort`)
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum SortKey {
+    Name,
+    File,
+    /// Lines spanned, `end_line - start_line`
+    Size,
+    Kind,
+}
+
+impl SortKey {
+    /// Compare two functions by the key, then by qualified name; functions
+    /// without a file or kind come last
+    fn compare(self, a: &FunctionInfo, b: &FunctionInfo) -> Ordering {
+        let by_key = match self {
+            SortKey::Name => a.name.cmp(&b.name),
+            SortKey::File => (a.file.is_none(), &a.file, a.start_line).cmp(&(
+                b.file.is_none(),
+                &b.file,
+                b.start_line,
+            )),
+            SortKey::Size => (a.end_line - a.start_line).cmp(&(b.end_line - b.start_line)),
+            SortKey::Kind => (a.kind.is_none(), &a.kind).cmp(&(b.kind.is_none(), &b.kind)),
+        };
+        by_key.then_with(|| a.qualified_name.cmp(&b.qualified_name))
+    }
+}
+
+impl Args {
+    /// The functions of the `--shard-index` shard, or all of them
+    fn shard(&self, functions: Vec<FunctionInfo>) -> Vec<FunctionInfo> {
+        let (Some(count), Some(index)) = (self.shard_count, self.shard_index) else {
+            return functions;
+        };
+        if index >= count {
+            eprintln!(
+                "Error: --shard-index {} is out of range for --shard-count {}",
+                index, count
+            );
+            output::exit(1);
+        }
+        let assigned = shards::assign(&functions, count as usize, self.shard_by);
+        functions
+            .into_iter()
+            .zip(assigned)
+            .filter(|&(_, shard)| shard == index as usize)
+            .map(|(func, _)| func)
+            .collect()
+    }
+
+    /// Reorder the entries of a report by `--sort` and `--reverse`, which
+    /// without `--sort` keeps the report's own order
+    fn sort_entries<'a, T>(&self, entries: &mut [T], function: impl Fn(&T) -> &'a FunctionInfo) {
+        if let Some(key) = self.sort {
+            entries.sort_by(|a, b| key.compare(function(a), function(b)));
+        }
+        if self.reverse {
+            entries.reverse();
+        }
+    }
+}
+
+/// How functions are grouped (`--group-by`)
+#[derive(Debug, Clone, ValueEnum)]
+enum GroupBy {
+    File,
+    Module,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
 enum OutputFormat {
     Json,
+    /// A bare JSON array of the functions, each with its file
+    JsonFlat,
     Text,
+    /// The names of `--format text` on one line, each quoted for a POSIX
+    /// shell when needed
+    Shell,
     Detailed,
+    /// GraphViz digraph of the call graph
+    Dot,
+    /// `--verify-only-module`/`--verify-function` arguments for Verus
+    VerusArgs,
+    /// One JSON object per line: each function as soon as its file is
+    /// parsed, then the summary
+    Ndjson,
+    /// Markdown tables of the functions with their kind, lines and spec
+    /// status, for issues and pull requests
+    Markdown,
+    /// SARIF 2.1.0 log of the findings of a report, for code scanning
+    Sarif,
+    /// GitHub Actions annotations (`::warning file=..,line=..::..`) of the
+    /// findings of a report, shown inline on pull requests: unspecified
+    /// functions, `assume`/`admit()` and unchecked termination by default,
+    /// missing decreases with `missing-decreases`; listings such as
+    /// `closure` and `callers` print a notice per function
+    Github,
+    /// SQLite database of the functions, files, calls and summary (with
+    /// `--output`)
+    Sqlite,
+    /// Exuberant Ctags tags file of the functions (and types, traits and
+    /// consts when included), for editor navigation
+    Ctags,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl OutputFormat {
+    /// Extension of the files `--output DIR` writes
+    fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Json | OutputFormat::JsonFlat => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Text
+            | OutputFormat::Shell
+            | OutputFormat::Detailed
+            | OutputFormat::VerusArgs
+            | OutputFormat::Github => "txt",
+            OutputFormat::Dot => "dot",
+            OutputFormat::Markdown => "md",
+            OutputFormat::Sarif => "sarif",
+            OutputFormat::Sqlite => "db",
+            OutputFormat::Ctags => "tags",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct FunctionInfo {
     name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     file: Option<String>,
     start_line: usize,
     end_line: usize,
+    /// Cargo target of the file: `lib`, `bin`, `test`, `example`, `bench`
+    /// or `build`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     kind: Option<String>,
+    /// `open`, `closed` or `uninterp` for spec functions (with `--show-kind`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spec_openness: Option<String>,
+    /// Return type, with the binder of a named return value (`(r: u64)`);
+    /// absent for unit (with `--show-kind` or `--show-signature`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    return_type: Option<String>,
+    /// The signature on one line, without body or visibility
+    /// (`--show-signature`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    /// Generic parameters, lifetimes and const generics included
+    /// (`<'a, const N: usize, T: Copy>`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generics: Option<String>,
+    /// `where` predicates, joined with `, `
+    #[serde(skip_serializing_if = "Option::is_none")]
+    where_clause: Option<String>,
+    /// Whether the function has type or const parameters, or is in an impl
+    /// or trait that does, so it cannot be verified without knowing them
+    #[serde(default)]
+    is_generic: bool,
+    /// Whether the function is declared `unsafe`
+    #[serde(default)]
+    is_unsafe: bool,
+    /// Whether the function is `async`
+    #[serde(default)]
+    is_async: bool,
+    /// Whether the function has a body: `false` for a trait's required
+    /// methods and for `uninterp spec fn f(..);` declarations
+    #[serde(default)]
+    has_body: bool,
+    /// Whether the function is inside a `verus!` block, rather than plain
+    /// Rust that Verus does not check
+    #[serde(default)]
+    in_verus_macro: bool,
+    /// Foreign ABI of an `extern "C" fn` or of a function declared in an
+    /// `extern "C" { .. }` block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    abi: Option<String>,
+    /// Whether the function is `#[no_mangle]`, exported under its own name
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    no_mangle: bool,
+    /// Symbol of `#[export_name = ".."]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    export_name: Option<String>,
+    /// Whether the function is test code: `#[test]`, `#[bench]` or under
+    /// `#[cfg(test)]`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    is_test: bool,
+    /// Whether a parameter or the receiver is taken by `&mut`
+    #[serde(default)]
+    takes_mut: bool,
+    /// Whether the `ensures` clauses refer to `old(..)` values
+    #[serde(default)]
+    uses_old: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     visibility: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    context: Option<String>, // "impl", "trait", or "standalone"
+    context: Option<FunctionContext>,
+    /// Type and trait of the enclosing impl, or the enclosing trait
+    #[serde(skip_serializing_if = "Option::is_none")]
+    impl_target: Option<ImplTarget>,
+    /// Module containing the function, e.g. `backend::serial::u64::field_verus`
+    /// (empty for the crate root)
+    module_path: String,
+    /// Module path plus the enclosing impl/trait type and the function name;
+    /// for a function nested in another one's body, the enclosing function's
+    /// qualified name plus the function name
+    qualified_name: String,
+    /// Qualified name of the function whose body this one is defined in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_function: Option<String>,
+    /// Name selecting this function with `--verify-function` when verifying
+    /// its module: `pow2k`, `FieldElement51::pow2k`, `<FieldElement51 as Mul>::mul`
+    verify_function_arg: String,
+    /// Functions and methods called from the body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    callees: Option<Vec<body::Callee>>,
+    /// Functions referenced from `requires`/`ensures`/`decreases` clauses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spec_deps: Option<Vec<body::Callee>>,
+    /// Pretty-printed `requires` clauses, one per clause
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requires: Option<Vec<String>>,
+    /// Pretty-printed `recommends` clauses, then `via f` if present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recommends: Option<Vec<String>>,
+    /// Pretty-printed `ensures` clauses, one per clause
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ensures: Option<Vec<String>>,
+    /// Whether the only `ensures` clause is `true`, or an exec function has
+    /// none, so verifying it proves little
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trivial_ensures: Option<bool>,
+    /// Whether an exec function has a `requires`, `ensures` or `returns`
+    /// clause (with `--show-specs` or `--unspecified-only`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    specified: Option<bool>,
+    /// Pretty-printed measures of the `decreases` clause
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decreases: Option<Vec<String>>,
+    /// Condition of `decreases .. when <expr>`, under which the measure
+    /// must decrease
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decreases_when: Option<String>,
+    /// Proof function of `decreases .. via f`, which proves termination and
+    /// is listed among the callees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decreases_via: Option<String>,
+    /// `while`/`loop`/`for` loops in the body, with their invariants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    loops: Option<Vec<loops::LoopInfo>>,
+    /// How many of `loops` have invariants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    loop_summary: Option<loops::LoopSummary>,
+    /// Effective `#[verifier::loop_isolation(..)]` setting, from the
+    /// function or the innermost impl, trait or module declaring one;
+    /// `None` when nothing does, leaving Verus's default (isolated)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    loop_isolation: Option<bool>,
+    /// Number of `proof { ... }` blocks in an exec function's body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof_blocks: Option<usize>,
+    /// Lines spanned by those proof blocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof_lines: Option<usize>,
+    /// Number of `assert`s in the body, including `assert ... by` and
+    /// `assert forall ... by`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assert_count: Option<usize>,
+    /// Number of `forall`/`exists`/`choose` expressions (and
+    /// `assert forall`s) in the signature and body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quantifier_count: Option<usize>,
+    /// Number of explicit `#[trigger]` and `#![trigger ...]` annotations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trigger_count: Option<usize>,
+    /// Number of `assume(..)`s in the body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assume_count: Option<usize>,
+    /// Number of `admit()` calls in the body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    admit_count: Option<usize>,
+    /// `by (bit_vector)`, `by (nonlinear_arith)`, ... annotations on asserts
+    /// and on the function, by prover (with `--show-specs`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provers: Option<BTreeMap<String, usize>>,
+    /// Number of `reveal`/`reveal_with_fuel` statements in the body (with
+    /// `--show-specs`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reveal_count: Option<usize>,
+    /// Highest fuel requested by a `reveal_with_fuel` in the body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_fuel: Option<u64>,
+    /// Number of `calc!` invocations in the body (with `--show-specs`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    calc_count: Option<usize>,
+    /// Line of each of those `calc!`s
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    calc_lines: Vec<usize>,
+    /// Number of `assert ... by { .. }` sub-proofs, nested ones included
+    /// (with `--show-specs`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    by_blocks: Option<usize>,
+    /// Lines spanned by the outermost sub-proofs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    by_block_lines: Option<usize>,
+    /// Assertion proved by each sub-proof (`--show-by-asserts`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    by_asserts: Vec<String>,
+    /// The `opens_invariants` set (`any`, `none`, `[a, b]`, ...), if declared
+    #[serde(skip_serializing_if = "Option::is_none")]
+    opens_invariants: Option<String>,
+    /// `returns` expression, a shorthand for `ensures result == <expr>`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    returns: Option<String>,
+    /// Whether the signature has a `no_unwind` clause
+    #[serde(skip_serializing_if = "Option::is_none")]
+    no_unwind: Option<bool>,
+    /// Condition of `no_unwind when <expr>`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    no_unwind_when: Option<String>,
+    /// Parameters of type `Ghost<T>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ghost_params: Vec<String>,
+    /// Parameters of type `Tracked<T>` or declared `tracked x: T`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tracked_params: Vec<String>,
+    /// Whether the return value is `Ghost<T>`, `Tracked<T>` or `tracked`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    returns_ghost: bool,
+    /// Whether an exec function uses `Ghost<T>` or `Tracked<T>` in its
+    /// parameter or return types, through the crate's type aliases too, or
+    /// binds ghost or tracked locals
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    ghost_wrappers_used: bool,
+    /// `let ghost` (or `let x: Ghost<T>`) bindings of an exec function
+    #[serde(default, skip_serializing_if = "is_zero")]
+    ghost_locals: usize,
+    /// `let tracked` (or `let x: Tracked<T>`) bindings of an exec function
+    #[serde(default, skip_serializing_if = "is_zero")]
+    tracked_locals: usize,
+    /// Names of the types in an exec function's signature and local
+    /// bindings, to look through the crate's type aliases
+    #[serde(skip)]
+    type_names: BTreeSet<String>,
+    /// Calls through closures or function pointers that cannot be named
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    unresolved_calls: Vec<String>,
+    /// Whether the function calls itself, directly or through other functions
+    /// (only computed when calls are extracted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_recursive: Option<bool>,
+    /// Whether the function is exec and `exec_allows_no_decreases_clause`
+    /// applies to it, on the function or an enclosing impl, trait, module
+    /// or crate, so its termination is not proven
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    termination_unchecked: bool,
+    /// Why the function is trusted rather than verified: `external_body`,
+    /// `termination_unchecked`, or `assume`/`admit` in the body (only
+    /// looked for when calls are extracted or with `--only-trusted`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trusted: Vec<String>,
+    /// Number of calls from the queried function (graph queries only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    depth: Option<usize>,
+    /// Outer attributes as written, doc comments left out
+    /// (`--show-attributes`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<String>,
+    /// Doc comment, lines joined with newlines (`--show-docs`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    doc: Option<String>,
+    /// Resource limit of `#[verifier::rlimit(..)]`: a number or `infinity`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rlimit: Option<String>,
+    /// Whether the function is `#[verifier::spinoff_prover]`, verified in a
+    /// separate solver instance
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    spinoff_prover: bool,
+    /// Whether the function is `#[verifier::opaque]`, so its definition is
+    /// only visible where it is revealed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    opaque: bool,
+    /// Functions this one reveals with `reveal`/`reveal_with_fuel` (only
+    /// computed when calls are extracted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reveals: Option<Vec<String>>,
+    /// Foreign type or trait an `external_spec` entry describes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_target: Option<String>,
+    /// Lemmas of a `broadcast group`; set only on the entries for groups,
+    /// which are listed alongside the functions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_members: Option<Vec<String>>,
+    /// Number of rules of a `macro_rules!` definition; set only on the
+    /// `macro_def` entries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_count: Option<usize>,
+    /// `#[cfg]` conditions that must all hold for the function to be
+    /// compiled: its own, those of the enclosing modules, impls and traits
+    /// and those of the `cfg_if!` branch it is defined in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    cfg: Vec<String>,
+    /// Macros defined with `macro_rules!` in the parsed files that the body
+    /// invokes, where expansion may hide asserts and lemma calls
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    local_macros: Vec<String>,
+    /// Role in a `state_machine!`: `invariant` or `inductive(tr)` for the
+    /// functions proving the machine's invariants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_machine_role: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ParsedOutput {
-    functions: Vec<FunctionInfo>,
-    functions_by_file: HashMap<String, Vec<FunctionInfo>>,
-    summary: Summary,
+fn is_zero(n: &usize) -> bool {
+    *n == 0
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Summary {
-    total_functions: usize,
-    total_files: usize,
-}
+impl FunctionInfo {
+    /// The kind without the `broadcast` of `broadcast proof fn`
+    fn mode(&self) -> Option<&str> {
+        let kind = self.kind.as_deref()?;
+        Some(kind.strip_prefix("broadcast ").unwrap_or(kind))
+    }
 
-/// Visitor that collects function information from an AST
-struct FunctionVisitor {
-    functions: Vec<FunctionInfo>,
-    file_path: Option<String>,
-    include_verus_constructs: bool,
-    include_methods: bool,
-    show_visibility: bool,
-    show_kind: bool,
+    fn is_broadcast_group(&self) -> bool {
+        self.group_members.is_some()
+    }
+
+    /// A trait impl method's qualified name through its self type, as
+    /// calls spell it: `field::Fe::mul` for `field::<Fe as Mul>::mul`
+    fn self_type_path(&self) -> Option<String> {
+        let target = self.impl_target.as_ref()?;
+        let (Some(ty), Some(tr)) = (&target.self_type, &target.trait_name) else {
+            return None;
+        };
+        Some(
+            self.qualified_name
+                .replacen(&format!("<{} as {}>", ty, tr), ty, 1),
+        )
+    }
+
+    fn is_macro_def(&self) -> bool {
+        self.rule_count.is_some()
+    }
+
+    /// Transitions of a `state_machine!`, listed with kind `init`,
+    /// `transition`, `readonly` or `property`
+    fn is_transition(&self) -> bool {
+        matches!(
+            self.kind.as_deref(),
+            Some("init" | "transition" | "readonly" | "property")
+        ) && self.context.as_ref().map(FunctionContext::kind) == Some("state_machine")
+    }
+
+    /// Items Verus does not verify: `#[verifier::external]` items and
+    /// external type/trait specifications
+    fn is_external(&self) -> bool {
+        matches!(self.kind.as_deref(), Some("external" | "external_spec"))
+    }
+
+    /// Whether the function is `external_body` or `external`, so
+    /// intentionally without a verified spec
+    fn is_intentionally_unspecified(&self) -> bool {
+        self.kind.as_deref() == Some("external")
+            || self.trusted.iter().any(|t| t == "external_body")
+    }
+
+    /// `assume`s plus `admit()`s in the body, when counted
+    fn proof_debt(&self) -> Option<usize> {
+        Some(self.assume_count? + self.admit_count?)
+    }
+
+    /// Whether the entry is a function Verus verifies, rather than a
+    /// broadcast group, an external item, a macro definition or a
+    /// state machine transition
+    fn is_verified_function(&self) -> bool {
+        !self.is_broadcast_group()
+            && !self.is_external()
+            && !self.is_macro_def()
+            && !self.is_transition()
+    }
+}
+
+/// The impl or trait a method is defined in: `impl Mul for &FieldElement51`
+/// has both a self type and a trait, an inherent impl only a self type and
+/// a trait definition only a trait
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ImplTarget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    self_type: Option<String>,
+    #[serde(rename = "trait", skip_serializing_if = "Option::is_none")]
+    trait_name: Option<String>,
+}
+
+impl ImplTarget {
+    /// Name used to qualify the methods: the self type, or the trait
+    fn type_name(&self) -> &str {
+        self.self_type
+            .as_deref()
+            .or(self.trait_name.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// `--verify-function` name of a method in this impl or trait
+    fn verify_function_arg(&self, method: &str) -> String {
+        match (&self.self_type, &self.trait_name) {
+            (Some(ty), Some(tr)) => format!("<{} as {}>::{}", ty, tr, method),
+            _ => format!("{}::{}", self.type_name(), method),
+        }
+    }
+}
+
+/// Where a function is defined: in an impl, a trait or neither
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum FunctionContext {
+    Structured(ContextInfo),
+    /// `impl`, `trait` or `standalone` alone (`--context-string`)
+    Kind(String),
+}
+
+impl FunctionContext {
+    fn kind(&self) -> &str {
+        match self {
+            FunctionContext::Structured(info) => &info.kind,
+            FunctionContext::Kind(kind) => kind,
+        }
+    }
+}
+
+/// The impl or trait a function is defined in, as written:
+/// `impl<'a> Mul<&'a FieldElement51> for &'a FieldElement51` has the self
+/// type `&'a FieldElement51`, the trait `Mul<&'a FieldElement51>` and the
+/// generics `<'a>`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ContextInfo {
+    /// `impl`, `trait`, `standalone`, `const-block` or `state_machine`
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    self_ty: Option<String>,
+    #[serde(rename = "trait", skip_serializing_if = "Option::is_none")]
+    trait_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generics: Option<String>,
+}
+
+/// A `broadcast use` statement, listed per module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BroadcastUseInfo {
+    /// Lemma or group brought into scope, resolved like a callee
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    line: usize,
+    /// Qualified name of the enclosing function, for uses inside a body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function: Option<String>,
+}
+
+/// `broadcast use` statements keyed by module path (`crate` for the root)
+type BroadcastUses = BTreeMap<String, Vec<BroadcastUseInfo>>;
+
+/// An `assume_specification [path] (...)` item: a spec Verus trusts for a
+/// function it does not verify
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AssumedSpecification {
+    /// Function the spec is for, resolved like a callee
+    /// (`core::option::Option::unwrap`, `<Vec<T> as Clone>::clone`)
+    target: String,
+    /// Module declaring the spec (empty for the crate root)
+    module_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    line: usize,
+    /// Pretty-printed `requires` clauses, one per clause
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    requires: Vec<String>,
+    /// Pretty-printed `ensures` clauses, one per clause
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ensures: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    returns: Option<String>,
+}
+
+/// A `global size_of ...` or `global layout ...` declaration, which holds
+/// for the verification of the whole crate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GlobalInfo {
+    /// The declaration as written: `global size_of usize == 8;`
+    declaration: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    line: usize,
+}
+
+/// A struct, enum or union definition (`--include-types`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TypeInfo {
+    name: String,
+    /// `struct`, `enum` or `union`
+    kind: String,
+    /// `exec`, or `ghost`/`tracked` for `ghost struct`/`tracked enum`
+    mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    start_line: usize,
+    end_line: usize,
+    /// Module containing the type (empty for the crate root)
+    module_path: String,
+    qualified_name: String,
+    /// Fields of the type, those of every variant for an enum
+    field_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variant_count: Option<usize>,
+    /// Fields of type `Ghost<T>` or `Tracked<T>` (`Variant::field` in enums)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ghost_fields: Vec<String>,
+}
+
+/// A macro invoked in item position whose expansion was not parsed, so the
+/// items it generates are missing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UnparsedMacro {
+    /// The macro path as written, without the `!`
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    start_line: usize,
+    end_line: usize,
+    /// Module containing the invocation (empty for the crate root)
+    module_path: String,
+}
+
+/// A `state_machine!` or `tokenized_state_machine!`; its transitions and
+/// invariant lemmas are listed among the functions, in context
+/// `state_machine`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StateMachineInfo {
+    name: String,
+    /// `state_machine` or `tokenized_state_machine`
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generics: Option<String>,
+    /// The state, as declared in `fields { .. }`
+    fields: Vec<StateMachineField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    start_line: usize,
+    end_line: usize,
+    /// Module containing the machine (empty for the crate root)
+    module_path: String,
+    qualified_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StateMachineField {
+    name: String,
+    /// The type as written
+    #[serde(rename = "type")]
+    ty: String,
+    /// Strategy of a tokenized machine's field: `variable`, `map`, ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sharding: Option<String>,
+}
+
+/// A type alias or an associated type of a trait or impl
+/// (`--include-types`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TypeAliasInfo {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generics: Option<String>,
+    /// The aliased type as written; `None` for an associated type a trait
+    /// declares without a default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    /// Bounds of an associated type declared by a trait
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bounds: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    start_line: usize,
+    end_line: usize,
+    /// Module containing the alias (empty for the crate root)
+    module_path: String,
+    /// Module path, then the impl or trait type for associated types
+    qualified_name: String,
+    /// The declaring impl or trait, `standalone` for module-level aliases
+    context: ContextInfo,
+}
+
+/// A `const` or `static` item, or an associated const (`--include-consts`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ConstInfo {
+    name: String,
+    /// `const`, `static` or `static mut`
+    kind: String,
+    /// `spec`, `proof` or `exec` when declared with a mode (`spec const`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+    /// The type as written
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    start_line: usize,
+    end_line: usize,
+    /// Module containing the item (empty for the crate root)
+    module_path: String,
+    /// Module path, then the impl or trait type for associated consts
+    qualified_name: String,
+}
+
+/// A trait definition (`--include-traits`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TraitInfo {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    start_line: usize,
+    end_line: usize,
+    /// Module containing the trait (empty for the crate root)
+    module_path: String,
+    qualified_name: String,
+    methods: Vec<TraitMethodInfo>,
+}
+
+/// A method declared by a trait
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TraitMethodInfo {
+    name: String,
+    /// Whether the trait provides a default body; impls must define the
+    /// others
+    has_default: bool,
+    /// Spec clauses of the declaration (`requires`, `ensures`, ...), which
+    /// every impl must satisfy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    spec_clauses: Vec<String>,
+}
+
+/// Version of the JSON output, checked when loading a saved file; bump it
+/// whenever the shape of `ParsedOutput` or the types in it changes, and
+/// update `schema.json` with it
+const SCHEMA_VERSION: u32 = 4;
+
+/// JSON Schema of `ParsedOutput`, printed by `--print-schema`
+const SCHEMA: &str = include_str!("schema.json");
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ParsedOutput {
+    #[serde(default)]
+    schema_version: u32,
+    functions: Vec<FunctionInfo>,
+    /// The functions by file, with `--group-by file`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    functions_by_file: Option<BTreeMap<String, Vec<FunctionInfo>>>,
+    /// The functions by module (`crate` for the root), with `--group-by
+    /// module`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    functions_by_module: Option<BTreeMap<String, ModuleFunctions>>,
+    summary: Summary,
+    /// Callees that matched no parsed function (graph queries only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unresolved_callees: Option<Vec<UnresolvedCallee>>,
+    /// Functions outside the `--within-module` module that the listed
+    /// functions call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_deps: Option<Vec<FunctionInfo>>,
+    #[serde(flatten)]
+    declarations: Declarations,
+}
+
+/// The functions of a module and the files defining them, more than one
+/// when `#[path]` attributes or `cfg`'d `mod` declarations split it
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ModuleFunctions {
+    files: BTreeSet<String>,
+    functions: Vec<FunctionInfo>,
+}
+
+impl ModuleFunctions {
+    /// The functions grouped by module (`crate` for the root)
+    fn by_module(functions: &[FunctionInfo]) -> BTreeMap<String, ModuleFunctions> {
+        let mut modules: BTreeMap<String, ModuleFunctions> = BTreeMap::new();
+        for func in functions {
+            let module = if func.module_path.is_empty() {
+                "crate".to_string()
+            } else {
+                func.module_path.clone()
+            };
+            let entry = modules.entry(module).or_default();
+            entry.files.extend(func.file.clone());
+            entry.functions.push(func.clone());
+        }
+        modules
+    }
+}
+
+/// Items outside functions that are listed alongside them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Declarations {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    broadcast_uses: BroadcastUses,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    assumed_specifications: Vec<AssumedSpecification>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    globals: Vec<GlobalInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    types: Vec<TypeInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    type_aliases: Vec<TypeAliasInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    traits: Vec<TraitInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    consts: Vec<ConstInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    unparsed_macros: Vec<UnparsedMacro>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    state_machines: Vec<StateMachineInfo>,
+    /// Files no `mod` declaration reaches from the crate root, whose
+    /// module paths are guessed from their location
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    unreachable_files: Vec<String>,
+    /// Functions left out by `--exclude-tests`, reported in the summary
+    #[serde(skip)]
+    excluded_tests: usize,
+    /// Specified exec functions per module, counted before
+    /// `--unspecified-only` leaves the specified ones out
+    #[serde(skip)]
+    spec_coverage: BTreeMap<String, SpecCoverage>,
+}
+
+impl Declarations {
+    /// The declarations in `file`, with `functions` its functions
+    fn of_file(&self, file: &str, functions: &[FunctionInfo]) -> Declarations {
+        let in_file = |f: &Option<String>| f.as_deref() == Some(file);
+        let mut broadcast_uses = self.broadcast_uses.clone();
+        for uses in broadcast_uses.values_mut() {
+            uses.retain(|u| in_file(&u.file));
+        }
+        broadcast_uses.retain(|_, uses| !uses.is_empty());
+        let mut declarations = Declarations {
+            broadcast_uses,
+            spec_coverage: SpecCoverage::by_module(functions),
+            ..self.clone()
+        };
+        declarations
+            .assumed_specifications
+            .retain(|a| in_file(&a.file));
+        declarations.globals.retain(|g| in_file(&g.file));
+        declarations.types.retain(|t| in_file(&t.file));
+        declarations.type_aliases.retain(|t| in_file(&t.file));
+        declarations.traits.retain(|t| in_file(&t.file));
+        declarations.consts.retain(|c| in_file(&c.file));
+        declarations.unparsed_macros.retain(|m| in_file(&m.file));
+        declarations.state_machines.retain(|m| in_file(&m.file));
+        declarations.unreachable_files.retain(|f| f == file);
+        declarations.excluded_tests = 0;
+        declarations
+    }
+}
+
+/// How many of a module's exec functions have a `requires` or `ensures`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SpecCoverage {
+    /// Exec functions, `external_body` and `external` ones excluded
+    exec_functions: usize,
+    specified: usize,
+    /// Percentage of `exec_functions` that are specified
+    percent_specified: f64,
+    /// `external_body` and `external` exec functions, left unspecified on
+    /// purpose
+    external: usize,
+}
+
+impl SpecCoverage {
+    /// Coverage of the functions whose `specified` was computed, by module
+    /// (`crate` for the root)
+    fn by_module(functions: &[FunctionInfo]) -> BTreeMap<String, SpecCoverage> {
+        let mut coverage: BTreeMap<String, SpecCoverage> = BTreeMap::new();
+        for func in functions {
+            let Some(specified) = func.specified else {
+                continue;
+            };
+            let module = if func.module_path.is_empty() {
+                "crate".to_string()
+            } else {
+                func.module_path.clone()
+            };
+            let entry = coverage.entry(module).or_default();
+            if func.is_intentionally_unspecified() {
+                entry.external += 1;
+            } else {
+                entry.exec_functions += 1;
+                entry.specified += usize::from(specified);
+            }
+        }
+        coverage.values_mut().for_each(SpecCoverage::set_percent);
+        coverage
+    }
+
+    fn set_percent(&mut self) {
+        if self.exec_functions > 0 {
+            let percent = 100.0 * self.specified as f64 / self.exec_functions as f64;
+            self.percent_specified = (percent * 10.0).round() / 10.0;
+        }
+    }
+
+    /// Add the counts of another part of the module
+    fn add(&mut self, other: &SpecCoverage) {
+        self.exec_functions += other.exec_functions;
+        self.specified += other.specified;
+        self.external += other.external;
+        self.set_percent();
+    }
+}
+
+/// How many of a module's functions are inside `verus!` blocks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct VerusCoverage {
+    functions: usize,
+    in_verus_macro: usize,
+    /// Percentage of `functions` inside `verus!`
+    percent_in_verus_macro: f64,
+}
+
+impl VerusCoverage {
+    /// Coverage of the functions listed, by module (`crate` for the root)
+    fn by_module(functions: &[FunctionInfo]) -> BTreeMap<String, VerusCoverage> {
+        let mut coverage: BTreeMap<String, VerusCoverage> = BTreeMap::new();
+        for func in functions.iter().filter(|f| f.is_verified_function()) {
+            let module = if func.module_path.is_empty() {
+                "crate".to_string()
+            } else {
+                func.module_path.clone()
+            };
+            let entry = coverage.entry(module).or_default();
+            entry.functions += 1;
+            entry.in_verus_macro += usize::from(func.in_verus_macro);
+        }
+        coverage.values_mut().for_each(VerusCoverage::set_percent);
+        coverage
+    }
+
+    fn set_percent(&mut self) {
+        if self.functions > 0 {
+            let percent = 100.0 * self.in_verus_macro as f64 / self.functions as f64;
+            self.percent_in_verus_macro = (percent * 10.0).round() / 10.0;
+        }
+    }
+
+    /// Add the counts of another part of the module
+    fn add(&mut self, other: &VerusCoverage) {
+        self.functions += other.functions;
+        self.in_verus_macro += other.in_verus_macro;
+        self.set_percent();
+    }
+}
+
+/// What a graph query reports besides the functions it found
+struct QueryExtras {
+    unresolved_callees: Vec<UnresolvedCallee>,
+    external_deps: Option<Vec<FunctionInfo>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Summary {
+    total_functions: usize,
+    total_files: usize,
+    /// Functions with a `trusted` reason
+    #[serde(default)]
+    trusted_functions: usize,
+    /// Functions declared `unsafe`
+    #[serde(default)]
+    unsafe_functions: usize,
+    /// `async` functions
+    #[serde(default)]
+    async_functions: usize,
+    /// Test functions left out by `--exclude-tests`
+    #[serde(default)]
+    excluded_tests: usize,
+    /// Struct, enum and union definitions (with `--include-types`)
+    #[serde(default)]
+    total_types: usize,
+    /// Item-position macro invocations that were not expanded
+    #[serde(default)]
+    unparsed_macros: usize,
+    /// Number of consts and statics by module (`crate` for the root), with
+    /// `--include-consts`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    consts_by_module: BTreeMap<String, usize>,
+    /// Specified exec functions per module (`crate` for the root), with
+    /// `--show-specs` or `--unspecified-only`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    spec_coverage: BTreeMap<String, SpecCoverage>,
+    /// Functions inside `verus!` blocks per module (`crate` for the root)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    verus_coverage: BTreeMap<String, VerusCoverage>,
+    /// Number of spec functions by `spec_openness`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    spec_openness: BTreeMap<String, usize>,
+    /// Modules defining the functions, with `--group-by module`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_modules: Option<usize>,
+}
+
+impl Summary {
+    fn of(functions: &[FunctionInfo], declarations: &Declarations, total_files: usize) -> Self {
+        let mut spec_openness = BTreeMap::new();
+        for openness in functions.iter().filter_map(|f| f.spec_openness.as_ref()) {
+            *spec_openness.entry(openness.clone()).or_default() += 1;
+        }
+        let mut consts_by_module = BTreeMap::new();
+        for c in &declarations.consts {
+            let module = if c.module_path.is_empty() {
+                "crate".to_string()
+            } else {
+                c.module_path.clone()
+            };
+            *consts_by_module.entry(module).or_default() += 1;
+        }
+        Summary {
+            total_functions: functions.len(),
+            total_files,
+            trusted_functions: functions.iter().filter(|f| !f.trusted.is_empty()).count(),
+            unsafe_functions: functions.iter().filter(|f| f.is_unsafe).count(),
+            async_functions: functions.iter().filter(|f| f.is_async).count(),
+            excluded_tests: declarations.excluded_tests,
+            total_types: declarations.types.len(),
+            unparsed_macros: declarations.unparsed_macros.len(),
+            consts_by_module,
+            spec_coverage: declarations.spec_coverage.clone(),
+            verus_coverage: VerusCoverage::by_module(functions),
+            spec_openness,
+            total_modules: None,
+        }
+    }
+
+    /// Add the summary of more files, as for files output one at a time
+    fn add(&mut self, other: Summary) {
+        self.total_functions += other.total_functions;
+        self.total_files += other.total_files;
+        self.trusted_functions += other.trusted_functions;
+        self.unsafe_functions += other.unsafe_functions;
+        self.async_functions += other.async_functions;
+        self.excluded_tests += other.excluded_tests;
+        self.total_types += other.total_types;
+        self.unparsed_macros += other.unparsed_macros;
+        for (module, count) in other.consts_by_module {
+            *self.consts_by_module.entry(module).or_default() += count;
+        }
+        for (module, coverage) in &other.spec_coverage {
+            self.spec_coverage
+                .entry(module.clone())
+                .or_default()
+                .add(coverage);
+        }
+        for (module, coverage) in &other.verus_coverage {
+            self.verus_coverage
+                .entry(module.clone())
+                .or_default()
+                .add(coverage);
+        }
+        for (openness, count) in other.spec_openness {
+            *self.spec_openness.entry(openness).or_default() += count;
+        }
+    }
+}
+
+/// What to extract for each function
+#[derive(Debug, Clone, Default)]
+struct ExtractOptions {
+    include_verus_constructs: bool,
+    include_methods: bool,
+    show_visibility: bool,
+    show_kind: bool,
+    show_calls: bool,
+    show_signature: bool,
+    elide_specs: bool,
+    show_specs: bool,
+    show_attributes: bool,
+    show_docs: bool,
+    show_by_asserts: bool,
+    /// Whether exec functions get `specified` without `--show-specs`
+    show_spec_status: bool,
+    include_types: bool,
+    include_traits: bool,
+    include_consts: bool,
+    context_string: bool,
+    has_opens_invariants: bool,
+    unwinding_allowed: bool,
+    loop_isolation_disabled: bool,
+    has_tracked: bool,
+    only_generic: bool,
+    only_monomorphic: bool,
+    only_unsafe: bool,
+    only_ffi: bool,
+    only_verus: bool,
+    only_plain: bool,
+    exclude_async: bool,
+    exclude_tests: bool,
+    only_target: Option<String>,
+    only_mutating: bool,
+    min_asserts: Option<usize>,
+    min_fuel: Option<u64>,
+    cfg_filter: Option<cfg::CfgSet>,
+    /// Count `assume`s and `admit()`s even without `--show-specs`
+    count_proof_debt: bool,
+    only_trusted: bool,
+    unspecified_only: bool,
+    only_assumed_specs: bool,
+    /// `--kind`
+    kind: Option<String>,
+}
+
+impl ExtractOptions {
+    fn from_args(args: &Args) -> Self {
+        // DOT output is a call graph with nodes colored by kind
+        let dot = matches!(args.format, OutputFormat::Dot);
+        // Markdown tables have columns for the kind and spec status
+        let markdown = matches!(args.format, OutputFormat::Markdown);
+        // Findings include unspecified functions and proof debt
+        let findings = matches!(args.format, OutputFormat::Sarif | OutputFormat::Github);
+        // SQLite tables have columns for the kind, visibility and spec status
+        let sqlite = matches!(args.format, OutputFormat::Sqlite);
+        // Tags have a kind letter per mode
+        let ctags = matches!(args.format, OutputFormat::Ctags);
+        // Baselines record the kind and signature of each function
+        let baseline = args.write_baseline.is_some() || args.check_baseline.is_some();
+        Self {
+            include_verus_constructs: args.include_verus_constructs,
+            include_methods: args.include_methods,
+            show_visibility: args.show_visibility || sqlite || args.stats,
+            show_kind: args.show_kind
+                || args.kind.is_some()
+                || baseline
+                || args.stats
+                || dot
+                || markdown
+                || sqlite
+                || ctags
+                || args.sort == Some(SortKey::Kind),
+            show_calls: args.show_calls || dot,
+            show_signature: args.show_signature || baseline,
+            elide_specs: args.elide_specs,
+            show_specs: args.show_specs,
+            show_attributes: args.show_attributes,
+            show_docs: args.show_docs,
+            show_by_asserts: args.show_by_asserts,
+            show_spec_status: markdown || findings || sqlite,
+            include_types: args.include_types,
+            include_traits: args.include_traits,
+            include_consts: args.include_consts,
+            context_string: args.context_string,
+            has_opens_invariants: args.has_opens_invariants,
+            unwinding_allowed: args.unwinding_allowed,
+            loop_isolation_disabled: args.loop_isolation_disabled,
+            has_tracked: args.has_tracked,
+            only_generic: args.only_generic,
+            only_monomorphic: args.only_monomorphic,
+            only_unsafe: args.only_unsafe,
+            only_ffi: args.only_ffi,
+            only_verus: args.only_verus,
+            only_plain: args.only_plain,
+            exclude_async: args.exclude_async,
+            exclude_tests: args.exclude_tests,
+            only_target: args.only_target.clone(),
+            only_mutating: args.only_mutating,
+            min_asserts: args.min_asserts,
+            min_fuel: args.min_fuel,
+            cfg_filter: args.cfg_filter.clone(),
+            count_proof_debt: args.fail_if_assumes.is_some() || findings,
+            only_trusted: args.only_trusted,
+            unspecified_only: args.unspecified_only,
+            only_assumed_specs: args.only_assumed_specs,
+            kind: args.kind.clone(),
+        }
+    }
+}
+
+/// Visitor that collects function information from an AST
+struct FunctionVisitor {
+    functions: Vec<FunctionInfo>,
+    file_path: Option<String>,
+    /// Cargo target of the file being visited
+    target: Option<String>,
+    /// Current module path: the file's module prefix plus any inline `mod` blocks
+    module_path: Vec<String>,
+    /// Impl or trait currently being visited
+    current_impl: Option<ImplTarget>,
+    /// Whether that impl or trait has type or const parameters
+    impl_generic: bool,
+    /// Self type, trait and generics of that impl or trait, as written
+    impl_context: Option<ContextInfo>,
+    /// Qualified name of the function whose body is being visited
+    current_function: Option<String>,
+    /// `broadcast use` statements with the module they appear in
+    broadcast_uses: Vec<(String, BroadcastUseInfo)>,
+    assumed_specifications: Vec<AssumedSpecification>,
+    globals: Vec<GlobalInfo>,
+    types: Vec<TypeInfo>,
+    type_aliases: Vec<TypeAliasInfo>,
+    traits: Vec<TraitInfo>,
+    consts: Vec<ConstInfo>,
+    /// Names of the macros defined with `macro_rules!`
+    macro_names: BTreeSet<String>,
+    /// Type names used by each type alias, for `ghost_wrappers_used`
+    alias_type_names: BTreeMap<String, BTreeSet<String>>,
+    unparsed_macros: Vec<UnparsedMacro>,
+    state_machines: Vec<StateMachineInfo>,
+    /// Files parsed as part of this one through `include!`
+    included_files: Vec<PathBuf>,
+    /// `#[cfg]` conditions of the enclosing file, modules, impls and
+    /// `cfg_if!` branches
+    cfg: Vec<String>,
+    /// Test functions left out with `--exclude-tests`
+    excluded_tests: usize,
+    /// `loop_isolation` setting of the enclosing file, modules, impls and
+    /// traits
+    loop_isolation: Option<bool>,
+    /// Whether one of those allows exec functions without `decreases`
+    termination_unchecked: bool,
+    /// ABI of the `extern { .. }` block being visited
+    foreign_abi: Option<String>,
+    /// Whether the items visited are inside a const or static initializer
+    in_const_block: bool,
+    /// Whether the items visited are inside a `verus!` block
+    in_verus_macro: bool,
+    /// `use` declarations of every module visited so far
+    imports: ImportTable,
+    options: ExtractOptions,
 }
 
 impl FunctionVisitor {
-    fn new(
-        file_path: Option<String>,
-        include_verus_constructs: bool,
-        include_methods: bool,
-        show_visibility: bool,
-        show_kind: bool,
-    ) -> Self {
+    fn new(file_path: Option<String>, module_prefix: Vec<String>, options: ExtractOptions) -> Self {
         Self {
             functions: Vec::new(),
             file_path,
-            include_verus_constructs,
-            include_methods,
-            show_visibility,
-            show_kind,
+            target: None,
+            module_path: module_prefix,
+            current_impl: None,
+            impl_generic: false,
+            impl_context: None,
+            current_function: None,
+            broadcast_uses: Vec::new(),
+            assumed_specifications: Vec::new(),
+            globals: Vec::new(),
+            types: Vec::new(),
+            type_aliases: Vec::new(),
+            traits: Vec::new(),
+            consts: Vec::new(),
+            macro_names: BTreeSet::new(),
+            alias_type_names: BTreeMap::new(),
+            unparsed_macros: Vec::new(),
+            state_machines: Vec::new(),
+            included_files: Vec::new(),
+            cfg: Vec::new(),
+            excluded_tests: 0,
+            loop_isolation: None,
+            termination_unchecked: false,
+            foreign_abi: None,
+            in_const_block: false,
+            in_verus_macro: false,
+            imports: ImportTable::new(),
+            options,
         }
     }
 
+    /// Module path plus the enclosing impl/trait type and `name`; trait
+    /// impl methods are qualified as `<Type as Trait>`, like
+    /// `--verify-function` names them, and stay apart from inherent methods
+    /// of the same name
+    fn qualified_name(&self, name: &str) -> String {
+        let mut qualified = match &self.current_function {
+            Some(parent) => vec![parent.clone()],
+            None => self.module_path.clone(),
+        };
+        qualified.extend(self.current_impl.as_ref().map(|target| {
+            match (&target.self_type, &target.trait_name) {
+                (Some(ty), Some(tr)) => format!("<{} as {}>", ty, tr),
+                _ => target.type_name().to_string(),
+            }
+        }));
+        qualified.push(name.to_string());
+        qualified.join("::")
+    }
+
+    /// Visit the initializer of a const or static, or a `const { .. }`
+    /// block: functions defined there are listed with context
+    /// `const-block`, outside any impl or trait
+    fn in_const_block(&mut self, visit: impl FnOnce(&mut Self)) {
+        let previous = self.current_impl.take();
+        let previous_generic = std::mem::take(&mut self.impl_generic);
+        let previous_context = self.impl_context.take();
+        let previous_in_const = std::mem::replace(&mut self.in_const_block, true);
+        visit(self);
+        self.in_const_block = previous_in_const;
+        self.impl_context = previous_context;
+        self.impl_generic = previous_generic;
+        self.current_impl = previous;
+    }
+
+    /// Visit a function's signature and body with `current_function` set;
+    /// functions nested in the body are outside the enclosing impl or trait
+    fn in_function(&mut self, sig: &verus_syn::Signature, visit: impl FnOnce(&mut Self)) {
+        let name = self.qualified_name(&sig.ident.to_string());
+        let previous = self.current_function.replace(name);
+        let previous_impl = self.current_impl.take();
+        let previous_generic = std::mem::take(&mut self.impl_generic);
+        let previous_context = self.impl_context.take();
+        visit(self);
+        self.impl_context = previous_context;
+        self.impl_generic = previous_generic;
+        self.current_impl = previous_impl;
+        self.current_function = previous;
+    }
+
     fn extract_function_kind(&self, sig: &verus_syn::Signature) -> String {
         // Check for Verus-specific function modes
         // Note: In verus_syn, mode is FnMode enum, not Option<FnMode>
-        use verus_syn::FnMode;
-        
-        let mode_str = match sig.mode {
-            FnMode::Spec(_) => "spec",
-            FnMode::SpecChecked(_) => "spec(checked)",
-            FnMode::Proof(_) => "proof", 
-            FnMode::ProofAxiom(_) => "proof(axiom)",
-            FnMode::Exec(_) => "exec",
-            FnMode::Default => "",
-        };
-        
-        if sig.constness.is_some() {
-            if mode_str.is_empty() {
-                "const fn".to_string()
-            } else {
-                format!("{} const fn", mode_str)
-            }
-        } else if !mode_str.is_empty() {
-            format!("{} fn", mode_str)
+        let mode_str = mode_name(&sig.mode);
+
+        let broadcast = if sig.broadcast.is_some() {
+            "broadcast "
         } else {
-            "fn".to_string()
+            ""
+        };
+
+        let mut words: Vec<&str> = Vec::new();
+        if !mode_str.is_empty() {
+            words.push(mode_str);
+        }
+        if sig.constness.is_some() {
+            words.push("const");
+        }
+        if sig.asyncness.is_some() {
+            words.push("async");
+        }
+        words.push("fn");
+        format!("{}{}", broadcast, words.join(" "))
+    }
+
+    /// Whether a spec function's body is visible outside its module; a spec
+    /// function without `open` or `closed` is closed
+    fn extract_spec_openness(&self, sig: &verus_syn::Signature) -> Option<String> {
+        use verus_syn::{FnMode, Publish};
+
+        if !matches!(sig.mode, FnMode::Spec(_) | FnMode::SpecChecked(_)) {
+            return None;
         }
+        let openness = match sig.publish {
+            Publish::Open(_) | Publish::OpenRestricted(_) => "open",
+            Publish::Uninterp(_) => "uninterp",
+            Publish::Closed(_) | Publish::Default => "closed",
+        };
+        Some(openness.to_string())
     }
 
     fn extract_visibility(&self, vis: &Visibility) -> String {
@@ -149,8 +2150,56 @@ impl FunctionVisitor {
         }
     }
 
+    /// The `context` of a function or item of kind `impl`, `trait`,
+    /// `standalone`, `const-block` or `state_machine`
+    fn context(&self, kind: &str) -> FunctionContext {
+        if self.options.context_string {
+            return FunctionContext::Kind(kind.to_string());
+        }
+        let enclosing = self
+            .impl_context
+            .as_ref()
+            .filter(|_| !matches!(kind, "standalone" | "const-block"));
+        FunctionContext::Structured(ContextInfo {
+            kind: kind.to_string(),
+            ..enclosing.cloned().unwrap_or_default()
+        })
+    }
+
+    /// Whether a function needs type or const arguments, its own or those
+    /// of the enclosing impl or trait
+    fn is_generic(&self, sig: &verus_syn::Signature) -> bool {
+        self.impl_generic || signature::has_type_params(&sig.generics)
+    }
+
     fn should_include_function(&self, sig: &verus_syn::Signature) -> bool {
-        if self.include_verus_constructs {
+        if self.options.has_opens_invariants && sig.spec.invariants.is_none() {
+            return false;
+        }
+        if self.options.unwinding_allowed && !(specs::is_exec(sig) && sig.spec.unwind.is_none()) {
+            return false;
+        }
+        if self.options.has_tracked
+            && ghost::params(sig).1.is_empty()
+            && !ghost::returns_tracked(sig)
+        {
+            return false;
+        }
+        if (self.options.only_generic || self.options.only_monomorphic)
+            && self.is_generic(sig) != self.options.only_generic
+        {
+            return false;
+        }
+        if self.options.only_unsafe && sig.unsafety.is_none() {
+            return false;
+        }
+        if self.options.exclude_async && sig.asyncness.is_some() {
+            return false;
+        }
+        if self.options.only_mutating && !mutation::takes_mut(sig) && !mutation::uses_old(sig) {
+            return false;
+        }
+        if self.options.include_verus_constructs {
             true
         } else {
             // Exclude Verus-specific modes (spec, proof, exec)
@@ -159,322 +2208,4226 @@ impl FunctionVisitor {
         }
     }
 
-    fn add_function(
+    /// Record a type definition with `--include-types`; `fields` holds the
+    /// fields of each variant of an enum, with the prefix naming them
+    fn add_type(
         &mut self,
-        name: String,
         span: proc_macro2::Span,
-        sig: &verus_syn::Signature,
-        vis: &Visibility,
-        context: Option<String>,
+        name: String,
+        kind: &str,
+        mode: &verus_syn::DataMode,
+        fields: &[(String, &verus_syn::Fields)],
+        variant_count: Option<usize>,
     ) {
-        if !self.should_include_function(sig) {
+        if !self.options.include_types {
             return;
         }
-
-        let kind = if self.show_kind {
-            Some(self.extract_function_kind(sig))
-        } else {
-            None
+        let field_count = fields.iter().map(|(_, f)| f.len()).sum();
+        let ghost_fields = fields
+            .iter()
+            .flat_map(|(prefix, f)| ghost::fields(f, prefix))
+            .collect();
+        let mode = match mode {
+            verus_syn::DataMode::Ghost(_) => "ghost",
+            verus_syn::DataMode::Tracked(_) => "tracked",
+            verus_syn::DataMode::Exec(_) | verus_syn::DataMode::Default => "exec",
         };
-
-        let visibility = if self.show_visibility {
-            Some(self.extract_visibility(vis))
+        let mut qualified = self.module_path.clone();
+        qualified.push(name.clone());
+        self.types.push(TypeInfo {
+            name,
+            kind: kind.to_string(),
+            mode: mode.to_string(),
+            file: self.file_path.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            module_path: self.module_path.join("::"),
+            qualified_name: qualified.join("::"),
+            field_count,
+            variant_count,
+            ghost_fields,
+        });
+    }
+
+    /// Record a type alias or associated type with `--include-types`
+    fn add_type_alias(
+        &mut self,
+        span: proc_macro2::Span,
+        name: String,
+        generics: &verus_syn::Generics,
+        target: Option<&verus_syn::Type>,
+        bounds: Option<String>,
+    ) {
+        if let Some(ty) = target {
+            self.alias_type_names
+                .entry(name.clone())
+                .or_default()
+                .extend(ghost::type_names(ty));
+        }
+        if !self.options.include_types {
+            return;
+        }
+        let context = self.impl_context.clone().unwrap_or_else(|| ContextInfo {
+            kind: "standalone".to_string(),
+            ..Default::default()
+        });
+        self.type_aliases.push(TypeAliasInfo {
+            qualified_name: self.qualified_name(&name),
+            name,
+            generics: signature::generics(generics),
+            target: target.map(pretty::pretty_type),
+            bounds,
+            file: self.file_path.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            module_path: self.module_path.join("::"),
+            context,
+        });
+    }
+
+    /// Record a `const` or `static` item with `--include-consts`
+    fn add_const(
+        &mut self,
+        span: proc_macro2::Span,
+        name: String,
+        kind: &str,
+        mode: &verus_syn::FnMode,
+        ty: &verus_syn::Type,
+    ) {
+        if !self.options.include_consts {
+            return;
+        }
+        let mode = mode_name(mode);
+        self.consts.push(ConstInfo {
+            qualified_name: self.qualified_name(&name),
+            name,
+            kind: kind.to_string(),
+            mode: (!mode.is_empty()).then(|| mode.to_string()),
+            ty: pretty::pretty_type(ty),
+            file: self.file_path.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            module_path: self.module_path.join("::"),
+        });
+    }
+
+    /// Record a trait definition and the methods it declares
+    fn add_trait(&mut self, node: &verus_syn::ItemTrait) {
+        let methods = node
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                verus_syn::TraitItem::Fn(f) => Some(TraitMethodInfo {
+                    name: f.sig.ident.to_string(),
+                    has_default: f.default.is_some(),
+                    spec_clauses: specs::clause_keywords(&f.sig)
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                }),
+                _ => None,
+            })
+            .collect();
+        let span = node.span();
+        let mut qualified = self.module_path.clone();
+        qualified.push(node.ident.to_string());
+        self.traits.push(TraitInfo {
+            name: node.ident.to_string(),
+            file: self.file_path.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            module_path: self.module_path.join("::"),
+            qualified_name: qualified.join("::"),
+            methods,
+        });
+    }
+
+    /// Whether entries that are not functions (broadcast groups, external
+    /// items) are listed; the filters selecting functions leave them out
+    fn include_items(&self) -> bool {
+        self.options.include_verus_constructs
+            && !self.options.has_opens_invariants
+            && !self.options.unwinding_allowed
+            && !self.options.loop_isolation_disabled
+            && !self.options.only_trusted
+            && !self.options.has_tracked
+            && !self.options.only_generic
+            && !self.options.only_monomorphic
+            && !self.options.only_unsafe
+            && !self.options.only_ffi
+            && !self.options.only_verus
+            && !self.options.only_plain
+            && !self.options.exclude_async
+            && !self.options.only_mutating
+            && self.options.min_asserts.is_none()
+            && self.options.min_fuel.is_none()
+            && self.options.cfg_filter.is_none()
+    }
+
+    /// Record an item Verus ignores (`kind` `external`) or one describing a
+    /// foreign type or trait (`external_spec`, with its `target`)
+    fn add_external_item(
+        &mut self,
+        span: proc_macro2::Span,
+        name: String,
+        vis: &Visibility,
+        context: &str,
+        kind: &str,
+        target: Option<String>,
+    ) {
+        if !self.include_items() {
+            return;
+        }
+        let mut qualified = self.module_path.clone();
+        qualified.push(name.clone());
+        self.functions.push(FunctionInfo {
+            name: name.clone(),
+            file: self.file_path.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            target: self.target.clone(),
+            kind: Some(kind.to_string()),
+            visibility: self
+                .options
+                .show_visibility
+                .then(|| self.extract_visibility(vis)),
+            context: Some(self.context(context)),
+            module_path: self.module_path.join("::"),
+            qualified_name: qualified.join("::"),
+            verify_function_arg: name,
+            external_target: target,
+            in_verus_macro: self.in_verus_macro,
+            ..Default::default()
+        });
+    }
+
+    /// Record a `broadcast group` as an entry whose members are its calls
+    fn add_broadcast_group(&mut self, node: &verus_syn::ItemBroadcastGroup) {
+        if !self.include_items() {
+            return;
+        }
+
+        let span = node.span();
+        let name = node.ident.to_string();
+        let members: Vec<String> = node
+            .paths
+            .iter()
+            .map(|p| body::path_to_string(&p.path))
+            .collect();
+        let mut qualified = self.module_path.clone();
+        qualified.push(name.clone());
+        let callees = self.options.show_calls.then(|| {
+            members
+                .iter()
+                .map(|path| body::Callee {
+                    path: path.clone(),
+                    ambiguous: false,
+                })
+                .collect()
+        });
+
+        self.functions.push(FunctionInfo {
+            name: name.clone(),
+            file: self.file_path.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            target: self.target.clone(),
+            kind: self
+                .options
+                .show_kind
+                .then(|| "broadcast group".to_string()),
+            visibility: self
+                .options
+                .show_visibility
+                .then(|| self.extract_visibility(&node.vis)),
+            context: Some(self.context("standalone")),
+            module_path: self.module_path.join("::"),
+            qualified_name: qualified.join("::"),
+            verify_function_arg: name,
+            callees,
+            spec_deps: self.options.show_calls.then(Vec::new),
+            group_members: Some(members),
+            in_verus_macro: self.in_verus_macro,
+            ..Default::default()
+        });
+    }
+
+    /// Record a `macro_rules!` definition as a `macro_def` entry
+    fn add_macro_def(&mut self, node: &ItemMacro, name: String) {
+        if !self.include_items() {
+            return;
+        }
+        let span = node.span();
+        let mut qualified = self.module_path.clone();
+        qualified.push(name.clone());
+        self.functions.push(FunctionInfo {
+            name: name.clone(),
+            file: self.file_path.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            target: self.target.clone(),
+            kind: Some("macro_def".to_string()),
+            context: Some(self.context("standalone")),
+            module_path: self.module_path.join("::"),
+            qualified_name: qualified.join("::"),
+            verify_function_arg: name,
+            rule_count: Some(macros::rule_count(node.mac.tokens.clone())),
+            in_verus_macro: self.in_verus_macro,
+            ..Default::default()
+        });
+    }
+
+    /// Record a `state_machine!` or `tokenized_state_machine!` and list its
+    /// transitions and functions as members of the machine; `false` when
+    /// the body does not parse
+    fn visit_state_machine(&mut self, mac: &verus_syn::Macro) -> bool {
+        use state_machines::{Member, StateMachine};
+
+        let Ok(machine) = verus_syn::parse2::<StateMachine>(mac.tokens.clone()) else {
+            return false;
+        };
+        let name = machine.name.to_string();
+        let span = mac.span();
+        self.state_machines.push(StateMachineInfo {
+            name: name.clone(),
+            kind: body::path_to_string(&mac.path),
+            generics: signature::generics(&machine.generics),
+            fields: machine
+                .fields
+                .iter()
+                .map(|field| StateMachineField {
+                    name: field
+                        .ident
+                        .as_ref()
+                        .map(|ident| ident.to_string())
+                        .unwrap_or_default(),
+                    ty: pretty::pretty_type(&field.ty),
+                    sharding: state_machines::sharding(&field.attrs),
+                })
+                .collect(),
+            file: self.file_path.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            module_path: self.module_path.join("::"),
+            qualified_name: self.qualified_name(&name),
+        });
+
+        let target = ImplTarget {
+            self_type: Some(name.clone()),
+            trait_name: None,
+        };
+        let context = ContextInfo {
+            kind: "state_machine".to_string(),
+            self_ty: Some(name),
+            trait_path: None,
+            generics: signature::generics(&machine.generics),
+        };
+        let previous = self.current_impl.replace(target);
+        let previous_generic = std::mem::replace(
+            &mut self.impl_generic,
+            signature::has_type_params(&machine.generics),
+        );
+        let previous_context = self.impl_context.replace(context);
+        for member in &machine.members {
+            match member {
+                Member::Transition(transition) => self.add_transition(transition),
+                Member::Fn(node) => {
+                    let count = self.functions.len();
+                    self.add_function(
+                        node.span(),
+                        &node.sig,
+                        &node.vis,
+                        &node.attrs,
+                        Some(&node.block),
+                        "state_machine",
+                    );
+                    if let Some(func) = self.functions.get_mut(count) {
+                        func.state_machine_role = state_machines::role(&node.attrs);
+                    }
+                    self.in_function(&node.sig, |v| verus_syn::visit::visit_item_fn(v, node));
+                }
+            }
+        }
+        self.impl_context = previous_context;
+        self.impl_generic = previous_generic;
+        self.current_impl = previous;
+        true
+    }
+
+    /// Record a transition of the state machine being visited as an entry
+    /// of kind `init`, `transition`, `readonly` or `property`
+    fn add_transition(&mut self, transition: &state_machines::Transition) {
+        if !self.include_items() {
+            return;
+        }
+        let verify_function_arg = match &self.current_impl {
+            Some(target) => target.verify_function_arg(&transition.name),
+            None => transition.name.clone(),
+        };
+        self.functions.push(FunctionInfo {
+            name: transition.name.clone(),
+            file: self.file_path.clone(),
+            start_line: transition.start_line,
+            end_line: transition.end_line,
+            target: self.target.clone(),
+            kind: Some(transition.kind.clone()),
+            context: Some(self.context("state_machine")),
+            cfg: self.cfg.clone(),
+            module_path: self.module_path.join("::"),
+            qualified_name: self.qualified_name(&transition.name),
+            verify_function_arg,
+            in_verus_macro: self.in_verus_macro,
+            ..Default::default()
+        });
+    }
+
+    /// Record a macro invoked in item position that is not expanded
+    fn add_unparsed_macro(&mut self, mac: &verus_syn::Macro) {
+        let span = mac.span();
+        self.unparsed_macros.push(UnparsedMacro {
+            path: body::path_to_string(&mac.path),
+            file: self.file_path.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            module_path: self.module_path.join("::"),
+        });
+    }
+
+    /// Visit the items of a `verus! { .. }` in statement or expression
+    /// position, or of those among the tokens of another macro; in a
+    /// function body, they are nested in that function
+    fn visit_verus_blocks(&mut self, mac: &verus_syn::Macro) {
+        let blocks = if mac.path.is_ident("verus") {
+            vec![mac.tokens.clone()]
+        } else {
+            macros::verus_blocks(mac.tokens.clone())
+        };
+        for tokens in blocks {
+            if let Ok(body) = verus_syn::parse2::<VerusMacroBody>(tokens) {
+                self.in_verus_macro(&body);
+            }
+        }
+    }
+
+    /// Visit the items of a `verus!` body with `in_verus_macro` set
+    fn in_verus_macro(&mut self, body: &VerusMacroBody) {
+        let previous = std::mem::replace(&mut self.in_verus_macro, true);
+        for item in &body.items {
+            self.visit_item(item);
+        }
+        self.in_verus_macro = previous;
+    }
+
+    /// Visit the items of the file named by `include!("path")`, resolved
+    /// relative to the including file, as part of the current module;
+    /// `false` when the path is not a plain literal or the file cannot be
+    /// read and parsed. A file is included at most once per parse.
+    fn include_file(&mut self, mac: &verus_syn::Macro) -> bool {
+        let Ok(literal) = verus_syn::parse2::<verus_syn::LitStr>(mac.tokens.clone()) else {
+            return false;
+        };
+        let Some(including) = &self.file_path else {
+            return false;
+        };
+        let path = Path::new(including)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(literal.value());
+        if self.included_files.contains(&path) {
+            return false;
+        }
+        let Ok(content) = fs::read_to_string(&path) else {
+            return false;
+        };
+        let Ok(file) = verus_syn::parse_file(&content) else {
+            return false;
+        };
+        self.included_files.push(path.clone());
+        let previous = self.file_path.replace(path.to_string_lossy().to_string());
+        for item in &file.items {
+            self.visit_item(item);
+        }
+        self.file_path = previous;
+        true
+    }
+
+    fn add_function(
+        &mut self,
+        span: proc_macro2::Span,
+        sig: &verus_syn::Signature,
+        vis: &Visibility,
+        attrs: &[verus_syn::Attribute],
+        body: Option<&verus_syn::Block>,
+        context: &str,
+    ) {
+        if !self.should_include_function(sig) {
+            return;
+        }
+        let context = match context {
+            "standalone" if self.in_const_block => "const-block",
+            _ => context,
+        };
+        let mut cfg = self.cfg.clone();
+        cfg.extend(cfg::conditions(attrs));
+        if let Some(ref enabled) = self.options.cfg_filter {
+            if !cfg.iter().all(|condition| enabled.satisfies(condition)) {
+                return;
+            }
+        }
+        let is_test = cfg::is_test(&cfg) || body::is_test_attr(attrs);
+        if is_test && self.options.exclude_tests {
+            self.excluded_tests += 1;
+            return;
+        }
+        let abi = sig.abi.as_ref().map(ffi::abi).or(self.foreign_abi.clone());
+        let no_mangle = ffi::no_mangle(attrs);
+        let export_name = ffi::export_name(attrs);
+        if self.options.only_ffi && abi.is_none() && !no_mangle && export_name.is_none() {
+            return;
+        }
+        if (self.options.only_verus && !self.in_verus_macro)
+            || (self.options.only_plain && self.in_verus_macro)
+        {
+            return;
+        }
+        let loop_isolation = loops::isolation(attrs).or(self.loop_isolation);
+        if self.options.loop_isolation_disabled && loop_isolation != Some(false) {
+            return;
+        }
+
+        let verifier_attrs = body::verifier_attrs(attrs);
+        // Verus ignores `#[verifier::external]` functions, so their mode
+        // does not matter
+        let (kind, spec_openness) = if verifier_attrs.iter().any(|a| a == "external") {
+            (Some("external".to_string()), None)
+        } else if self.options.show_kind {
+            (
+                Some(self.extract_function_kind(sig)),
+                self.extract_spec_openness(sig),
+            )
+        } else {
+            (None, None)
+        };
+
+        let visibility = if self.options.show_visibility {
+            Some(self.extract_visibility(vis))
+        } else {
+            None
+        };
+
+        let mut callees = None;
+        let mut spec_deps = None;
+        let mut unresolved_calls = Vec::new();
+        let mut reveals = None;
+        let mut trusted = BTreeSet::new();
+        if verifier_attrs.iter().any(|a| a == "external_body") {
+            trusted.insert("external_body".to_string());
+        }
+        let termination_unchecked = specs::is_exec(sig)
+            && (self.termination_unchecked || specs::allows_no_decreases(attrs));
+        if termination_unchecked {
+            trusted.insert("termination_unchecked".to_string());
+        }
+        if self.options.show_calls || self.options.only_trusted {
+            let self_type = self.current_impl.as_ref().map(ImplTarget::type_name);
+            let facts = body::scan_function(sig, body, self_type);
+            trusted.extend(facts.trusted);
+            if self.options.show_calls {
+                callees = Some(facts.callees.into_iter().collect());
+                spec_deps = Some(facts.spec_deps.into_iter().collect());
+                unresolved_calls = facts.unresolved_calls.into_iter().collect();
+                reveals = Some(facts.reveals.into_iter().collect());
+            }
+        }
+        if self.options.only_trusted && trusted.is_empty() {
+            return;
+        }
+
+        let mut requires = None;
+        let mut recommends = None;
+        let mut ensures = None;
+        let mut trivial_ensures = None;
+        let mut decreases = None;
+        let mut decreases_when = None;
+        let mut decreases_via = None;
+        let mut returns = None;
+        let mut no_unwind = None;
+        let mut no_unwind_when = None;
+        let mut function_loops = None;
+        let mut loop_summary = None;
+        let mut proof_blocks = None;
+        let mut proof_lines = None;
+        let mut assert_count = None;
+        let mut assume_count = None;
+        let mut admit_count = None;
+        let mut provers = None;
+        let mut reveal_count = None;
+        let mut max_fuel = None;
+        let mut calc_count = None;
+        let mut calc_lines = Vec::new();
+        let mut by_blocks = None;
+        let mut by_block_lines = None;
+        let mut by_asserts = Vec::new();
+        if self.options.show_specs
+            || self.options.min_asserts.is_some()
+            || self.options.min_fuel.is_some()
+            || self.options.count_proof_debt
+            || self.options.show_by_asserts
+        {
+            let facts = proofs::scan(sig, body);
+            if self
+                .options
+                .min_asserts
+                .is_some_and(|min| facts.asserts < min)
+            {
+                return;
+            }
+            if self
+                .options
+                .min_fuel
+                .is_some_and(|min| facts.max_fuel.is_none_or(|fuel| fuel < min))
+            {
+                return;
+            }
+            max_fuel = facts.max_fuel;
+            assert_count = Some(facts.asserts);
+            assume_count = Some(facts.assumes);
+            admit_count = Some(facts.admits);
+            if self.options.show_specs {
+                provers = Some(facts.provers);
+                reveal_count = Some(facts.reveals);
+                calc_count = Some(facts.calcs.len());
+                calc_lines = facts.calcs;
+                by_blocks = Some(facts.by_blocks);
+                by_block_lines = Some(facts.by_block_lines);
+            }
+            if self.options.show_by_asserts {
+                by_asserts = facts.by_asserts;
+            }
+            if self.options.show_specs && specs::is_exec(sig) {
+                proof_blocks = Some(facts.proof_blocks);
+                proof_lines = Some(facts.proof_lines);
+            }
+        }
+        let mut quantifier_count = None;
+        let mut trigger_count = None;
+        if self.options.show_specs {
+            let facts = quantifiers::scan(sig, body);
+            quantifier_count = Some(facts.quantifiers);
+            trigger_count = Some(facts.triggers);
+        }
+        let opens_invariants = if self.options.show_specs || self.options.has_opens_invariants {
+            specs::opens_invariants(sig)
+        } else {
+            None
+        };
+        if self.options.show_specs {
+            decreases = Some(specs::decreases(sig));
+            decreases_when = specs::decreases_when(sig);
+            decreases_via = specs::decreases_via(sig);
+            returns = specs::returns(sig);
+            no_unwind = Some(sig.spec.unwind.is_some());
+            no_unwind_when = specs::no_unwind_when(sig);
+            let found = loops::loops(body);
+            loop_summary = Some(loops::LoopSummary::of(&found));
+            function_loops = Some(found);
+            let clauses = specs::ensures(sig);
+            requires = Some(specs::requires(sig));
+            recommends = Some(specs::recommends(sig));
+            trivial_ensures = Some(specs::trivial_ensures(sig, &clauses));
+            ensures = Some(clauses);
+        }
+        // The functions of a state machine are written in its DSL, where a
+        // bare `fn` is not exec
+        let specified = (specs::is_exec(sig)
+            && context != "state_machine"
+            && (self.options.show_specs
+                || self.options.unspecified_only
+                || self.options.show_spec_status))
+            .then(|| specs::is_specified(sig));
+
+        let (ghost_params, tracked_params) = ghost::params(sig);
+        let is_exec = specs::is_exec(sig);
+        let locals = body
+            .filter(|_| is_exec)
+            .map(ghost::locals)
+            .unwrap_or_default();
+        let mut type_names = BTreeSet::new();
+        if is_exec {
+            type_names.extend(ghost::signature_type_names(sig));
+            type_names.extend(locals.type_names);
+        }
+        let ghost_wrappers_used = is_exec
+            && (ghost::names_wrapper(&type_names)
+                || sig.inputs.iter().any(|arg| arg.tracked.is_some())
+                || ghost::returns_tracked(sig)
+                || locals.ghost + locals.tracked > 0);
+        let return_type = if self.options.show_kind || self.options.show_signature {
+            signature::return_type(sig)
         } else {
             None
         };
+        let signature = self
+            .options
+            .show_signature
+            .then(|| signature::signature(sig, !self.options.elide_specs));
+
+        let attributes = if self.options.show_attributes {
+            body::attributes(attrs)
+        } else {
+            Vec::new()
+        };
+
+        let name = sig.ident.to_string();
+        let module_path = self.module_path.join("::");
+        let qualified_name = self.qualified_name(&name);
+        let verify_function_arg = match &self.current_impl {
+            Some(target) => target.verify_function_arg(&name),
+            None => name.clone(),
+        };
 
         self.functions.push(FunctionInfo {
             name,
             file: self.file_path.clone(),
             start_line: span.start().line,
             end_line: span.end().line,
+            target: self.target.clone(),
             kind,
+            spec_openness,
+            return_type,
+            signature,
+            generics: signature::generics(&sig.generics),
+            where_clause: signature::where_clause(&sig.generics),
+            is_generic: self.is_generic(sig),
+            is_unsafe: sig.unsafety.is_some(),
+            is_async: sig.asyncness.is_some(),
+            has_body: body.is_some(),
+            in_verus_macro: self.in_verus_macro,
+            abi,
+            no_mangle,
+            export_name,
+            is_test,
+            takes_mut: mutation::takes_mut(sig),
+            uses_old: mutation::uses_old(sig),
             visibility,
-            context,
+            context: Some(self.context(context)),
+            impl_target: self.current_impl.clone(),
+            module_path,
+            qualified_name,
+            parent_function: self.current_function.clone(),
+            verify_function_arg,
+            callees,
+            spec_deps,
+            requires,
+            recommends,
+            ensures,
+            trivial_ensures,
+            specified,
+            decreases,
+            decreases_when,
+            decreases_via,
+            loops: function_loops,
+            loop_summary,
+            loop_isolation,
+            proof_blocks,
+            proof_lines,
+            assert_count,
+            quantifier_count,
+            trigger_count,
+            assume_count,
+            admit_count,
+            provers,
+            reveal_count,
+            max_fuel,
+            calc_count,
+            calc_lines,
+            by_blocks,
+            by_block_lines,
+            by_asserts,
+            opens_invariants,
+            returns,
+            no_unwind,
+            no_unwind_when,
+            ghost_params,
+            tracked_params,
+            returns_ghost: ghost::returns_ghost(sig),
+            ghost_wrappers_used,
+            ghost_locals: locals.ghost,
+            tracked_locals: locals.tracked,
+            type_names,
+            unresolved_calls,
+            is_recursive: None,
+            termination_unchecked,
+            trusted: trusted.into_iter().collect(),
+            depth: None,
+            attributes,
+            doc: self.options.show_docs.then(|| body::doc(attrs)).flatten(),
+            rlimit: body::verifier_attr_arg(attrs, "rlimit"),
+            spinoff_prover: verifier_attrs.iter().any(|a| a == "spinoff_prover"),
+            opaque: verifier_attrs.iter().any(|a| a == "opaque"),
+            reveals,
+            external_target: None,
+            group_members: None,
+            rule_count: None,
+            state_machine_role: None,
+            cfg,
+            local_macros: body
+                .map(macros::invoked)
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for FunctionVisitor {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        let span = node.span();
+        self.add_function(
+            span,
+            &node.sig,
+            &node.vis,
+            &node.attrs,
+            node.semi_token.is_none().then_some(&*node.block),
+            "standalone",
+        );
+
+        // Continue visiting nested items
+        self.in_function(&node.sig, |v| verus_syn::visit::visit_item_fn(v, node));
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        // Without methods, the body is still visited for nested functions
+        if self.options.include_methods {
+            let span = node.span();
+            self.add_function(
+                span,
+                &node.sig,
+                &node.vis,
+                &node.attrs,
+                node.semi_token.is_none().then_some(&node.block),
+                "impl",
+            );
+        }
+
+        // Continue visiting nested items
+        self.in_function(&node.sig, |v| verus_syn::visit::visit_impl_item_fn(v, node));
+    }
+
+    fn visit_item_foreign_mod(&mut self, node: &'ast verus_syn::ItemForeignMod) {
+        let previous = self.foreign_abi.replace(ffi::abi(&node.abi));
+        verus_syn::visit::visit_item_foreign_mod(self, node);
+        self.foreign_abi = previous;
+    }
+
+    fn visit_foreign_item_fn(&mut self, node: &'ast verus_syn::ForeignItemFn) {
+        self.add_function(
+            node.span(),
+            &node.sig,
+            &node.vis,
+            &node.attrs,
+            None,
+            "standalone",
+        );
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast TraitItemFn) {
+        // Without methods, the body is still visited for nested functions
+        if self.options.include_methods {
+            let span = node.span();
+
+            // Trait items don't have explicit visibility (they inherit from trait)
+            let vis = Visibility::Inherited;
+            self.add_function(
+                span,
+                &node.sig,
+                &vis,
+                &node.attrs,
+                node.default.as_ref(),
+                "trait",
+            );
+        }
+
+        // Continue visiting nested items
+        self.in_function(&node.sig, |v| {
+            verus_syn::visit::visit_trait_item_fn(v, node)
+        });
+    }
+
+    fn visit_broadcast_use(&mut self, node: &'ast verus_syn::BroadcastUse) {
+        let module = if self.module_path.is_empty() {
+            "crate".to_string()
+        } else {
+            self.module_path.join("::")
+        };
+        for path in &node.paths {
+            let info = BroadcastUseInfo {
+                path: body::path_to_string(&path.path),
+                file: self.file_path.clone(),
+                line: path.span().start().line,
+                function: self.current_function.clone(),
+            };
+            self.broadcast_uses.push((module.clone(), info));
+        }
+    }
+
+    fn visit_assume_specification(&mut self, node: &'ast verus_syn::AssumeSpecification) {
+        let path = body::path_to_string(&node.path);
+        let target = match &node.qself {
+            Some(qself) => {
+                let segments: Vec<&str> = path.split("::").collect();
+                let (trait_path, rest) = segments.split_at(qself.position);
+                let ty = pretty::pretty_type(&qself.ty);
+                if trait_path.is_empty() {
+                    format!("<{}>::{}", ty, rest.join("::"))
+                } else {
+                    format!("<{} as {}>::{}", ty, trait_path.join("::"), rest.join("::"))
+                }
+            }
+            None => path,
+        };
+        let (requires, ensures, returns) = specs::assumed(node);
+        self.assumed_specifications.push(AssumedSpecification {
+            target,
+            module_path: self.module_path.join("::"),
+            file: self.file_path.clone(),
+            line: node.span().start().line,
+            requires,
+            ensures,
+            returns,
+        });
+    }
+
+    fn visit_item_union(&mut self, node: &'ast verus_syn::ItemUnion) {
+        let fields = verus_syn::Fields::Named(node.fields.clone());
+        self.add_type(
+            node.span(),
+            node.ident.to_string(),
+            "union",
+            &verus_syn::DataMode::Default,
+            &[(String::new(), &fields)],
+            None,
+        );
+    }
+
+    fn visit_item_type(&mut self, node: &'ast verus_syn::ItemType) {
+        let name = node.ident.to_string();
+        self.add_type_alias(node.span(), name, &node.generics, Some(&node.ty), None);
+    }
+
+    fn visit_impl_item_type(&mut self, node: &'ast verus_syn::ImplItemType) {
+        let name = node.ident.to_string();
+        self.add_type_alias(node.span(), name, &node.generics, Some(&node.ty), None);
+    }
+
+    fn visit_trait_item_type(&mut self, node: &'ast verus_syn::TraitItemType) {
+        let bounds = (!node.bounds.is_empty()).then(|| pretty::pretty_type(&node.bounds));
+        let target = node.default.as_ref().map(|(_, ty)| ty);
+        let name = node.ident.to_string();
+        self.add_type_alias(node.span(), name, &node.generics, target, bounds);
+    }
+
+    fn visit_item_const(&mut self, node: &'ast verus_syn::ItemConst) {
+        let name = node.ident.to_string();
+        self.add_const(node.span(), name, "const", &node.mode, &node.ty);
+        self.in_const_block(|v| verus_syn::visit::visit_item_const(v, node));
+    }
+
+    fn visit_item_static(&mut self, node: &'ast verus_syn::ItemStatic) {
+        let kind = match node.mutability {
+            verus_syn::StaticMutability::Mut(_) => "static mut",
+            _ => "static",
+        };
+        let name = node.ident.to_string();
+        self.add_const(node.span(), name, kind, &node.mode, &node.ty);
+        self.in_const_block(|v| verus_syn::visit::visit_item_static(v, node));
+    }
+
+    fn visit_impl_item_const(&mut self, node: &'ast verus_syn::ImplItemConst) {
+        let name = node.ident.to_string();
+        self.add_const(node.span(), name, "const", &node.mode, &node.ty);
+        self.in_const_block(|v| verus_syn::visit::visit_impl_item_const(v, node));
+    }
+
+    fn visit_trait_item_const(&mut self, node: &'ast verus_syn::TraitItemConst) {
+        let name = node.ident.to_string();
+        self.add_const(node.span(), name, "const", &node.mode, &node.ty);
+        self.in_const_block(|v| verus_syn::visit::visit_trait_item_const(v, node));
+    }
+
+    fn visit_expr_const(&mut self, node: &'ast verus_syn::ExprConst) {
+        self.in_const_block(|v| verus_syn::visit::visit_expr_const(v, node));
+    }
+
+    fn visit_stmt_macro(&mut self, node: &'ast verus_syn::StmtMacro) {
+        self.visit_verus_blocks(&node.mac);
+        verus_syn::visit::visit_stmt_macro(self, node);
+    }
+
+    fn visit_expr_macro(&mut self, node: &'ast verus_syn::ExprMacro) {
+        self.visit_verus_blocks(&node.mac);
+        verus_syn::visit::visit_expr_macro(self, node);
+    }
+
+    fn visit_global(&mut self, node: &'ast verus_syn::Global) {
+        use pretty::{pretty, pretty_type};
+        // Printed by hand: verus_syn's printer drops the alignment value
+        let declaration = match &node.inner {
+            verus_syn::GlobalInner::SizeOf(s) => format!(
+                "global size_of {} == {};",
+                pretty_type(&s.type_),
+                pretty(&s.expr_lit)
+            ),
+            verus_syn::GlobalInner::Layout(l) => {
+                let (size, _, size_value) = &l.size;
+                let mut text = format!(
+                    "global layout {} is {} == {}",
+                    pretty_type(&l.type_),
+                    size,
+                    pretty(size_value)
+                );
+                if let Some((_, align, _, align_value)) = &l.align {
+                    text.push_str(&format!(", {} == {}", align, pretty(align_value)));
+                }
+                text.push(';');
+                text
+            }
+        };
+        self.globals.push(GlobalInfo {
+            declaration,
+            file: self.file_path.clone(),
+            line: node.span().start().line,
         });
     }
+
+    fn visit_item_broadcast_group(&mut self, node: &'ast verus_syn::ItemBroadcastGroup) {
+        self.add_broadcast_group(node);
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast verus_syn::ItemStruct) {
+        let attrs = body::verifier_attrs(&node.attrs);
+        if attrs.iter().any(|a| a == "external_type_specification") {
+            let target = node
+                .fields
+                .iter()
+                .next()
+                .map(|f| pretty::pretty_type(&f.ty));
+            self.add_external_item(
+                node.span(),
+                node.ident.to_string(),
+                &node.vis,
+                "standalone",
+                "external_spec",
+                target,
+            );
+        } else if attrs.iter().any(|a| a == "external") {
+            self.add_external_item(
+                node.span(),
+                node.ident.to_string(),
+                &node.vis,
+                "standalone",
+                "external",
+                None,
+            );
+        } else {
+            self.add_type(
+                node.span(),
+                node.ident.to_string(),
+                "struct",
+                &node.mode,
+                &[(String::new(), &node.fields)],
+                None,
+            );
+        }
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast verus_syn::ItemEnum) {
+        if body::verifier_attrs(&node.attrs)
+            .iter()
+            .any(|a| a == "external")
+        {
+            self.add_external_item(
+                node.span(),
+                node.ident.to_string(),
+                &node.vis,
+                "standalone",
+                "external",
+                None,
+            );
+            return;
+        }
+        let fields: Vec<(String, &verus_syn::Fields)> = node
+            .variants
+            .iter()
+            .map(|v| (format!("{}::", v.ident), &v.fields))
+            .collect();
+        self.add_type(
+            node.span(),
+            node.ident.to_string(),
+            "enum",
+            &node.mode,
+            &fields,
+            Some(node.variants.len()),
+        );
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast verus_syn::ItemImpl) {
+        if body::verifier_attrs(&node.attrs)
+            .iter()
+            .any(|a| a == "external")
+        {
+            // Nothing in an external impl is seen by Verus
+            self.add_external_item(
+                node.span(),
+                type_name(&node.self_ty),
+                &Visibility::Inherited,
+                "impl",
+                "external",
+                None,
+            );
+            return;
+        }
+        let target = ImplTarget {
+            self_type: Some(type_name(&node.self_ty)),
+            trait_name: node
+                .trait_
+                .as_ref()
+                .and_then(|(_, path, _)| path.segments.last().map(|seg| seg.ident.to_string())),
+        };
+        let context = ContextInfo {
+            kind: "impl".to_string(),
+            self_ty: Some(pretty::pretty_type(&node.self_ty)),
+            trait_path: node
+                .trait_
+                .as_ref()
+                .map(|(_, path, _)| pretty::pretty_type(path)),
+            generics: signature::generics(&node.generics),
+        };
+        let previous = self.current_impl.replace(target);
+        let previous_generic = std::mem::replace(
+            &mut self.impl_generic,
+            signature::has_type_params(&node.generics),
+        );
+        let previous_context = self.impl_context.replace(context);
+        let depth = self.cfg.len();
+        self.cfg.extend(cfg::conditions(&node.attrs));
+        let previous_isolation = self.loop_isolation;
+        self.loop_isolation = loops::isolation(&node.attrs).or(self.loop_isolation);
+        let previous_unchecked = self.termination_unchecked;
+        self.termination_unchecked |= specs::allows_no_decreases(&node.attrs);
+        verus_syn::visit::visit_item_impl(self, node);
+        self.termination_unchecked = previous_unchecked;
+        self.loop_isolation = previous_isolation;
+        self.cfg.truncate(depth);
+        self.impl_context = previous_context;
+        self.impl_generic = previous_generic;
+        self.current_impl = previous;
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast verus_syn::ItemTrait) {
+        let attrs = body::verifier_attrs(&node.attrs);
+        if attrs.iter().any(|a| a == "external_trait_specification") {
+            // The trait being specified is the bound of the
+            // `ExternalTraitSpecificationFor` associated type
+            let target = node.items.iter().find_map(|item| match item {
+                verus_syn::TraitItem::Type(ty) if ty.ident == "ExternalTraitSpecificationFor" => {
+                    Some(pretty::pretty_type(&ty.bounds))
+                }
+                _ => None,
+            });
+            self.add_external_item(
+                node.span(),
+                node.ident.to_string(),
+                &node.vis,
+                "trait",
+                "external_spec",
+                target,
+            );
+            return;
+        }
+        if attrs.iter().any(|a| a == "external") {
+            self.add_external_item(
+                node.span(),
+                node.ident.to_string(),
+                &node.vis,
+                "trait",
+                "external",
+                None,
+            );
+            return;
+        }
+        if self.options.include_traits {
+            self.add_trait(node);
+        }
+        let target = ImplTarget {
+            self_type: None,
+            trait_name: Some(node.ident.to_string()),
+        };
+        let context = ContextInfo {
+            kind: "trait".to_string(),
+            self_ty: None,
+            trait_path: Some(node.ident.to_string()),
+            generics: signature::generics(&node.generics),
+        };
+        let previous = self.current_impl.replace(target);
+        let previous_generic = std::mem::replace(
+            &mut self.impl_generic,
+            signature::has_type_params(&node.generics),
+        );
+        let previous_context = self.impl_context.replace(context);
+        let depth = self.cfg.len();
+        self.cfg.extend(cfg::conditions(&node.attrs));
+        let previous_isolation = self.loop_isolation;
+        self.loop_isolation = loops::isolation(&node.attrs).or(self.loop_isolation);
+        let previous_unchecked = self.termination_unchecked;
+        self.termination_unchecked |= specs::allows_no_decreases(&node.attrs);
+        verus_syn::visit::visit_item_trait(self, node);
+        self.termination_unchecked = previous_unchecked;
+        self.loop_isolation = previous_isolation;
+        self.cfg.truncate(depth);
+        self.impl_context = previous_context;
+        self.impl_generic = previous_generic;
+        self.current_impl = previous;
+    }
+
+    fn visit_item_mod(&mut self, node: &'ast verus_syn::ItemMod) {
+        // `mod foo;` declarations are handled by the file-based prefix of foo.rs
+        if node.content.is_none() {
+            return;
+        }
+        if body::verifier_attrs(&node.attrs)
+            .iter()
+            .any(|a| a == "external")
+        {
+            self.add_external_item(
+                node.span(),
+                node.ident.to_string(),
+                &node.vis,
+                "standalone",
+                "external",
+                None,
+            );
+            return;
+        }
+
+        if self.options.exclude_tests && cfg::is_test(&cfg::conditions(&node.attrs)) {
+            // Nothing in a test module is listed; its functions are only
+            // counted
+            let mut tests = FunctionVisitor::new(
+                self.file_path.clone(),
+                self.module_path.clone(),
+                self.options.clone(),
+            );
+            verus_syn::visit::visit_item_mod(&mut tests, node);
+            self.excluded_tests += tests.functions.len() + tests.excluded_tests;
+            return;
+        }
+
+        // Items inside a module are not nested in the surrounding impl/trait
+        let previous = self.current_impl.take();
+        let previous_generic = std::mem::take(&mut self.impl_generic);
+        let previous_context = self.impl_context.take();
+        self.module_path.push(node.ident.to_string());
+        let depth = self.cfg.len();
+        self.cfg.extend(cfg::conditions(&node.attrs));
+        let previous_isolation = self.loop_isolation;
+        self.loop_isolation = loops::isolation(&node.attrs).or(self.loop_isolation);
+        let previous_unchecked = self.termination_unchecked;
+        self.termination_unchecked |= specs::allows_no_decreases(&node.attrs);
+        verus_syn::visit::visit_item_mod(self, node);
+        self.termination_unchecked = previous_unchecked;
+        self.loop_isolation = previous_isolation;
+        self.cfg.truncate(depth);
+        self.module_path.pop();
+        self.impl_context = previous_context;
+        self.impl_generic = previous_generic;
+        self.current_impl = previous;
+    }
+
+    fn visit_item_use(&mut self, node: &'ast verus_syn::ItemUse) {
+        self.imports
+            .entry(self.module_path.join("::"))
+            .or_default()
+            .add_use(&node.tree, &self.module_path);
+    }
+
+    fn visit_item_macro(&mut self, node: &'ast ItemMacro) {
+        let known = node.mac.path.get_ident().is_some_and(|ident| {
+            ["macro_rules", "verus", "cfg_if"]
+                .iter()
+                .any(|k| ident == k)
+        });
+        let included = node.mac.path.is_ident("include") && self.include_file(&node.mac);
+        if self.options.exclude_tests && node.mac.path.is_ident("proptest") {
+            self.excluded_tests += macros::fn_count(node.mac.tokens.clone());
+            return;
+        }
+        let state_machine = ["state_machine", "tokenized_state_machine"]
+            .iter()
+            .any(|k| node.mac.path.is_ident(k))
+            && self.visit_state_machine(&node.mac);
+        if !known && !included && !state_machine {
+            self.add_unparsed_macro(&node.mac);
+            self.visit_verus_blocks(&node.mac);
+        }
+        if let Some(ident) = &node.mac.path.get_ident() {
+            if let (true, Some(name)) = (*ident == "macro_rules", &node.ident) {
+                self.macro_names.insert(name.to_string());
+                self.add_macro_def(node, name.to_string());
+            } else if *ident == "verus" {
+                // Parse verus! macro body as items
+                if let Ok(items) = verus_syn::parse2::<VerusMacroBody>(node.mac.tokens.clone()) {
+                    self.in_verus_macro(&items);
+                }
+            } else if *ident == "cfg_if" {
+                // Parse cfg_if! macro body
+                if let Ok(body) = verus_syn::parse2::<CfgIfMacroBody>(node.mac.tokens.clone()) {
+                    for (conditions, items) in body.branches {
+                        let depth = self.cfg.len();
+                        self.cfg.extend(conditions);
+                        for item in items {
+                            self.visit_item(&item);
+                        }
+                        self.cfg.truncate(depth);
+                    }
+                }
+            }
+        }
+        verus_syn::visit::visit_item_macro(self, node);
+    }
+
+    fn visit_impl_item_macro(&mut self, node: &'ast verus_syn::ImplItemMacro) {
+        self.add_unparsed_macro(&node.mac);
+    }
+
+    fn visit_trait_item_macro(&mut self, node: &'ast verus_syn::TraitItemMacro) {
+        self.add_unparsed_macro(&node.mac);
+    }
+}
+
+/// Mode keyword of a function or const (`spec(checked)`, `proof(axiom)`),
+/// empty when none is written
+fn mode_name(mode: &verus_syn::FnMode) -> &'static str {
+    use verus_syn::FnMode;
+
+    match mode {
+        FnMode::Spec(_) => "spec",
+        FnMode::SpecChecked(_) => "spec(checked)",
+        FnMode::Proof(_) => "proof",
+        FnMode::ProofAxiom(_) => "proof(axiom)",
+        FnMode::Exec(_) => "exec",
+        FnMode::Default => "",
+    }
+}
+
+/// Simple name of an impl's self type: `FieldElement51` for
+/// `impl<'a> Mul for &'a FieldElement51<T>`
+fn type_name(ty: &verus_syn::Type) -> String {
+    use verus_syn::Type;
+
+    match ty {
+        Type::Path(p) => p
+            .path
+            .segments
+            .last()
+            .map(|seg| seg.ident.to_string())
+            .unwrap_or_default(),
+        Type::Reference(r) => type_name(&r.elem),
+        Type::Paren(p) => type_name(&p.elem),
+        Type::Group(g) => type_name(&g.elem),
+        other => pretty::pretty_type(other),
+    }
+}
+
+/// Module path of a file derived from its location relative to `src/`
+/// (or to the scanned directory when there is no `src/` component):
+/// `src/backend/serial/u64/field_verus.rs` becomes
+/// `backend::serial::u64::field_verus`, while `lib.rs`, `main.rs` and
+/// `mod.rs` map to their parent module. The root of a cargo target, such as
+/// `src/bin/tool.rs`, is a crate root itself.
+fn module_prefix_for_file(file_path: &Path, root: &Path) -> Vec<String> {
+    if targets::is_root(file_path) {
+        return Vec::new();
+    }
+    let components: Vec<String> = file_path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    let relative: Vec<String> = match components.iter().rposition(|c| c == "src") {
+        Some(idx) => components[idx + 1..].to_vec(),
+        None => match file_path.strip_prefix(root) {
+            Ok(rel) if rel.components().next().is_some() => rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect(),
+            _ => components.last().cloned().into_iter().collect(),
+        },
+    };
+
+    let mut module_path = Vec::new();
+    let count = relative.len();
+    for (i, component) in relative.into_iter().enumerate() {
+        if i + 1 < count {
+            module_path.push(component);
+            continue;
+        }
+        let stem = component.strip_suffix(".rs").unwrap_or(&component);
+        let is_root = stem == "mod" || (count == 1 && (stem == "lib" || stem == "main"));
+        if !is_root {
+            module_path.push(stem.to_string());
+        }
+    }
+    module_path
+}
+
+/// Helper struct to parse verus! macro body as a list of items
+struct VerusMacroBody {
+    items: Vec<Item>,
+}
+
+impl verus_syn::parse::Parse for VerusMacroBody {
+    fn parse(input: verus_syn::parse::ParseStream) -> verus_syn::Result<Self> {
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            items.push(input.parse()?);
+        }
+        Ok(VerusMacroBody { items })
+    }
+}
+
+/// Helper struct to parse cfg_if! macro body
+struct CfgIfMacroBody {
+    /// Items of each branch, with the conditions under which the branch is
+    /// taken: its own and the negations of the earlier ones
+    branches: Vec<(Vec<String>, Vec<Item>)>,
+}
+
+impl verus_syn::parse::Parse for CfgIfMacroBody {
+    fn parse(input: verus_syn::parse::ParseStream) -> verus_syn::Result<Self> {
+        use verus_syn::Token;
+
+        let mut branches = Vec::new();
+        if !input.peek(Token![if]) {
+            return Ok(CfgIfMacroBody { branches });
+        }
+        // Conditions of the branches so far, all false in a later branch
+        let mut earlier: Vec<String> = Vec::new();
+        let mut conditional = true;
+        loop {
+            let mut conditions: Vec<String> = earlier.iter().map(|c| cfg::negate(c)).collect();
+            if conditional {
+                input.parse::<Token![if]>()?;
+                input.parse::<Token![#]>()?;
+                let attr_group: proc_macro2::Group = input.parse()?;
+                if let Some(condition) = cfg::branch_condition(attr_group.stream()) {
+                    conditions.push(condition.clone());
+                    earlier.push(condition);
+                }
+            }
+
+            let content;
+            verus_syn::braced!(content in input);
+            let mut items = Vec::new();
+            while !content.is_empty() {
+                items.push(content.parse()?);
+            }
+            branches.push((conditions, items));
+
+            // `else { .. }` is the last branch
+            if !conditional || !input.peek(Token![else]) {
+                break;
+            }
+            input.parse::<Token![else]>()?;
+            conditional = input.peek(Token![if]);
+        }
+
+        Ok(CfgIfMacroBody { branches })
+    }
+}
+
+/// Everything extracted from one file
+struct ParsedFile {
+    functions: Vec<FunctionInfo>,
+    broadcast_uses: Vec<(String, BroadcastUseInfo)>,
+    assumed_specifications: Vec<AssumedSpecification>,
+    globals: Vec<GlobalInfo>,
+    types: Vec<TypeInfo>,
+    type_aliases: Vec<TypeAliasInfo>,
+    traits: Vec<TraitInfo>,
+    consts: Vec<ConstInfo>,
+    macro_names: BTreeSet<String>,
+    /// Type names used by each type alias, for `ghost_wrappers_used`
+    alias_type_names: BTreeMap<String, BTreeSet<String>>,
+    unparsed_macros: Vec<UnparsedMacro>,
+    state_machines: Vec<StateMachineInfo>,
+    included_files: Vec<PathBuf>,
+    excluded_tests: usize,
+    imports: ImportTable,
+}
+
+fn parse_file(
+    file_path: &Path,
+    module: modules::FileModule,
+    options: &ExtractOptions,
+) -> Result<ParsedFile, String> {
+    let mut visitor = FunctionVisitor::new(
+        Some(file_path.to_string_lossy().to_string()),
+        module.path,
+        options.clone(),
+    );
+    visitor.target = targets::target_of(file_path);
+    let other_target = options
+        .only_target
+        .as_ref()
+        .is_some_and(|only| visitor.target.as_ref() != Some(only));
+
+    if !other_target {
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| format!("Failed to read file {}: {}", file_path.display(), e))?;
+
+        let syntax_tree = verus_syn::parse_file(&content)
+            .map_err(|e| format!("Failed to parse file {}: {}", file_path.display(), e))?;
+
+        visitor.cfg = module.cfg;
+        visitor.cfg.extend(cfg::conditions(&syntax_tree.attrs));
+        visitor.loop_isolation = loops::isolation(&syntax_tree.attrs).or(module.loop_isolation);
+        visitor.termination_unchecked =
+            module.termination_unchecked || specs::allows_no_decreases(&syntax_tree.attrs);
+        visitor.visit_file(&syntax_tree);
+    }
+    if options.exclude_tests && cfg::is_test(&visitor.cfg) {
+        // A `#[cfg(test)] mod tests;` file is left out like an inline one
+        let excluded = visitor.functions.len() + visitor.excluded_tests;
+        visitor = FunctionVisitor::new(None, Vec::new(), options.clone());
+        visitor.excluded_tests = excluded;
+    }
+
+    Ok(ParsedFile {
+        functions: visitor.functions,
+        broadcast_uses: visitor.broadcast_uses,
+        assumed_specifications: visitor.assumed_specifications,
+        globals: visitor.globals,
+        types: visitor.types,
+        type_aliases: visitor.type_aliases,
+        traits: visitor.traits,
+        consts: visitor.consts,
+        macro_names: visitor.macro_names,
+        alias_type_names: visitor.alias_type_names,
+        unparsed_macros: visitor.unparsed_macros,
+        state_machines: visitor.state_machines,
+        included_files: visitor.included_files,
+        excluded_tests: visitor.excluded_tests,
+        imports: visitor.imports,
+    })
+}
+
+/// The `.rs` files under `path`, in the same order on every run
+fn find_rust_files(path: &Path) -> Vec<PathBuf> {
+    WalkDir::new(path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+/// Functions extracted from all files under `args.path`
+struct Extraction {
+    functions: Vec<FunctionInfo>,
+    declarations: Declarations,
+    total_files: usize,
+}
+
+/// The module of a file from the `mod` declarations, and whether they
+/// reach it; unreached files get a module path from their location
+fn file_module(
+    module_map: &modules::ModuleMap,
+    file_path: &Path,
+    root: &Path,
+) -> (modules::FileModule, bool) {
+    match module_map.get(&modules::canonical(file_path)) {
+        Some(module) => (module.clone(), true),
+        None => (
+            modules::FileModule {
+                path: module_prefix_for_file(file_path, root),
+                ..Default::default()
+            },
+            false,
+        ),
+    }
+}
+
+/// Complete the functions with what is known of the crate: the macros and
+/// the type aliases it defines
+fn apply_crate_facts(
+    functions: &mut [FunctionInfo],
+    macro_names: &BTreeSet<String>,
+    alias_type_names: &BTreeMap<String, BTreeSet<String>>,
+) {
+    let wrapping_aliases = ghost::wrapping_aliases(alias_type_names);
+    for func in functions {
+        // Only the macros defined in the crate hide logic worth finding
+        func.local_macros.retain(|m| macro_names.contains(m));
+        func.ghost_wrappers_used |= func
+            .type_names
+            .iter()
+            .any(|name| wrapping_aliases.contains(name));
+    }
+}
+
+/// The last line of `--format ndjson`
+#[derive(Serialize)]
+struct NdjsonSummary<'a> {
+    schema_version: u32,
+    summary: &'a Summary,
+}
+
+/// `--count-only`: the number of functions and files, with the number of
+/// functions of each kind under `--show-kind`
+#[derive(Serialize, Default)]
+pub(crate) struct Counts {
+    schema_version: u32,
+    total_functions: usize,
+    total_files: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    functions_by_kind: Option<BTreeMap<String, usize>>,
+}
+
+impl Counts {
+    pub(crate) fn new(show_kind: bool) -> Self {
+        Counts {
+            schema_version: SCHEMA_VERSION,
+            functions_by_kind: show_kind.then(BTreeMap::new),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn add<'a>(&mut self, functions: impl IntoIterator<Item = &'a FunctionInfo>) {
+        for func in functions {
+            self.total_functions += 1;
+            if let (Some(by_kind), Some(kind)) = (&mut self.functions_by_kind, &func.kind) {
+                *by_kind.entry(kind.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    pub(crate) fn print(&self, format: &OutputFormat) {
+        match format {
+            OutputFormat::Json | OutputFormat::JsonFlat | OutputFormat::Ndjson => {
+                outln!("{}", output::json(self))
+            }
+            OutputFormat::Text | OutputFormat::Detailed => {
+                outln!(
+                    "{} functions in {} files",
+                    self.total_functions,
+                    self.total_files
+                );
+                for (kind, count) in self.functions_by_kind.iter().flatten() {
+                    outln!("{}: {}", kind, count);
+                }
+            }
+            OutputFormat::Dot
+            | OutputFormat::VerusArgs
+            | OutputFormat::Markdown
+            | OutputFormat::Sarif
+            | OutputFormat::Github
+            | OutputFormat::Sqlite
+            | OutputFormat::Shell
+            | OutputFormat::Ctags => unsupported_format("--count-only", format),
+        }
+    }
+}
+
+/// `--stats`
+fn print_stats(format: &OutputFormat, functions: &[FunctionInfo]) {
+    let stats = stats::Stats::of(functions);
+    match format {
+        OutputFormat::Json | OutputFormat::JsonFlat | OutputFormat::Ndjson => {
+            outln!("{}", output::json(&stats))
+        }
+        OutputFormat::Text | OutputFormat::Detailed => out!("{}", stats.render()),
+        OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown
+        | OutputFormat::Sarif
+        | OutputFormat::Github
+        | OutputFormat::Sqlite
+        | OutputFormat::Shell
+        | OutputFormat::Ctags => unsupported_format("--stats", format),
+    }
+}
+
+/// `--count-only`: count the functions file by file, like
+/// [`stream_functions`], without keeping them
+fn count_functions(args: &Args, options: &ExtractOptions) {
+    let mut counts = Counts::new(options.show_kind);
+    counts.total_files = for_each_file(args, options, |functions, _| counts.add(&functions));
+    counts.print(&args.format);
+}
+
+/// `--format ndjson`: print the functions of each file as soon as it is
+/// parsed, then the summary, holding one file at a time. What needs the
+/// whole crate is left out or taken from the file alone: callees are not
+/// resolved through imports, and `local_macros` and `ghost_wrappers_used`
+/// only see the macros and type aliases of the file.
+fn stream_functions(args: &Args, options: &ExtractOptions) {
+    let mut summary = Summary::default();
+    summary.total_files = for_each_file(args, options, |functions, declarations| {
+        for func in &functions {
+            outln!("{}", serde_json::to_string(func).unwrap());
+        }
+        summary.add(Summary::of(&functions, &declarations, 0));
+    });
+    let last = NdjsonSummary {
+        schema_version: SCHEMA_VERSION,
+        summary: &summary,
+    };
+    outln!("{}", serde_json::to_string(&last).unwrap());
+}
+
+/// Parse the files one at a time, handing `each` the functions and
+/// declarations of each file with the filters applied; returns the number
+/// of files
+fn for_each_file(
+    args: &Args,
+    options: &ExtractOptions,
+    mut each: impl FnMut(Vec<FunctionInfo>, Declarations),
+) -> usize {
+    if !args.path.exists() {
+        eprintln!("Error: Path does not exist: {}", args.path.display());
+        output::exit(1);
+    }
+
+    let mut print_file = |parsed: ParsedFile| {
+        let mut functions = parsed.functions;
+        functions.sort_by(|a, b| SortKey::File.compare(a, b));
+        apply_crate_facts(
+            &mut functions,
+            &parsed.macro_names,
+            &parsed.alias_type_names,
+        );
+        if options.only_assumed_specs {
+            functions.clear();
+        }
+        let declarations = Declarations {
+            types: parsed.types,
+            consts: parsed.consts,
+            unparsed_macros: parsed.unparsed_macros,
+            excluded_tests: parsed.excluded_tests,
+            spec_coverage: SpecCoverage::by_module(&functions),
+            ..Default::default()
+        };
+        if options.unspecified_only {
+            functions.retain(|f| f.specified == Some(false) && !f.is_intentionally_unspecified());
+        }
+        if let Some(ref kind) = options.kind {
+            functions.retain(|f| query::matches_kind(f, kind));
+        }
+        each(functions, declarations);
+    };
+
+    if args.path.is_file() {
+        let module = modules::FileModule {
+            path: module_prefix_for_file(&args.path, &args.path),
+            ..Default::default()
+        };
+        match parse_file(&args.path, module, options) {
+            Ok(parsed) => print_file(parsed),
+            Err(e) => {
+                eprintln!("Error parsing file: {}", e);
+                output::exit(1);
+            }
+        }
+        1
+    } else {
+        let rust_files = find_rust_files(&args.path);
+        let module_map = modules::build(&modules::crate_roots(&rust_files));
+        // Files pulled in with `include!` belong to the including module;
+        // the files including others are parsed once beforehand to skip them
+        let included: HashSet<PathBuf> = rust_files
+            .iter()
+            .filter(|path| fs::read_to_string(path).is_ok_and(|text| text.contains("include!")))
+            .filter_map(|path| {
+                let (module, _) = file_module(&module_map, path, &args.path);
+                parse_file(path, module, options).ok()
+            })
+            .flat_map(|parsed| parsed.included_files)
+            .map(|path| modules::canonical(&path))
+            .collect();
+        for file_path in &rust_files {
+            if included.contains(&modules::canonical(file_path)) {
+                continue;
+            }
+            let (module, _) = file_module(&module_map, file_path, &args.path);
+            match parse_file(file_path, module, options) {
+                Ok(parsed) => print_file(parsed),
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        }
+        rust_files.len()
+    }
+}
+
+fn extract(args: &Args, options: &ExtractOptions) -> Extraction {
+    if !args.path.exists() {
+        eprintln!("Error: Path does not exist: {}", args.path.display());
+        output::exit(1);
+    }
+
+    let mut functions = Vec::new();
+    let mut broadcast_uses = Vec::new();
+    let mut assumed_specifications = Vec::new();
+    let mut globals = Vec::new();
+    let mut types = Vec::new();
+    let mut type_aliases = Vec::new();
+    let mut traits = Vec::new();
+    let mut consts = Vec::new();
+    let mut macro_names = BTreeSet::new();
+    let mut alias_type_names = BTreeMap::new();
+    let mut unparsed_macros = Vec::new();
+    let mut state_machines = Vec::new();
+    let mut unreachable_files = Vec::new();
+    let mut excluded_tests = 0;
+    let mut imports = ImportTable::new();
+    let mut total_files = 0;
+
+    if args.path.is_file() {
+        match parse_file(
+            &args.path,
+            modules::FileModule {
+                path: module_prefix_for_file(&args.path, &args.path),
+                ..Default::default()
+            },
+            options,
+        ) {
+            Ok(parsed) => {
+                let declares = !parsed.assumed_specifications.is_empty()
+                    || !parsed.globals.is_empty()
+                    || !parsed.types.is_empty()
+                    || !parsed.type_aliases.is_empty()
+                    || !parsed.traits.is_empty()
+                    || !parsed.consts.is_empty()
+                    || !parsed.unparsed_macros.is_empty()
+                    || !parsed.state_machines.is_empty();
+                if !parsed.functions.is_empty() || declares {
+                    functions.extend(parsed.functions);
+                    total_files = 1;
+                }
+                broadcast_uses = parsed.broadcast_uses;
+                assumed_specifications = parsed.assumed_specifications;
+                globals = parsed.globals;
+                types = parsed.types;
+                type_aliases = parsed.type_aliases;
+                traits = parsed.traits;
+                consts = parsed.consts;
+                macro_names = parsed.macro_names;
+                alias_type_names = parsed.alias_type_names;
+                unparsed_macros = parsed.unparsed_macros;
+                state_machines = parsed.state_machines;
+                excluded_tests = parsed.excluded_tests;
+                imports = parsed.imports;
+            }
+            Err(e) => {
+                eprintln!("Error parsing file: {}", e);
+                output::exit(1);
+            }
+        }
+    } else {
+        let rust_files = find_rust_files(&args.path);
+        total_files = rust_files.len();
+
+        // Without a crate root the module paths come from the file layout
+        let module_map = modules::build(&modules::crate_roots(&rust_files));
+
+        let parsed_files: Vec<(PathBuf, bool, ParsedFile)> = rust_files
+            .into_iter()
+            .filter_map(|file_path| {
+                let (module, reachable) = file_module(&module_map, &file_path, &args.path);
+                match parse_file(&file_path, module, options) {
+                    Ok(parsed) => Some((file_path, reachable, parsed)),
+                    Err(e) => {
+                        eprintln!("Warning: {}", e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        // Files pulled in with `include!` belong to the including module,
+        // not to a module of their own
+        let included: HashSet<PathBuf> = parsed_files
+            .iter()
+            .flat_map(|(_, _, parsed)| &parsed.included_files)
+            .map(|path| modules::canonical(path))
+            .collect();
+
+        for (file_path, reachable, parsed) in parsed_files {
+            if included.contains(&modules::canonical(&file_path)) {
+                continue;
+            }
+            if !reachable && !module_map.is_empty() {
+                unreachable_files.push(file_path.to_string_lossy().to_string());
+            }
+            functions.extend(parsed.functions);
+            broadcast_uses.extend(parsed.broadcast_uses);
+            assumed_specifications.extend(parsed.assumed_specifications);
+            globals.extend(parsed.globals);
+            types.extend(parsed.types);
+            type_aliases.extend(parsed.type_aliases);
+            traits.extend(parsed.traits);
+            consts.extend(parsed.consts);
+            macro_names.extend(parsed.macro_names);
+            for (alias, names) in parsed.alias_type_names {
+                alias_type_names
+                    .entry(alias)
+                    .or_insert_with(BTreeSet::new)
+                    .extend(names);
+            }
+            unparsed_macros.extend(parsed.unparsed_macros);
+            state_machines.extend(parsed.state_machines);
+            excluded_tests += parsed.excluded_tests;
+            imports::merge_tables(&mut imports, parsed.imports);
+        }
+    }
+
+    // The output must not depend on the order the files were walked or
+    // parsed in
+    functions.sort_by(|a, b| SortKey::File.compare(a, b));
+    unreachable_files.sort();
+    imports::resolve_callees(&mut functions, &imports);
+    apply_crate_facts(&mut functions, &macro_names, &alias_type_names);
+    let mut broadcast_uses = resolve_broadcast_uses(broadcast_uses, &functions, &imports);
+    // Targets in `<T as Trait>::f` form name no module path to resolve
+    let targets = assumed_specifications
+        .iter_mut()
+        .filter(|s| !s.target.starts_with('<'))
+        .map(|s| (s.module_path.as_str(), &mut s.target));
+    imports::resolve_paths(targets, &functions, &imports);
+    assumed_specifications.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    globals.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    types.sort_by(|a, b| (&a.file, a.start_line).cmp(&(&b.file, b.start_line)));
+    type_aliases.sort_by(|a, b| (&a.file, a.start_line).cmp(&(&b.file, b.start_line)));
+    traits.sort_by(|a, b| (&a.file, a.start_line).cmp(&(&b.file, b.start_line)));
+    consts.sort_by(|a, b| (&a.file, a.start_line).cmp(&(&b.file, b.start_line)));
+    unparsed_macros.sort_by(|a, b| (&a.file, a.start_line).cmp(&(&b.file, b.start_line)));
+    state_machines.sort_by(|a, b| (&a.file, a.start_line).cmp(&(&b.file, b.start_line)));
+    if args.warn_unparsed_macros {
+        for m in &unparsed_macros {
+            eprintln!(
+                "Warning: {}! at {}:{} was not expanded; items it generates are missing",
+                m.path,
+                m.file.as_deref().unwrap_or("<input>"),
+                m.start_line
+            );
+        }
+    }
+    if options.only_assumed_specs {
+        // The functions were only needed to resolve the targets
+        functions.clear();
+        broadcast_uses.clear();
+        globals.clear();
+        types.clear();
+        type_aliases.clear();
+        traits.clear();
+        consts.clear();
+        unparsed_macros.clear();
+        state_machines.clear();
+        unreachable_files.clear();
+    }
+    let spec_coverage = SpecCoverage::by_module(&functions);
+    if options.unspecified_only {
+        functions.retain(|f| f.specified == Some(false) && !f.is_intentionally_unspecified());
+    }
+    if let Some(ref kind) = options.kind {
+        functions.retain(|f| query::matches_kind(f, kind));
+    }
+    if options.show_calls {
+        let recursive = CallGraph::new(&functions, false).recursive_functions();
+        for (func, is_recursive) in functions.iter_mut().zip(recursive) {
+            func.is_recursive = Some(is_recursive);
+        }
+    }
+
+    Extraction {
+        functions,
+        declarations: Declarations {
+            broadcast_uses,
+            assumed_specifications,
+            globals,
+            types,
+            type_aliases,
+            traits,
+            consts,
+            unparsed_macros,
+            state_machines,
+            unreachable_files,
+            excluded_tests,
+            spec_coverage,
+        },
+        total_files,
+    }
+}
+
+/// Group `broadcast use` statements by module, resolving their paths
+/// through the module's imports
+fn resolve_broadcast_uses(
+    uses: Vec<(String, BroadcastUseInfo)>,
+    functions: &[FunctionInfo],
+    imports: &ImportTable,
+) -> BroadcastUses {
+    let mut by_module = BroadcastUses::new();
+    for (module, info) in uses {
+        by_module.entry(module).or_default().push(info);
+    }
+    let paths = by_module.iter_mut().flat_map(|(module, uses)| {
+        let module_path = if module == "crate" {
+            ""
+        } else {
+            module.as_str()
+        };
+        uses.iter_mut()
+            .map(move |info| (module_path, &mut info.path))
+    });
+    imports::resolve_paths(paths, functions, imports);
+    for uses in by_module.values_mut() {
+        uses.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    }
+    by_module
+}
+
+/// The names of `--format text`, one per line or NUL-terminated with
+/// `--print0`, or of `--format shell` quoted on one line
+fn print_names<'a>(output: &OutputOptions, names: impl IntoIterator<Item = &'a str>) {
+    if matches!(output.format, OutputFormat::Shell) {
+        outln!("{}", shell::join(names));
+    } else if output.print0 {
+        for name in names {
+            out!("{}\0", name);
+        }
+    } else {
+        for name in names {
+            outln!("{}", name);
+        }
+    }
+}
+
+/// `--format verus-args`: the arguments quoted for the shell and joined with
+/// spaces, or each NUL-terminated with `--print0`
+fn print_verus_args(verus_args: &[String], print0: bool) {
+    if print0 {
+        for arg in verus_args {
+            out!("{}\0", arg);
+        }
+    } else {
+        outln!("{}", shell::join(verus_args.iter().map(String::as_str)));
+    }
+}
+
+/// Print a list of functions in the requested format, with the
+/// declarations of their modules (`broadcast use`, `assume_specification`,
+/// `global`); `query` holds the
+/// unresolved callees and external dependencies when the list is the
+/// result of a graph query
+fn print_functions(
+    output: &OutputOptions,
+    functions: &[FunctionInfo],
+    declarations: &Declarations,
+    total_files: usize,
+    query: Option<QueryExtras>,
+) {
+    let mut sorted = functions.to_vec();
+    if let Some(key) = output.sort {
+        sorted.sort_by(|a, b| key.compare(a, b));
+    }
+    if output.reverse {
+        sorted.reverse();
+    }
+    let functions = &sorted[..];
+    let format = &output.format;
+    let warn_unresolved = matches!(format, OutputFormat::Text | OutputFormat::Detailed);
+    let (unresolved, external_deps) = match query {
+        Some(q) => (Some(q.unresolved_callees), q.external_deps),
+        None => (None, None),
+    };
+    match format {
+        OutputFormat::Json => {
+            let mut summary = Summary::of(functions, declarations, total_files);
+            let (functions_by_file, functions_by_module) = match output.group_by {
+                Some(GroupBy::Module) => {
+                    let modules = ModuleFunctions::by_module(functions);
+                    summary.total_modules = Some(modules.len());
+                    (None, Some(modules))
+                }
+                None => (None, None),
+                Some(GroupBy::File) => {
+                    let mut functions_by_file: BTreeMap<String, Vec<FunctionInfo>> =
+                        BTreeMap::new();
+                    for func in functions {
+                        if let Some(ref file) = func.file {
+                            functions_by_file
+                                .entry(file.clone())
+                                .or_default()
+                                .push(func.clone());
+                        }
+                    }
+                    (Some(functions_by_file), None)
+                }
+            };
+            let output = ParsedOutput {
+                schema_version: SCHEMA_VERSION,
+                functions: functions.to_vec(),
+                functions_by_file,
+                functions_by_module,
+                summary,
+                unresolved_callees: unresolved.clone(),
+                external_deps: external_deps.clone(),
+                declarations: declarations.clone(),
+            };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Markdown => {
+            let summary = Summary::of(functions, declarations, total_files);
+            out!(
+                "{}",
+                markdown::render(
+                    functions,
+                    &summary,
+                    output.group_by.as_ref().unwrap_or(&GroupBy::Module)
+                )
+            );
+        }
+        OutputFormat::Ctags => out!("{}", ctags::render(functions, declarations)),
+        OutputFormat::Sqlite => {
+            let summary = Summary::of(functions, declarations, total_files);
+            let db = output
+                .database
+                .as_deref()
+                .expect("clap requires --output with --format sqlite");
+            if let Err(e) = sqlite::write(db, functions, &summary, output.append) {
+                eprintln!("Error: {}", e);
+                output::exit(1);
+            }
+        }
+        OutputFormat::Github if output.listing.is_some() => {
+            let listing = output.listing.unwrap_or_default();
+            for func in functions {
+                let message = format!("`{}` is listed by {}", func.qualified_name, listing);
+                outln!("{}", github::notice(listing, func, &message));
+            }
+        }
+        OutputFormat::Sarif | OutputFormat::Github => {
+            let found = findings::function_findings(functions);
+            print_findings(format, &findings::FUNCTION_RULES, &found);
+        }
+        OutputFormat::Ndjson => {
+            for func in functions {
+                outln!("{}", serde_json::to_string(func).unwrap());
+            }
+            let summary = Summary::of(functions, declarations, total_files);
+            let last = NdjsonSummary {
+                schema_version: SCHEMA_VERSION,
+                summary: &summary,
+            };
+            outln!("{}", serde_json::to_string(&last).unwrap());
+        }
+        OutputFormat::JsonFlat => outln!("{}", output::json(functions)),
+        OutputFormat::Text | OutputFormat::Shell if output.bare_names => {
+            // Bare names; a name may stand for several functions
+            let mut by_name: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+            for func in functions {
+                by_name
+                    .entry(func.name.as_str())
+                    .or_default()
+                    .insert(func.qualified_name.as_str());
+            }
+            for (name, qualified) in &by_name {
+                if qualified.len() > 1 {
+                    let all: Vec<&str> = qualified.iter().copied().collect();
+                    eprintln!(
+                        "Warning: {} is ambiguous ({} functions: {})",
+                        name,
+                        qualified.len(),
+                        all.join(", ")
+                    );
+                }
+            }
+            print_names(output, by_name.into_keys());
+        }
+        OutputFormat::Text | OutputFormat::Shell => {
+            // Qualified names
+            let mut names: Vec<_> = functions
+                .iter()
+                .map(|f| f.qualified_name.as_str())
+                .collect();
+            let assumed = &declarations.assumed_specifications;
+            names.extend(assumed.iter().map(|s| s.target.as_str()));
+            // By name, unless `--sort` ordered the functions
+            if output.sort.is_none() {
+                names.sort();
+                if output.reverse {
+                    names.reverse();
+                }
+            }
+            let mut seen = HashSet::new();
+            names.retain(|name| seen.insert(*name));
+            print_names(output, names);
+        }
+        OutputFormat::Detailed => {
+            let (groups, listed): (Vec<&FunctionInfo>, Vec<&FunctionInfo>) =
+                functions.iter().partition(|f| f.is_broadcast_group());
+            for func in &listed {
+                out!("{}", func.name);
+                if let Some(ref kind) = func.kind {
+                    match func.spec_openness {
+                        Some(ref openness) => out!(" [{} {}]", openness, kind),
+                        None => out!(" [{}]", kind),
+                    }
+                }
+                if func.is_unsafe {
+                    out!(" [unsafe]");
+                }
+                if !func.has_body && func.is_verified_function() {
+                    out!(" [declaration]");
+                }
+                if let Some(ref abi) = func.abi {
+                    out!(" [extern \"{}\"]", abi);
+                }
+                if func.no_mangle {
+                    out!(" (no_mangle)");
+                }
+                if let Some(ref symbol) = func.export_name {
+                    out!(" (export_name {})", symbol);
+                }
+                if let Some(ref target) = func.external_target {
+                    out!(" (specifies {})", target);
+                }
+                if let Some(rules) = func.rule_count {
+                    out!(" ({} rules)", rules);
+                }
+                if let Some(ref role) = func.state_machine_role {
+                    out!(" ({})", role);
+                }
+                if let Some(ref parent) = func.parent_function {
+                    out!(" (nested in {})", parent);
+                }
+                if func.ghost_wrappers_used {
+                    out!(" (ghost wrappers)");
+                }
+                if let Some(ref opens) = func.opens_invariants {
+                    out!(" [opens_invariants {}]", opens);
+                }
+                if let Some(ref vis) = func.visibility {
+                    out!(" ({})", vis);
+                }
+                if let Some(ref file) = func.file {
+                    out!(" @ {}:{}:{}", file, func.start_line, func.end_line);
+                }
+                if let Some(ref context) = func.context {
+                    out!(" in {}", context.kind());
+                }
+                if let Some(depth) = func.depth {
+                    out!(" at depth {}", depth);
+                }
+                if !func.trusted.is_empty() {
+                    out!(" (trusted: {})", func.trusted.join(", "));
+                }
+                if func.trivial_ensures == Some(true) {
+                    out!(" (trivial ensures)");
+                }
+                if func.opaque {
+                    out!(" (opaque)");
+                }
+                if func.loop_isolation == Some(false) {
+                    out!(" (loop_isolation(false))");
+                }
+                if let Some(ref rlimit) = func.rlimit {
+                    out!(" (rlimit {})", rlimit);
+                }
+                if func.spinoff_prover {
+                    out!(" (spinoff_prover)");
+                }
+                outln!();
+                for line in func.doc.iter().flat_map(|doc| doc.lines()) {
+                    outln!("    ///{}{}", if line.is_empty() { "" } else { " " }, line);
+                }
+                for attribute in &func.attributes {
+                    outln!("    {}", attribute);
+                }
+                if !func.cfg.is_empty() {
+                    outln!("    cfg: {}", func.cfg.join(", "));
+                }
+                if !func.local_macros.is_empty() {
+                    outln!("    macros: {}", func.local_macros.join(", "));
+                }
+                if let Some(ref signature) = func.signature {
+                    outln!("    {}", signature);
+                }
+                for clause in func.requires.iter().flatten() {
+                    outln!("    requires {}", clause);
+                }
+                for clause in func.recommends.iter().flatten() {
+                    outln!("    recommends {}", clause);
+                }
+                for clause in func.ensures.iter().flatten() {
+                    outln!("    ensures {}", clause);
+                }
+                if let Some(ref returns) = func.returns {
+                    outln!("    returns {}", returns);
+                }
+                match (func.no_unwind, &func.no_unwind_when) {
+                    (_, Some(when)) => outln!("    no_unwind when {}", when),
+                    (Some(true), None) => outln!("    no_unwind"),
+                    _ => {}
+                }
+                if !func.ghost_params.is_empty() {
+                    outln!("    ghost params: {}", func.ghost_params.join(", "));
+                }
+                if !func.tracked_params.is_empty() {
+                    outln!("    tracked params: {}", func.tracked_params.join(", "));
+                }
+                if func.returns_ghost {
+                    outln!("    returns ghost");
+                }
+                if func.ghost_locals + func.tracked_locals > 0 {
+                    outln!(
+                        "    ghost locals: {}, tracked locals: {}",
+                        func.ghost_locals,
+                        func.tracked_locals
+                    );
+                }
+                if let Some(ref generics) = func.generics {
+                    outln!("    generics {}", generics);
+                }
+                if let Some(ref predicates) = func.where_clause {
+                    outln!("    where {}", predicates);
+                }
+                if let Some(measures) = func.decreases.as_ref().filter(|d| !d.is_empty()) {
+                    out!("    decreases {}", measures.join(", "));
+                    if let Some(ref when) = func.decreases_when {
+                        out!(" when {}", when);
+                    }
+                    if let Some(ref via) = func.decreases_via {
+                        out!(" via {}", via);
+                    }
+                    outln!();
+                }
+                for revealed in func.reveals.iter().flatten() {
+                    outln!("    reveals {}", revealed);
+                }
+                if let Some(ref summary) = func.loop_summary {
+                    if summary.with_invariants + summary.without_invariants > 0 {
+                        outln!(
+                            "    loops: {} with invariants, {} without",
+                            summary.with_invariants,
+                            summary.without_invariants
+                        );
+                    }
+                }
+                if let Some(asserts @ 1..) = func.assert_count {
+                    outln!("    asserts: {}", asserts);
+                }
+                if let Some(quantifiers @ 1..) = func.quantifier_count {
+                    outln!(
+                        "    quantifiers: {} ({} triggers)",
+                        quantifiers,
+                        func.trigger_count.unwrap_or(0)
+                    );
+                }
+                if let Some(provers) = func.provers.as_ref().filter(|p| !p.is_empty()) {
+                    let counts: Vec<String> = provers
+                        .iter()
+                        .map(|(prover, count)| format!("{} {}", count, prover))
+                        .collect();
+                    outln!("    provers: {}", counts.join(", "));
+                }
+                if let Some(reveals @ 1..) = func.reveal_count {
+                    out!("    reveal statements: {}", reveals);
+                    if let Some(fuel) = func.max_fuel {
+                        out!(" (fuel up to {})", fuel);
+                    }
+                    outln!();
+                }
+                if let (Some(blocks @ 1..), Some(lines)) = (func.by_blocks, func.by_block_lines) {
+                    outln!("    by blocks: {} spanning {} lines", blocks, lines);
+                }
+                for asserted in &func.by_asserts {
+                    outln!("    assert {} by", asserted);
+                }
+                if !func.calc_lines.is_empty() {
+                    let lines: Vec<String> =
+                        func.calc_lines.iter().map(|l| l.to_string()).collect();
+                    outln!("    calc!: {} (lines {})", lines.len(), lines.join(", "));
+                }
+                if func.proof_debt().unwrap_or(0) > 0 {
+                    outln!(
+                        "    proof debt: {} assume, {} admit",
+                        func.assume_count.unwrap_or(0),
+                        func.admit_count.unwrap_or(0)
+                    );
+                }
+                if let (Some(blocks @ 1..), Some(lines)) = (func.proof_blocks, func.proof_lines) {
+                    outln!("    proof blocks: {} spanning {} lines", blocks, lines);
+                }
+                for l in func.loops.iter().flatten() {
+                    outln!(
+                        "    {} loop at lines {}-{}",
+                        l.kind,
+                        l.start_line,
+                        l.end_line
+                    );
+                    for clause in &l.invariant {
+                        outln!("        invariant {}", clause);
+                    }
+                    for clause in &l.invariant_except_break {
+                        outln!("        invariant_except_break {}", clause);
+                    }
+                    for clause in &l.invariant_ensures {
+                        outln!("        invariant_ensures {}", clause);
+                    }
+                    for clause in &l.ensures {
+                        outln!("        ensures {}", clause);
+                    }
+                }
+            }
+            if !declarations.types.is_empty() {
+                outln!("\nTypes:");
+                for ty in &declarations.types {
+                    out!("{} [{} {}]", ty.qualified_name, ty.mode, ty.kind);
+                    if let Some(ref file) = ty.file {
+                        out!(" @ {}:{}:{}", file, ty.start_line, ty.end_line);
+                    }
+                    match ty.variant_count {
+                        Some(variants) => {
+                            outln!(" ({} variants, {} fields)", variants, ty.field_count)
+                        }
+                        None => outln!(" ({} fields)", ty.field_count),
+                    }
+                    if !ty.ghost_fields.is_empty() {
+                        outln!("    ghost fields: {}", ty.ghost_fields.join(", "));
+                    }
+                }
+            }
+            if !declarations.type_aliases.is_empty() {
+                outln!("\nType aliases:");
+                for alias in &declarations.type_aliases {
+                    out!(
+                        "{}{}",
+                        alias.qualified_name,
+                        alias.generics.as_deref().unwrap_or("")
+                    );
+                    if let Some(ref bounds) = alias.bounds {
+                        out!(": {}", bounds);
+                    }
+                    if let Some(ref target) = alias.target {
+                        out!(" = {}", target);
+                    }
+                    out!(" [{}]", alias.context.kind);
+                    if let Some(ref file) = alias.file {
+                        out!(" @ {}:{}:{}", file, alias.start_line, alias.end_line);
+                    }
+                    outln!();
+                }
+            }
+            if !declarations.traits.is_empty() {
+                outln!("\nTraits:");
+                for tr in &declarations.traits {
+                    out!("{}", tr.qualified_name);
+                    if let Some(ref file) = tr.file {
+                        out!(" @ {}:{}:{}", file, tr.start_line, tr.end_line);
+                    }
+                    outln!();
+                    for method in &tr.methods {
+                        out!("    {}", method.name);
+                        if method.has_default {
+                            out!(" (default)");
+                        }
+                        if !method.spec_clauses.is_empty() {
+                            out!(" [{}]", method.spec_clauses.join(", "));
+                        }
+                        outln!();
+                    }
+                }
+            }
+            if !declarations.consts.is_empty() {
+                outln!("\nConsts:");
+                for c in &declarations.consts {
+                    out!("{} [", c.qualified_name);
+                    if let Some(ref mode) = c.mode {
+                        out!("{} ", mode);
+                    }
+                    out!("{}]: {}", c.kind, c.ty);
+                    if let Some(ref file) = c.file {
+                        out!(" @ {}:{}:{}", file, c.start_line, c.end_line);
+                    }
+                    outln!();
+                }
+            }
+            if !declarations.state_machines.is_empty() {
+                outln!("\nState machines:");
+                for machine in &declarations.state_machines {
+                    out!(
+                        "{}{} [{}]",
+                        machine.qualified_name,
+                        machine.generics.as_deref().unwrap_or(""),
+                        machine.kind
+                    );
+                    if let Some(ref file) = machine.file {
+                        out!(" @ {}:{}:{}", file, machine.start_line, machine.end_line);
+                    }
+                    outln!();
+                    for field in &machine.fields {
+                        out!("    {}: {}", field.name, field.ty);
+                        if let Some(ref sharding) = field.sharding {
+                            out!(" (sharding {})", sharding);
+                        }
+                        outln!();
+                    }
+                }
+            }
+            if !declarations.unparsed_macros.is_empty() {
+                outln!("\nUnparsed macros:");
+                for m in &declarations.unparsed_macros {
+                    out!("{}!", m.path);
+                    if let Some(ref file) = m.file {
+                        out!(" @ {}:{}:{}", file, m.start_line, m.end_line);
+                    }
+                    outln!();
+                }
+            }
+            if !declarations.unreachable_files.is_empty() {
+                outln!("\nUnreachable files (module paths guessed):");
+                for file in &declarations.unreachable_files {
+                    outln!("{}", file);
+                }
+            }
+            if !declarations.globals.is_empty() {
+                outln!("\nGlobals:");
+                for global in &declarations.globals {
+                    out!("{}", global.declaration);
+                    if let Some(ref file) = global.file {
+                        out!(" @ {}:{}", file, global.line);
+                    }
+                    outln!();
+                }
+            }
+            if !declarations.broadcast_uses.is_empty() {
+                outln!("\nBroadcast uses:");
+                for (module, uses) in &declarations.broadcast_uses {
+                    outln!("{}:", module);
+                    for u in uses {
+                        out!("    {}", u.path);
+                        if let Some(ref file) = u.file {
+                            out!(" @ {}:{}", file, u.line);
+                        }
+                        if let Some(ref function) = u.function {
+                            out!(" in {}", function);
+                        }
+                        outln!();
+                    }
+                }
+            }
+            if !declarations.assumed_specifications.is_empty() {
+                outln!("\nAssumed specifications:");
+                for spec in &declarations.assumed_specifications {
+                    out!("{}", spec.target);
+                    if let Some(ref file) = spec.file {
+                        out!(" @ {}:{}", file, spec.line);
+                    }
+                    outln!();
+                    for clause in &spec.requires {
+                        outln!("    requires {}", clause);
+                    }
+                    for clause in &spec.ensures {
+                        outln!("    ensures {}", clause);
+                    }
+                    if let Some(ref returns) = spec.returns {
+                        outln!("    returns {}", returns);
+                    }
+                }
+            }
+            if !groups.is_empty() {
+                outln!("\nBroadcast groups:");
+                for group in &groups {
+                    out!("{}", group.qualified_name);
+                    if let Some(ref file) = group.file {
+                        out!(" @ {}:{}:{}", file, group.start_line, group.end_line);
+                    }
+                    outln!();
+                    for member in group.group_members.iter().flatten() {
+                        outln!("    {}", member);
+                    }
+                }
+            }
+            outln!(
+                "\nSummary: {} functions in {} files",
+                listed.len(),
+                total_files
+            );
+            let summary = Summary::of(functions, declarations, total_files);
+            if summary.trusted_functions > 0 {
+                outln!("Trusted functions: {}", summary.trusted_functions);
+            }
+            if summary.unsafe_functions > 0 {
+                outln!("Unsafe functions: {}", summary.unsafe_functions);
+            }
+            if summary.async_functions > 0 {
+                outln!("Async functions: {}", summary.async_functions);
+            }
+            if summary.excluded_tests > 0 {
+                outln!("Excluded test functions: {}", summary.excluded_tests);
+            }
+            if summary.total_types > 0 {
+                outln!("Types: {}", summary.total_types);
+            }
+            if summary.unparsed_macros > 0 {
+                outln!("Unparsed macros: {}", summary.unparsed_macros);
+            }
+            if !summary.consts_by_module.is_empty() {
+                let counts: Vec<String> = summary
+                    .consts_by_module
+                    .iter()
+                    .map(|(module, count)| format!("{} {}", count, module))
+                    .collect();
+                outln!("Consts: {}", counts.join(", "));
+            }
+            if !summary.spec_coverage.is_empty() {
+                outln!("Spec coverage (specified exec functions):");
+                for (module, coverage) in &summary.spec_coverage {
+                    out!(
+                        "    {}: {}/{} ({:.1}%)",
+                        module,
+                        coverage.specified,
+                        coverage.exec_functions,
+                        coverage.percent_specified
+                    );
+                    if coverage.external > 0 {
+                        out!(", {} external", coverage.external);
+                    }
+                    outln!();
+                }
+            }
+            if !summary.verus_coverage.is_empty() {
+                outln!("Inside verus!:");
+                for (module, coverage) in &summary.verus_coverage {
+                    outln!(
+                        "    {}: {}/{} ({:.1}%)",
+                        module,
+                        coverage.in_verus_macro,
+                        coverage.functions,
+                        coverage.percent_in_verus_macro
+                    );
+                }
+            }
+            if !summary.spec_openness.is_empty() {
+                let counts: Vec<String> = summary
+                    .spec_openness
+                    .iter()
+                    .map(|(openness, count)| format!("{} {}", count, openness))
+                    .collect();
+                outln!("Spec functions: {}", counts.join(", "));
+            }
+        }
+        OutputFormat::Dot => {
+            let graph = CallGraph::new(functions, true);
+            let all: Vec<usize> = (0..functions.len()).collect();
+            out!("{}", dot::render(functions, &graph, &all));
+        }
+        OutputFormat::VerusArgs => {
+            print_verus_args(&verus_args::verus_args(functions), output.print0);
+        }
+    }
+
+    if warn_unresolved {
+        for u in unresolved.iter().flatten() {
+            eprintln!("Warning: unresolved callee {} in {}", u.callee, u.caller);
+        }
+        for func in external_deps.iter().flatten() {
+            eprintln!(
+                "Note: depends on {} outside the module",
+                func.qualified_name
+            );
+        }
+    }
+}
+
+/// Functions matching a user-supplied name: an exact qualified name, or
+/// any function whose qualified name ends with it
+fn find_roots(graph: &CallGraph, functions: &[FunctionInfo], name: &str) -> Vec<usize> {
+    let roots = graph.resolve(name);
+    if roots.is_empty() {
+        eprintln!("Error: No function named {}", name);
+        output::exit(1);
+    }
+    if roots.len() > 1 {
+        let names: Vec<&str> = roots
+            .iter()
+            .map(|&idx| functions[idx].qualified_name.as_str())
+            .collect();
+        eprintln!(
+            "Warning: {} matches {} functions: {}",
+            name,
+            roots.len(),
+            names.join(", ")
+        );
+    }
+    roots
+}
+
+/// Extract functions with their calls and build the call graph for a
+/// graph query subcommand
+fn extract_call_graph(args: &Args) -> Extraction {
+    let mut options = ExtractOptions::from_args(args);
+    options.show_calls = true;
+    extract(args, &options)
+}
+
+/// Print the result of a graph query (functions paired with their depth),
+/// reporting callees that could not be resolved from the listed functions.
+/// Functions in `external` are listed separately as external dependencies.
+fn print_graph_query(
+    listing: &'static str,
+    args: &Args,
+    extraction: &Extraction,
+    graph: &CallGraph,
+    reached: &[(usize, usize)],
+    external: Option<&[(usize, usize)]>,
+) {
+    let indices: Vec<usize> = reached.iter().map(|&(idx, _)| idx).collect();
+    if matches!(args.format, OutputFormat::Dot) {
+        let mut all = indices;
+        all.extend(external.iter().flat_map(|e| e.iter().map(|&(idx, _)| idx)));
+        out!("{}", dot::render(&extraction.functions, graph, &all));
+        return;
+    }
+
+    let unresolved = graph.unresolved_callees(&indices);
+
+    let listed = |reached: &[(usize, usize)]| -> Vec<FunctionInfo> {
+        reached
+            .iter()
+            .map(|&(idx, depth)| {
+                let mut func = FunctionInfo {
+                    depth: Some(depth),
+                    ..extraction.functions[idx].clone()
+                };
+                if !args.show_calls {
+                    func.callees = None;
+                    func.spec_deps = None;
+                    func.unresolved_calls.clear();
+                }
+                func
+            })
+            .collect()
+    };
+    let functions = listed(reached);
+    let files: HashSet<&str> = functions.iter().filter_map(|f| f.file.as_deref()).collect();
+
+    let query = QueryExtras {
+        unresolved_callees: unresolved,
+        external_deps: external.map(listed),
+    };
+    let output = OutputOptions {
+        listing: Some(listing),
+        ..args.output()
+    };
+    print_functions(
+        &output,
+        &functions,
+        &Declarations::default(),
+        files.len(),
+        Some(query),
+    );
+}
+
+/// `closure` subcommand: the function (or the roots) plus everything it
+/// transitively calls, stopping at functions outside `within` if given
+fn run_closure(
+    args: &Args,
+    function: Option<&str>,
+    root_args: &RootArgs,
+    spec_deps: bool,
+    depth: Option<usize>,
+    within: Option<&str>,
+) {
+    let extraction = root_args.extract(args);
+    let graph = CallGraph::new(&extraction.functions, spec_deps);
+    let mut roots = function
+        .map(|name| find_roots(&graph, &extraction.functions, name))
+        .unwrap_or_default();
+    if root_args.roots_mode.is_some() || root_args.roots_file.is_some() {
+        let selected = root_args.select(&extraction.functions, &[]);
+        print_root_set(&extraction.functions, &selected);
+        for idx in selected {
+            if !roots.contains(&idx) {
+                roots.push(idx);
+            }
+        }
+    }
+    if roots.is_empty() {
+        eprintln!("Error: No function matches the given roots");
+        output::exit(1);
+    }
+    let reached = graph.closure_with_depth(&roots, depth, within);
+
+    match within {
+        Some(module) => {
+            let (inside, outside): (Vec<_>, Vec<_>) =
+                reached.into_iter().partition(|&(idx, depth)| {
+                    depth == 0 || callgraph::in_module(&extraction.functions[idx], module)
+                });
+            print_graph_query(
+                "closure",
+                args,
+                &extraction,
+                &graph,
+                &inside,
+                Some(&outside),
+            );
+        }
+        None => print_graph_query("closure", args, &extraction, &graph, &reached, None),
+    }
+}
+
+/// `callers` subcommand: everything that (transitively) calls the function
+fn run_callers(args: &Args, function: &str, depth: Option<usize>) {
+    let extraction = extract_call_graph(args);
+    let graph = CallGraph::new(&extraction.functions, true);
+    let roots = find_roots(&graph, &extraction.functions, function);
+    let callers = graph.callers(&roots, depth);
+    print_graph_query("callers", args, &extraction, &graph, &callers, None);
+}
+
+#[derive(Serialize)]
+struct CallGraphOutput {
+    /// Resolved callees of every function, by qualified name
+    edges: BTreeMap<String, Vec<String>>,
+    unresolved_callees: Vec<UnresolvedCallee>,
+}
+
+/// Where a function listed by a report is defined
+#[derive(Serialize)]
+struct FunctionLocation {
+    qualified_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    start_line: usize,
+}
+
+#[derive(Serialize)]
+struct SccOutput {
+    sccs: Vec<Vec<FunctionLocation>>,
+}
+
+impl FunctionLocation {
+    fn of(func: &FunctionInfo) -> Self {
+        FunctionLocation {
+            qualified_name: func.qualified_name.clone(),
+            file: func.file.clone(),
+            start_line: func.start_line,
+        }
+    }
+}
+
+impl std::fmt::Display for FunctionLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.qualified_name)?;
+        if let Some(ref file) = self.file {
+            write!(f, " @ {}:{}", file, self.start_line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Print the findings of a report with `--format sarif` or `--format github`;
+/// `rules` are those the report can find
+fn print_findings(
+    format: &OutputFormat,
+    rules: &[&'static findings::Rule],
+    found: &[findings::Finding],
+) {
+    match format {
+        OutputFormat::Github => out!("{}", github::render(found)),
+        _ => outln!("{}", sarif::render(rules, found)),
+    }
+}
+
+/// A `--format` a report cannot be written in: SARIF or GitHub annotations
+/// for a report listing no findings, SQLite or ctags for one not listing
+/// functions
+fn unsupported_format(command: &str, format: &OutputFormat) -> ! {
+    let format = format.to_possible_value().expect("no variant is skipped");
+    eprintln!(
+        "Error: {} does not support --format {}",
+        command,
+        format.get_name()
+    );
+    output::exit(1);
+}
+
+/// `diff` subcommand
+fn run_diff(args: &diff::DiffArgs) {
+    let diff = diff::compare(args).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        output::exit(1);
+    });
+    match args.format {
+        diff::DiffFormat::Json => outln!("{}", output::json(&diff)),
+        diff::DiffFormat::Text => out!("{}", diff.render()),
+    }
+}
+
+/// `call-graph` subcommand; `root` restricts the graph to the closure of
+/// one function
+fn run_call_graph(args: &Args, sccs: bool, root: Option<&str>) {
+    let extraction = extract_call_graph(args);
+    let functions = &extraction.functions;
+    let graph = CallGraph::new(functions, true);
+    let indices: Vec<usize> = match root {
+        Some(name) => graph.closure(&find_roots(&graph, functions, name)),
+        None => (0..functions.len()).collect(),
+    };
+    let included: HashSet<usize> = indices.iter().copied().collect();
+
+    if sccs {
+        // A component is either entirely inside a closure or entirely outside
+        let cyclic: Vec<Vec<usize>> = graph
+            .sccs()
+            .into_iter()
+            .filter(|component| component.len() > 1 && included.contains(&component[0]))
+            .collect();
+        if matches!(args.format, OutputFormat::Dot) {
+            let members: Vec<usize> = cyclic.into_iter().flatten().collect();
+            out!("{}", dot::render(functions, &graph, &members));
+            return;
+        }
+
+        let components: Vec<Vec<FunctionLocation>> = cyclic
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|idx| FunctionLocation::of(&functions[idx]))
+                    .collect()
+            })
+            .collect();
+
+        match args.format {
+            OutputFormat::Json | OutputFormat::Ndjson => {
+                let output = SccOutput { sccs: components };
+                outln!("{}", output::json(&output));
+            }
+            OutputFormat::JsonFlat
+            | OutputFormat::Shell
+            | OutputFormat::Sarif
+            | OutputFormat::Github
+            | OutputFormat::Sqlite
+            | OutputFormat::Ctags => unsupported_format("call-graph", &args.format),
+            OutputFormat::Text
+            | OutputFormat::Detailed
+            | OutputFormat::Dot
+            | OutputFormat::VerusArgs
+            | OutputFormat::Markdown => {
+                for (i, component) in components.iter().enumerate() {
+                    outln!("SCC {} ({} functions):", i + 1, component.len());
+                    for member in component {
+                        outln!("  {}", member);
+                    }
+                }
+                outln!(
+                    "\nSummary: {} strongly connected components",
+                    components.len()
+                );
+            }
+        }
+        return;
+    }
+
+    if matches!(args.format, OutputFormat::Dot) {
+        out!("{}", dot::render(functions, &graph, &indices));
+        return;
+    }
+
+    let mut edges: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for &idx in &indices {
+        let func = &functions[idx];
+        let callees = edges.entry(func.qualified_name.clone()).or_default();
+        callees.extend(
+            graph
+                .callees(idx)
+                .iter()
+                .filter(|callee| included.contains(callee))
+                .map(|&callee| functions[callee].qualified_name.clone()),
+        );
+        callees.sort();
+        callees.dedup();
+    }
+    let unresolved_callees = graph.unresolved_callees(&indices);
+
+    match args.format {
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = CallGraphOutput {
+                edges,
+                unresolved_callees,
+            };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Sarif
+        | OutputFormat::Github
+        | OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("call-graph", &args.format),
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            for (caller, callees) in &edges {
+                for callee in callees {
+                    outln!("{} -> {}", caller, callee);
+                }
+            }
+            for u in &unresolved_callees {
+                eprintln!("Warning: unresolved callee {} in {}", u.callee, u.caller);
+            }
+        }
+    }
+}
+
+/// Modules needing at most this many functions are flagged, since
+/// `--verify-function` flags may be cheaper than verifying the module
+const FEW_FUNCTIONS: usize = 2;
+
+/// A module containing part of a closure
+#[derive(Serialize)]
+struct ModuleCoverEntry {
+    /// Module path (`crate` for the crate root)
+    module: String,
+    /// Functions of the module in the closure
+    needed: usize,
+    /// All functions of the module
+    total: usize,
+    functions: Vec<String>,
+    /// Whether only a few functions are needed
+    few_functions: bool,
+}
+
+#[derive(Serialize)]
+struct ModuleCoverOutput {
+    function: String,
+    modules: Vec<ModuleCoverEntry>,
+}
+
+/// `module-cover` subcommand: the set of modules covering a closure
+fn run_module_cover(args: &Args, function: &str) {
+    let extraction = extract_call_graph(args);
+    let functions = &extraction.functions;
+    let graph = CallGraph::new(functions, true);
+    let roots = find_roots(&graph, functions, function);
+    let reached = graph.closure(&roots);
+
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    for func in functions {
+        *totals.entry(func.module_path.as_str()).or_default() += 1;
+    }
+    let mut needed: BTreeMap<&str, Vec<&FunctionInfo>> = BTreeMap::new();
+    for &idx in &reached {
+        let func = &functions[idx];
+        needed
+            .entry(func.module_path.as_str())
+            .or_default()
+            .push(func);
+    }
+
+    if matches!(args.format, OutputFormat::VerusArgs) {
+        let mut verus_args = Vec::new();
+        for module in needed.keys() {
+            if module.is_empty() {
+                verus_args.push("--verify-root".to_string());
+            } else {
+                verus_args.push("--verify-only-module".to_string());
+                verus_args.push(module.to_string());
+            }
+        }
+        print_verus_args(&verus_args, args.print0);
+        return;
+    }
+
+    let modules: Vec<ModuleCoverEntry> = needed
+        .into_iter()
+        .map(|(module, funcs)| {
+            let mut names: Vec<String> = funcs
+                .iter()
+                .map(|f| f.verify_function_arg.clone())
+                .collect();
+            names.sort();
+            names.dedup();
+            ModuleCoverEntry {
+                module: if module.is_empty() {
+                    "crate".to_string()
+                } else {
+                    module.to_string()
+                },
+                needed: funcs.len(),
+                total: totals[module],
+                few_functions: funcs.len() <= FEW_FUNCTIONS,
+                functions: names,
+            }
+        })
+        .collect();
+
+    match args.format {
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = ModuleCoverOutput {
+                function: function.to_string(),
+                modules,
+            };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Sarif
+        | OutputFormat::Github
+        | OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("module-cover", &args.format),
+        _ => {
+            for entry in &modules {
+                out!(
+                    "{}: {} of {} functions",
+                    entry.module,
+                    entry.needed,
+                    entry.total
+                );
+                if entry.few_functions {
+                    out!(
+                        " (only {}; --verify-function may be enough)",
+                        entry.functions.join(", ")
+                    );
+                }
+                outln!();
+            }
+            outln!("\nSummary: {} modules", modules.len());
+        }
+    }
 }
 
-impl<'ast> Visit<'ast> for FunctionVisitor {
-    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
-        let name = node.sig.ident.to_string();
-        let span = node.span();
-        self.add_function(name, span, &node.sig, &node.vis, Some("standalone".to_string()));
+#[derive(Serialize)]
+struct ModuleGraphOutput {
+    /// Modules each module depends on, by module path (`crate` for the root)
+    modules: BTreeMap<String, BTreeSet<String>>,
+}
 
-        // Continue visiting nested items
-        verus_syn::visit::visit_item_fn(self, node);
-    }
+/// `module-graph` subcommand: function-level calls aggregated to modules
+fn run_module_graph(args: &Args) {
+    let extraction = extract_call_graph(args);
+    let graph = CallGraph::new(&extraction.functions, true);
+    let modules = graph.module_dependencies();
 
-    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
-        if !self.include_methods {
-            return;
+    match args.format {
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = ModuleGraphOutput { modules };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Sarif
+        | OutputFormat::Github
+        | OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("module-graph", &args.format),
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            for (from, targets) in &modules {
+                for to in targets {
+                    outln!("{} -> {}", from, to);
+                }
+            }
         }
+        OutputFormat::Dot => out!("{}", dot::render_modules(&modules)),
+    }
+}
 
-        let name = node.sig.ident.to_string();
-        let span = node.span();
-        self.add_function(name, span, &node.sig, &node.vis, Some("impl".to_string()));
+#[derive(Serialize)]
+struct UnusedLemmasOutput {
+    unused_lemmas: Vec<FunctionLocation>,
+}
 
-        // Continue visiting nested items
-        verus_syn::visit::visit_impl_item_fn(self, node);
-    }
+/// `unused-lemmas` subcommand
+fn run_unused_lemmas(args: &Args, allow: &[String]) {
+    let allow = reports::FunctionPatterns::new(allow, "--allow").unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        output::exit(1);
+    });
 
-    fn visit_trait_item_fn(&mut self, node: &'ast TraitItemFn) {
-        if !self.include_methods {
-            return;
-        }
+    // Function modes decide which functions are lemmas and which are roots
+    let mut options = ExtractOptions::from_args(args);
+    options.show_calls = true;
+    options.show_kind = true;
+    let extraction = extract(args, &options);
+    let functions = &extraction.functions;
+    let graph = CallGraph::new(functions, true);
 
-        let name = node.sig.ident.to_string();
-        let span = node.span();
-        
-        // Trait items don't have explicit visibility (they inherit from trait)
-        let vis = Visibility::Inherited;
-        self.add_function(name, span, &node.sig, &vis, Some("trait".to_string()));
+    let mut unused_indices = reports::unused_lemmas(functions, &graph, &allow);
+    args.sort_entries(&mut unused_indices, |&idx| &functions[idx]);
+    let unused: Vec<FunctionLocation> = unused_indices
+        .iter()
+        .map(|&idx| FunctionLocation::of(&functions[idx]))
+        .collect();
 
-        // Continue visiting nested items
-        verus_syn::visit::visit_trait_item_fn(self, node);
+    match args.format {
+        OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("unused-lemmas", &args.format),
+        OutputFormat::Sarif | OutputFormat::Github => {
+            let found: Vec<findings::Finding> = unused_indices
+                .iter()
+                .map(|&idx| {
+                    let func = &functions[idx];
+                    let message = format!(
+                        "`{}` is not reachable from any exec function",
+                        func.qualified_name
+                    );
+                    findings::Finding::new(&findings::UNUSED_LEMMA, func, message)
+                })
+                .collect();
+            print_findings(&args.format, &[&findings::UNUSED_LEMMA], &found);
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = UnusedLemmasOutput {
+                unused_lemmas: unused,
+            };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            for lemma in &unused {
+                outln!("{}", lemma);
+            }
+            outln!("\nSummary: {} unused lemmas", unused.len());
+        }
     }
+}
 
-    fn visit_item_impl(&mut self, node: &'ast verus_syn::ItemImpl) {
-        verus_syn::visit::visit_item_impl(self, node);
-    }
+#[derive(Serialize)]
+struct MissingDecreasesEntry {
+    #[serde(flatten)]
+    location: FunctionLocation,
+    recursion: callgraph::Recursion,
+}
 
-    fn visit_item_trait(&mut self, node: &'ast verus_syn::ItemTrait) {
-        verus_syn::visit::visit_item_trait(self, node);
-    }
+#[derive(Serialize)]
+struct MissingDecreasesOutput {
+    missing_decreases: Vec<MissingDecreasesEntry>,
+}
 
-    fn visit_item_mod(&mut self, node: &'ast verus_syn::ItemMod) {
-        verus_syn::visit::visit_item_mod(self, node);
+/// `missing-decreases` subcommand: recursive functions Verus will reject
+/// (or that rely on `exec_allows_no_decreases_clause`)
+fn run_missing_decreases(args: &Args) {
+    let mut options = ExtractOptions::from_args(args);
+    options.show_calls = true;
+    options.show_specs = true;
+    let extraction = extract(args, &options);
+    let functions = &extraction.functions;
+    // Recursion goes through body calls; a spec clause mentioning the
+    // function itself is not a recursive call
+    let graph = CallGraph::new(functions, false);
+
+    let mut found = reports::missing_decreases(functions, &graph);
+    args.sort_entries(&mut found, |&(idx, _)| &functions[idx]);
+    let missing: Vec<MissingDecreasesEntry> = found
+        .iter()
+        .map(|&(idx, recursion)| MissingDecreasesEntry {
+            location: FunctionLocation::of(&functions[idx]),
+            recursion,
+        })
+        .collect();
+
+    match args.format {
+        OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("missing-decreases", &args.format),
+        OutputFormat::Sarif | OutputFormat::Github => {
+            let found: Vec<findings::Finding> = found
+                .iter()
+                .map(|&(idx, recursion)| {
+                    let func = &functions[idx];
+                    let message = format!(
+                        "`{}` has {} recursion but no decreases clause",
+                        func.qualified_name, recursion
+                    );
+                    findings::Finding::new(&findings::MISSING_DECREASES, func, message)
+                })
+                .collect();
+            print_findings(&args.format, &[&findings::MISSING_DECREASES], &found);
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = MissingDecreasesOutput {
+                missing_decreases: missing,
+            };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            for entry in &missing {
+                outln!("{} ({} recursion)", entry.location, entry.recursion);
+            }
+            outln!(
+                "\nSummary: {} recursive functions without decreases",
+                missing.len()
+            );
+        }
     }
+}
 
-    fn visit_item_macro(&mut self, node: &'ast ItemMacro) {
-        if let Some(ident) = &node.mac.path.get_ident() {
-            if *ident == "verus" {
-                // Parse verus! macro body as items
-                if let Ok(items) = verus_syn::parse2::<VerusMacroBody>(node.mac.tokens.clone()) {
-                    for item in items.items {
-                        self.visit_item(&item);
-                    }
+#[derive(Serialize)]
+struct OpaqueEntry {
+    #[serde(flatten)]
+    location: FunctionLocation,
+    /// Qualified names of the functions revealing it
+    revealed_by: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct OpaqueRevealsOutput {
+    opaque_functions: Vec<OpaqueEntry>,
+    summary: OpaqueRevealsSummary,
+}
+
+#[derive(Serialize)]
+struct OpaqueRevealsSummary {
+    opaque_functions: usize,
+    never_revealed: usize,
+}
+
+/// `opaque-reveals` subcommand
+fn run_opaque_reveals(args: &Args) {
+    let mut options = ExtractOptions::from_args(args);
+    options.show_calls = true;
+    let extraction = extract(args, &options);
+    let functions = &extraction.functions;
+    let graph = CallGraph::new(functions, false);
+
+    let mut reveals = reports::opaque_reveals(functions, &graph);
+    args.sort_entries(&mut reveals, |(idx, _)| &functions[*idx]);
+    let entries: Vec<OpaqueEntry> = reveals
+        .iter()
+        .map(|(idx, revealers)| {
+            let mut revealed_by: Vec<String> = revealers
+                .iter()
+                .map(|&r| functions[r].qualified_name.clone())
+                .collect();
+            revealed_by.sort();
+            revealed_by.dedup();
+            OpaqueEntry {
+                location: FunctionLocation::of(&functions[*idx]),
+                revealed_by,
+            }
+        })
+        .collect();
+    let never_revealed = entries.iter().filter(|e| e.revealed_by.is_empty()).count();
+
+    match args.format {
+        OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("opaque-reveals", &args.format),
+        OutputFormat::Sarif | OutputFormat::Github => {
+            let found: Vec<findings::Finding> = reveals
+                .iter()
+                .filter(|(_, revealers)| revealers.is_empty())
+                .map(|&(idx, _)| {
+                    let func = &functions[idx];
+                    let message = format!("`{}` is opaque and never revealed", func.qualified_name);
+                    findings::Finding::new(&findings::NEVER_REVEALED, func, message)
+                })
+                .collect();
+            print_findings(&args.format, &[&findings::NEVER_REVEALED], &found);
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = OpaqueRevealsOutput {
+                summary: OpaqueRevealsSummary {
+                    opaque_functions: entries.len(),
+                    never_revealed,
+                },
+                opaque_functions: entries,
+            };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            for entry in &entries {
+                outln!("{}", entry.location);
+                if entry.revealed_by.is_empty() {
+                    outln!("    never revealed");
                 }
-            } else if *ident == "cfg_if" {
-                // Parse cfg_if! macro body
-                if let Ok(branches) = verus_syn::parse2::<CfgIfMacroBody>(node.mac.tokens.clone())
-                {
-                    for items in branches.all_items {
-                        for item in items {
-                            self.visit_item(&item);
-                        }
-                    }
+                for name in &entry.revealed_by {
+                    outln!("    revealed by {}", name);
                 }
             }
+            outln!(
+                "\nSummary: {} opaque functions, {} never revealed",
+                entries.len(),
+                never_revealed
+            );
         }
-        verus_syn::visit::visit_item_macro(self, node);
     }
 }
 
-/// Helper struct to parse verus! macro body as a list of items
-struct VerusMacroBody {
-    items: Vec<Item>,
+#[derive(Serialize)]
+struct QuantifierEntry {
+    #[serde(flatten)]
+    location: FunctionLocation,
+    quantifier_count: usize,
+    trigger_count: usize,
+    /// Index of the function, for GitHub notices
+    #[serde(skip)]
+    index: usize,
 }
 
-impl verus_syn::parse::Parse for VerusMacroBody {
-    fn parse(input: verus_syn::parse::ParseStream) -> verus_syn::Result<Self> {
-        let mut items = Vec::new();
-        while !input.is_empty() {
-            items.push(input.parse()?);
+#[derive(Serialize)]
+struct QuantifiersOutput {
+    functions: Vec<QuantifierEntry>,
+}
+
+/// `quantifiers` subcommand: the likeliest sources of SMT timeouts
+fn run_quantifiers(args: &Args) {
+    let mut options = ExtractOptions::from_args(args);
+    options.show_specs = true;
+    let extraction = extract(args, &options);
+
+    let mut entries: Vec<QuantifierEntry> = extraction
+        .functions
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.quantifier_count.unwrap_or(0) > 0)
+        .map(|(index, f)| QuantifierEntry {
+            location: FunctionLocation::of(f),
+            quantifier_count: f.quantifier_count.unwrap_or(0),
+            trigger_count: f.trigger_count.unwrap_or(0),
+            index,
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.quantifier_count
+            .cmp(&a.quantifier_count)
+            .then_with(|| a.location.qualified_name.cmp(&b.location.qualified_name))
+    });
+    args.sort_entries(&mut entries, |e| &extraction.functions[e.index]);
+
+    match args.format {
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = QuantifiersOutput { functions: entries };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Sarif
+        | OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("quantifiers", &args.format),
+        OutputFormat::Github => {
+            for entry in &entries {
+                let func = &extraction.functions[entry.index];
+                let message = format!(
+                    "`{}`: {} quantifiers, {} triggers",
+                    func.qualified_name, entry.quantifier_count, entry.trigger_count
+                );
+                outln!("{}", github::notice("quantifiers", func, &message));
+            }
+        }
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            for entry in &entries {
+                outln!(
+                    "{}: {} quantifiers, {} triggers",
+                    entry.location,
+                    entry.quantifier_count,
+                    entry.trigger_count
+                );
+            }
+            outln!("\nSummary: {} functions with quantifiers", entries.len());
         }
-        Ok(VerusMacroBody { items })
     }
 }
 
-/// Helper struct to parse cfg_if! macro body
-struct CfgIfMacroBody {
-    all_items: Vec<Vec<Item>>,
+#[derive(Serialize)]
+struct NonlinearArithEntry {
+    #[serde(flatten)]
+    location: FunctionLocation,
+    verify_function_arg: String,
+    nonlinear_arith: usize,
+    /// Index of the function, for GitHub notices
+    #[serde(skip)]
+    index: usize,
 }
 
-impl verus_syn::parse::Parse for CfgIfMacroBody {
-    fn parse(input: verus_syn::parse::ParseStream) -> verus_syn::Result<Self> {
-        use verus_syn::Token;
+#[derive(Serialize)]
+struct NonlinearArithOutput {
+    functions: Vec<NonlinearArithEntry>,
+}
 
-        let mut all_items = Vec::new();
+/// `nonlinear-arith` subcommand: the proofs most likely to time out
+fn run_nonlinear_arith(args: &Args) {
+    let mut options = ExtractOptions::from_args(args);
+    options.show_specs = true;
+    let extraction = extract(args, &options);
 
-        if input.peek(Token![if]) {
-            input.parse::<Token![if]>()?;
-            input.parse::<Token![#]>()?;
-            let _attr_group: proc_macro2::Group = input.parse()?;
+    let mut entries: Vec<NonlinearArithEntry> = extraction
+        .functions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, f)| {
+            let count = *f.provers.as_ref()?.get("nonlinear_arith")?;
+            Some(NonlinearArithEntry {
+                location: FunctionLocation::of(f),
+                verify_function_arg: f.verify_function_arg.clone(),
+                nonlinear_arith: count,
+                index,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.nonlinear_arith
+            .cmp(&a.nonlinear_arith)
+            .then_with(|| a.location.qualified_name.cmp(&b.location.qualified_name))
+    });
+    args.sort_entries(&mut entries, |e| &extraction.functions[e.index]);
 
-            let content;
-            verus_syn::braced!(content in input);
-            let mut items = Vec::new();
-            while !content.is_empty() {
-                items.push(content.parse()?);
+    match args.format {
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = NonlinearArithOutput { functions: entries };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Sarif
+        | OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("nonlinear-arith", &args.format),
+        OutputFormat::Github => {
+            for entry in &entries {
+                let func = &extraction.functions[entry.index];
+                let message = format!(
+                    "`{}`: {} nonlinear_arith (--verify-function {})",
+                    func.qualified_name, entry.nonlinear_arith, entry.verify_function_arg
+                );
+                outln!("{}", github::notice("nonlinear-arith", func, &message));
+            }
+        }
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            for entry in &entries {
+                outln!(
+                    "{}: {} nonlinear_arith (--verify-function {})",
+                    entry.location,
+                    entry.nonlinear_arith,
+                    entry.verify_function_arg
+                );
             }
-            all_items.push(items);
+            outln!(
+                "\nSummary: {} functions using nonlinear arithmetic",
+                entries.len()
+            );
         }
+    }
+}
 
-        while input.peek(Token![else]) {
-            input.parse::<Token![else]>()?;
+#[derive(Serialize)]
+struct RlimitEntry {
+    #[serde(flatten)]
+    location: FunctionLocation,
+    rlimit: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    spinoff_prover: bool,
+    #[serde(skip)]
+    value: f64,
+    /// Index of the function, for SARIF findings
+    #[serde(skip)]
+    index: usize,
+}
 
-            if input.peek(Token![if]) {
-                input.parse::<Token![if]>()?;
-                input.parse::<Token![#]>()?;
-                let _attr_group: proc_macro2::Group = input.parse()?;
+#[derive(Serialize)]
+struct RlimitsOutput {
+    functions: Vec<RlimitEntry>,
+}
 
-                let content;
-                verus_syn::braced!(content in input);
-                let mut items = Vec::new();
-                while !content.is_empty() {
-                    items.push(content.parse()?);
-                }
-                all_items.push(items);
-            } else {
-                let content;
-                verus_syn::braced!(content in input);
-                let mut items = Vec::new();
-                while !content.is_empty() {
-                    items.push(content.parse()?);
+/// `rlimits` subcommand: the verification-time hot spots worth splitting
+fn run_rlimits(args: &Args, above: f64) {
+    let options = ExtractOptions::from_args(args);
+    let extraction = extract(args, &options);
+
+    let mut entries: Vec<RlimitEntry> = extraction
+        .functions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, f)| {
+            let rlimit = f.rlimit.clone()?;
+            let value = match rlimit.as_str() {
+                "infinity" => f64::INFINITY,
+                n => n.parse().ok()?,
+            };
+            (value > above).then(|| RlimitEntry {
+                location: FunctionLocation::of(f),
+                rlimit,
+                spinoff_prover: f.spinoff_prover,
+                value,
+                index,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.value
+            .total_cmp(&a.value)
+            .then_with(|| a.location.qualified_name.cmp(&b.location.qualified_name))
+    });
+    args.sort_entries(&mut entries, |e| &extraction.functions[e.index]);
+
+    match args.format {
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = RlimitsOutput { functions: entries };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("rlimits", &args.format),
+        OutputFormat::Sarif | OutputFormat::Github => {
+            let found: Vec<findings::Finding> = entries
+                .iter()
+                .map(|entry| {
+                    let func = &extraction.functions[entry.index];
+                    let message = format!(
+                        "`{}` has rlimit {}, above {}",
+                        func.qualified_name, entry.rlimit, above
+                    );
+                    findings::Finding::new(&findings::HIGH_RLIMIT, func, message)
+                })
+                .collect();
+            print_findings(&args.format, &[&findings::HIGH_RLIMIT], &found);
+        }
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            for entry in &entries {
+                out!("{}: rlimit {}", entry.location, entry.rlimit);
+                if entry.spinoff_prover {
+                    out!(" (spinoff_prover)");
                 }
-                all_items.push(items);
-                break;
+                outln!();
             }
+            outln!(
+                "\nSummary: {} functions with an rlimit above {}",
+                entries.len(),
+                above
+            );
         }
-
-        Ok(CfgIfMacroBody { all_items })
     }
 }
 
-fn parse_file(
-    file_path: &Path,
-    include_verus_constructs: bool,
-    include_methods: bool,
-    show_visibility: bool,
-    show_kind: bool,
-) -> Result<Vec<FunctionInfo>, String> {
-    let content = fs::read_to_string(file_path)
-        .map_err(|e| format!("Failed to read file {}: {}", file_path.display(), e))?;
+/// A function's line span, for reports grouped by file
+#[derive(Serialize)]
+struct FunctionSpan {
+    qualified_name: String,
+    start_line: usize,
+    end_line: usize,
+}
 
-    let syntax_tree = verus_syn::parse_file(&content)
-        .map_err(|e| format!("Failed to parse file {}: {}", file_path.display(), e))?;
+/// Print the roots a query computed to stderr, for checking them
+fn print_root_set(functions: &[FunctionInfo], roots: &[usize]) {
+    eprintln!("Roots ({}):", roots.len());
+    for &idx in roots {
+        eprintln!("  {}", functions[idx].qualified_name);
+    }
+}
 
-    let mut visitor = FunctionVisitor::new(
-        Some(file_path.to_string_lossy().to_string()),
-        include_verus_constructs,
-        include_methods,
-        show_visibility,
-        show_kind,
-    );
-    visitor.visit_file(&syntax_tree);
+#[derive(Serialize)]
+struct DeadFunctionsOutput {
+    /// The roots, by qualified name
+    roots: Vec<String>,
+    /// Unreachable functions by file
+    dead_functions: BTreeMap<String, Vec<FunctionSpan>>,
+    summary: DeadFunctionsSummary,
+}
 
-    Ok(visitor.functions)
+#[derive(Serialize)]
+struct DeadFunctionsSummary {
+    total_functions: usize,
+    roots: usize,
+    dead_functions: usize,
 }
 
-fn find_rust_files(path: &Path) -> Vec<PathBuf> {
-    WalkDir::new(path)
+/// `emit-plan` subcommand
+fn run_emit_plan(args: &Args, template: &str, style: &plan::PlanStyle) {
+    let template = plan::Template::parse(template).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        output::exit(1);
+    });
+    let options = ExtractOptions::from_args(args);
+    let extraction = extract(args, &options);
+    let verified = extraction
+        .functions
         .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().map_or(false, |ext| ext == "rs"))
-        .map(|e| e.path().to_path_buf())
-        .collect()
+        .filter(|f| f.is_verified_function())
+        .collect();
+    let sharded = args.shard(verified);
+    let mut functions: Vec<&FunctionInfo> = sharded.iter().collect();
+    args.sort_entries(&mut functions, |f| *f);
+    out!("{}", plan::render(&template, style, &functions));
 }
 
-fn main() {
-    let args = Args::parse();
-
-    if !args.path.exists() {
-        eprintln!("Error: Path does not exist: {}", args.path.display());
-        std::process::exit(1);
-    }
-
-    let mut all_functions = Vec::new();
-    let mut functions_by_file: HashMap<String, Vec<FunctionInfo>> = HashMap::new();
-    let mut total_files = 0;
+/// `plan` subcommand
+fn run_plan(args: &Args, strategy: jobs::Strategy, job_cost: usize) {
+    // The cost counts asserts, quantifiers and nonlinear arithmetic
+    let mut options = ExtractOptions::from_args(args);
+    options.show_specs = true;
+    let extraction = extract(args, &options);
+    let functions: Vec<&FunctionInfo> = extraction
+        .functions
+        .iter()
+        .filter(|f| f.is_verified_function())
+        .collect();
+    let planned = jobs::plan(&functions, strategy, job_cost);
 
-    if args.path.is_file() {
-        match parse_file(
-            &args.path,
-            args.include_verus_constructs,
-            args.include_methods,
-            args.show_visibility,
-            args.show_kind,
-        ) {
-            Ok(functions) => {
-                let file_path = args.path.to_string_lossy().to_string();
-                if !functions.is_empty() {
-                    functions_by_file.insert(file_path, functions.clone());
-                    all_functions.extend(functions);
-                    total_files = 1;
-                }
+    match args.format {
+        OutputFormat::Json => outln!("{}", output::json(&planned)),
+        OutputFormat::Ndjson => {
+            for job in &planned {
+                outln!("{}", serde_json::to_string(job).unwrap());
             }
-            Err(e) => {
-                eprintln!("Error parsing file: {}", e);
-                std::process::exit(1);
+        }
+        OutputFormat::Text | OutputFormat::Detailed => {
+            for job in &planned {
+                outln!(
+                    "{}\t{}",
+                    job.cost,
+                    shell::join(job.verus_args.iter().map(String::as_str))
+                );
             }
         }
-    } else {
-        let rust_files = find_rust_files(&args.path);
-        total_files = rust_files.len();
+        OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown
+        | OutputFormat::Sarif
+        | OutputFormat::Github
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("plan", &args.format),
+    }
+}
 
-        for file_path in rust_files {
-            match parse_file(
-                &file_path,
-                args.include_verus_constructs,
-                args.include_methods,
-                args.show_visibility,
-                args.show_kind,
-            ) {
-                Ok(functions) => {
-                    if !functions.is_empty() {
-                        let path_str = file_path.to_string_lossy().to_string();
-                        functions_by_file.insert(path_str, functions.clone());
-                        all_functions.extend(functions);
-                    }
+/// `dead-functions` subcommand
+fn run_dead_functions(args: &Args, roots: &[String], root_args: &RootArgs) {
+    let extraction = root_args.extract(args);
+    let functions = &extraction.functions;
+    let graph = CallGraph::new(functions, true);
+    let roots = root_args.select(functions, roots);
+    if roots.is_empty() {
+        eprintln!("Error: No function matches the given roots");
+        output::exit(1);
+    }
+
+    let mut dead = reports::dead_functions(functions, &graph, &roots);
+    args.sort_entries(&mut dead, |&idx| &functions[idx]);
+    let mut by_file: BTreeMap<String, Vec<FunctionSpan>> = BTreeMap::new();
+    for &idx in &dead {
+        let func = &functions[idx];
+        by_file
+            .entry(func.file.clone().unwrap_or_default())
+            .or_default()
+            .push(FunctionSpan {
+                qualified_name: func.qualified_name.clone(),
+                start_line: func.start_line,
+                end_line: func.end_line,
+            });
+    }
+    match args.format {
+        OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("dead-functions", &args.format),
+        OutputFormat::Sarif | OutputFormat::Github => {
+            let found: Vec<findings::Finding> = dead
+                .iter()
+                .map(|&idx| {
+                    let func = &functions[idx];
+                    let message = format!(
+                        "`{}` is not reachable from any of the {} roots",
+                        func.qualified_name,
+                        roots.len()
+                    );
+                    findings::Finding::new(&findings::DEAD_FUNCTION, func, message)
+                })
+                .collect();
+            print_findings(&args.format, &[&findings::DEAD_FUNCTION], &found);
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = DeadFunctionsOutput {
+                roots: roots
+                    .iter()
+                    .map(|&idx| functions[idx].qualified_name.clone())
+                    .collect(),
+                dead_functions: by_file,
+                summary: DeadFunctionsSummary {
+                    total_functions: functions.len(),
+                    roots: roots.len(),
+                    dead_functions: dead.len(),
+                },
+            };
+            outln!("{}", output::json(&output));
+        }
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            if root_args.roots_mode.is_some() || root_args.roots_file.is_some() {
+                outln!("Roots:");
+                for &idx in &roots {
+                    outln!("  {}", functions[idx].qualified_name);
                 }
-                Err(e) => {
-                    eprintln!("Warning: {}", e);
+                outln!();
+            }
+            for (file, spans) in &by_file {
+                outln!("{}:", file);
+                for span in spans {
+                    outln!(
+                        "  {} (lines {}-{})",
+                        span.qualified_name,
+                        span.start_line,
+                        span.end_line
+                    );
                 }
             }
+            outln!(
+                "\nSummary: {} of {} functions unreachable from {} roots",
+                dead.len(),
+                functions.len(),
+                roots.len()
+            );
         }
     }
+}
+
+#[derive(Serialize)]
+struct TrustedOutput {
+    /// Trusted functions each function transitively depends on (itself
+    /// included), by qualified name
+    functions: BTreeMap<String, Vec<String>>,
+    /// Why each trusted function is trusted
+    trusted_leaves: BTreeMap<String, Vec<String>>,
+    summary: TrustedSummary,
+}
+
+#[derive(Serialize)]
+struct TrustedSummary {
+    total_functions: usize,
+    trusted_leaves: usize,
+    functions_relying_on_trusted: usize,
+}
+
+/// `trusted` subcommand: the trusted computing base of every function
+fn run_trusted(args: &Args) {
+    let extraction = extract_call_graph(args);
+    let functions = &extraction.functions;
+    let graph = CallGraph::new(functions, true);
+    let deps = reports::trusted_dependencies(functions, &graph);
+
+    let mut by_function: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (idx, leaves) in deps.iter().enumerate() {
+        let names = by_function
+            .entry(functions[idx].qualified_name.clone())
+            .or_default();
+        names.extend(
+            leaves
+                .iter()
+                .map(|&leaf| functions[leaf].qualified_name.clone()),
+        );
+        names.sort();
+        names.dedup();
+    }
+    let trusted_leaves: BTreeMap<String, Vec<String>> = functions
+        .iter()
+        .filter(|f| !f.trusted.is_empty())
+        .map(|f| (f.qualified_name.clone(), f.trusted.clone()))
+        .collect();
+    let relying = by_function.values().filter(|deps| !deps.is_empty()).count();
 
     match args.format {
-        OutputFormat::Json => {
-            let output = ParsedOutput {
-                functions: all_functions.clone(),
-                functions_by_file,
-                summary: Summary {
-                    total_functions: all_functions.len(),
-                    total_files,
+        OutputFormat::JsonFlat
+        | OutputFormat::Shell
+        | OutputFormat::Sqlite
+        | OutputFormat::Ctags => unsupported_format("trusted", &args.format),
+        OutputFormat::Sarif | OutputFormat::Github => {
+            let found: Vec<findings::Finding> = functions
+                .iter()
+                .filter(|f| !f.trusted.is_empty())
+                .map(|func| {
+                    let message = format!(
+                        "`{}` is trusted ({})",
+                        func.qualified_name,
+                        func.trusted.join(", ")
+                    );
+                    findings::Finding::new(&findings::TRUSTED, func, message)
+                })
+                .collect();
+            print_findings(&args.format, &[&findings::TRUSTED], &found);
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let output = TrustedOutput {
+                summary: TrustedSummary {
+                    total_functions: by_function.len(),
+                    trusted_leaves: trusted_leaves.len(),
+                    functions_relying_on_trusted: relying,
                 },
+                functions: by_function,
+                trusted_leaves,
             };
-            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            outln!("{}", output::json(&output));
         }
-        OutputFormat::Text => {
-            // Just print function names, one per line
-            let mut names: Vec<_> = all_functions.iter().map(|f| f.name.as_str()).collect();
-            names.sort();
-            names.dedup();
-            for name in names {
-                println!("{}", name);
+        OutputFormat::Text
+        | OutputFormat::Detailed
+        | OutputFormat::Dot
+        | OutputFormat::VerusArgs
+        | OutputFormat::Markdown => {
+            for (name, reasons) in &trusted_leaves {
+                outln!("trusted: {} ({})", name, reasons.join(", "));
             }
-        }
-        OutputFormat::Detailed => {
-            for func in &all_functions {
-                print!("{}", func.name);
-                if let Some(ref kind) = func.kind {
-                    print!(" [{}]", kind);
+            for (name, deps) in &by_function {
+                if !deps.is_empty() && !trusted_leaves.contains_key(name) {
+                    outln!("{} relies on {}", name, deps.join(", "));
                 }
-                if let Some(ref vis) = func.visibility {
-                    print!(" ({})", vis);
-                }
-                if let Some(ref file) = func.file {
-                    print!(" @ {}:{}:{}", file, func.start_line, func.end_line);
-                }
-                if let Some(ref context) = func.context {
-                    print!(" in {}", context);
-                }
-                println!();
             }
-            println!("\nSummary: {} functions in {} files", all_functions.len(), total_files);
+            outln!(
+                "\nSummary: {} of {} functions rely on {} trusted functions",
+                relying,
+                by_function.len(),
+                trusted_leaves.len()
+            );
         }
     }
 }
 
+/// `--fail-if-assumes`: exit with an error when the functions hold more
+/// than `max` `assume`s and `admit()`s, listing where they are
+fn check_proof_debt(functions: &[FunctionInfo], max: usize) {
+    let offending: Vec<&FunctionInfo> = functions
+        .iter()
+        .filter(|f| f.proof_debt().unwrap_or(0) > 0)
+        .collect();
+    let total: usize = offending.iter().filter_map(|f| f.proof_debt()).sum();
+    if total <= max {
+        return;
+    }
+    eprintln!(
+        "Error: {} assume/admit occurrences, more than the {} allowed",
+        total, max
+    );
+    for func in offending {
+        eprintln!(
+            "  {}: {} assume, {} admit",
+            FunctionLocation::of(func),
+            func.assume_count.unwrap_or(0),
+            func.admit_count.unwrap_or(0)
+        );
+    }
+    output::exit(1);
+}
+
+/// `--check-baseline`: exit with an error when the functions differ from
+/// those of the baseline, listing the differences
+fn check_baseline(functions: &[FunctionInfo], path: &Path) {
+    let old = baseline::Baseline::read(path).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        output::exit(1);
+    });
+    let diff = baseline::Baseline::of(functions).diff(&old);
+    if diff.is_empty() {
+        return;
+    }
+    eprintln!(
+        "Error: the functions differ from the baseline {} (update it with --write-baseline):",
+        path.display()
+    );
+    eprint!("{}", diff);
+    output::exit(1);
+}
+
+/// `--fail-if-termination-unchecked`: exit with an error when more than
+/// `max` functions do without a termination proof, listing them
+fn check_termination_unchecked(functions: &[FunctionInfo], max: usize) {
+    let offending: Vec<&FunctionInfo> = functions
+        .iter()
+        .filter(|f| f.termination_unchecked)
+        .collect();
+    if offending.len() <= max {
+        return;
+    }
+    eprintln!(
+        "Error: {} functions with unchecked termination, more than the {} allowed",
+        offending.len(),
+        max
+    );
+    for func in offending {
+        eprintln!("  {}", FunctionLocation::of(func));
+    }
+    output::exit(1);
+}
+
+/// Move the `--output` file into place, exiting with an error when it
+/// cannot be written
+fn finish_output() {
+    if let Err(e) = output::commit() {
+        eprintln!("Error: {}", e);
+        output::exit(1);
+    }
+}
+
+/// `--output DIR`: the functions of each input file written to a file of
+/// their own, at the input's path under `dir` with the format's extension
+fn print_per_file(args: &Args, options: &ExtractOptions, dir: &Path) {
+    let extraction = extract(args, options);
+    let mut by_file: BTreeMap<&str, Vec<FunctionInfo>> = BTreeMap::new();
+    for func in &extraction.functions {
+        by_file
+            .entry(func.file.as_deref().unwrap_or_default())
+            .or_default()
+            .push(func.clone());
+    }
+    for (file, functions) in &by_file {
+        let path = Path::new(file);
+        let relative = match path.strip_prefix(&args.path) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative,
+            _ => Path::new(path.file_name().unwrap_or(path.as_os_str())),
+        };
+        let mut target = dir.join(relative).into_os_string();
+        target.push(".");
+        target.push(args.format.extension());
+        let _pending = output::to_file(Path::new(&target)).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            output::exit(1);
+        });
+        let declarations = extraction.declarations.of_file(file, functions);
+        print_functions(&args.output(), functions, &declarations, 1, None);
+        finish_output();
+    }
+}
+
+/// `--print-schema`: the JSON Schema of the output of this version
+fn print_schema() {
+    let schema: serde_json::Value = serde_json::from_str(SCHEMA).expect("schema.json is JSON");
+    assert_eq!(
+        schema["properties"]["schema_version"]["const"], SCHEMA_VERSION,
+        "schema.json describes another schema_version"
+    );
+    out!("{}", SCHEMA);
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if cli.print_schema {
+        print_schema();
+        return;
+    }
+
+    let (format, target, json) = cli.output_target();
+    let format = format.clone();
+    // Only names and Verus arguments are NUL-terminated
+    if cli.print0() && !matches!(format, OutputFormat::Text | OutputFormat::VerusArgs) {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                format!(
+                    "--print0 requires --format text or verus-args, not {}",
+                    format
+                        .to_possible_value()
+                        .expect("no variant is skipped")
+                        .get_name()
+                ),
+            )
+            .exit();
+    }
+    let target = target.map(Path::to_path_buf);
+    output::set_pretty(json.pretty(target.is_some()));
+    let sqlite = matches!(format, OutputFormat::Sqlite);
+    let per_file = target
+        .as_ref()
+        .filter(|t| t.is_dir() || t.to_string_lossy().ends_with(std::path::MAIN_SEPARATOR));
+    if per_file.is_some() && (cli.command.is_some() || sqlite) {
+        eprintln!(
+            "Error: --output DIR writes a file per input file, which only the default mode does, in any format but sqlite"
+        );
+        output::exit(1);
+    }
+    // SQLite writes the database itself
+    let _pending = match &target {
+        Some(file) if per_file.is_none() && !sqlite => {
+            Some(output::to_file(file).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                output::exit(1);
+            }))
+        }
+        _ => None,
+    };
+    let per_file = per_file.cloned();
+
+    match (cli.command, cli.args) {
+        (
+            Some(Command::Closure {
+                function,
+                roots,
+                no_spec_deps,
+                depth,
+                within_module,
+                args,
+            }),
+            _,
+        ) => run_closure(
+            &args,
+            function.as_deref(),
+            &roots,
+            !no_spec_deps,
+            depth,
+            within_module.as_deref(),
+        ),
+        (
+            Some(Command::Callers {
+                function,
+                direct_only,
+                depth,
+                args,
+            }),
+            _,
+        ) => {
+            let depth = if direct_only { Some(1) } else { depth };
+            run_callers(&args, &function, depth)
+        }
+        (Some(Command::CallGraph { sccs, root, args }), _) => {
+            run_call_graph(&args, sccs, root.as_deref())
+        }
+        (Some(Command::ModuleCover { function, args }), _) => run_module_cover(&args, &function),
+        (Some(Command::ModuleGraph { args }), _) => run_module_graph(&args),
+        (Some(Command::UnusedLemmas { allow, args }), _) => run_unused_lemmas(&args, &allow),
+        (Some(Command::Trusted { args }), _) => run_trusted(&args),
+        (Some(Command::OpaqueReveals { args }), _) => run_opaque_reveals(&args),
+        (Some(Command::Quantifiers { args }), _) => run_quantifiers(&args),
+        (Some(Command::NonlinearArith { args }), _) => run_nonlinear_arith(&args),
+        (Some(Command::Rlimits { above, args }), _) => run_rlimits(&args, above),
+        (
+            Some(Command::EmitPlan {
+                template,
+                style,
+                args,
+            }),
+            _,
+        ) => run_emit_plan(&args, &template, &style),
+        (
+            Some(Command::Plan {
+                strategy,
+                job_cost,
+                args,
+            }),
+            _,
+        ) => run_plan(&args, strategy, job_cost),
+        (
+            Some(Command::DeadFunctions {
+                roots,
+                root_args,
+                args,
+            }),
+            _,
+        ) => run_dead_functions(&args, &roots, &root_args),
+        (Some(Command::MissingDecreases { args }), _) => run_missing_decreases(&args),
+        (Some(Command::Query { args }), _) => {
+            if let Err(e) = query::run(&args) {
+                eprintln!("Error: {}", e);
+                output::exit(1);
+            }
+        }
+        (Some(Command::Diff { args }), _) => run_diff(&args),
+        (None, Some(args)) => {
+            let options = ExtractOptions::from_args(&args);
+            if let Some(dir) = per_file {
+                print_per_file(&args, &options, &dir);
+                return;
+            }
+            // Resolving calls and the checks need every function at once
+            // So do orders other than the files'
+            let streamed = !options.show_calls
+                && matches!(args.sort, None | Some(SortKey::File))
+                && !args.reverse
+                && args.fail_if_assumes.is_none()
+                && args.fail_if_termination_unchecked.is_none()
+                && args.write_baseline.is_none()
+                && args.check_baseline.is_none()
+                && args.shard_count.is_none();
+            if args.count_only && args.shard_count.is_none() {
+                count_functions(&args, &options);
+                finish_output();
+                return;
+            }
+            if matches!(args.format, OutputFormat::Ndjson) && streamed {
+                stream_functions(&args, &options);
+                finish_output();
+                return;
+            }
+            let mut extraction = extract(&args, &options);
+            extraction.functions = args.shard(extraction.functions);
+            if args.count_only {
+                let mut counts = Counts::new(options.show_kind);
+                counts.add(&extraction.functions);
+                counts.total_files = extraction.total_files;
+                counts.print(&args.format);
+            } else if args.stats {
+                print_stats(&args.format, &extraction.functions);
+            } else {
+                print_functions(
+                    &args.output(),
+                    &extraction.functions,
+                    &extraction.declarations,
+                    extraction.total_files,
+                    None,
+                );
+            }
+            // The output is complete even when a check fails
+            finish_output();
+            if let Some(ref path) = args.write_baseline {
+                let written = baseline::Baseline::of(&extraction.functions).write(path);
+                if let Err(e) = written {
+                    eprintln!("Error: {}", e);
+                    output::exit(1);
+                }
+            }
+            if let Some(max) = args.fail_if_assumes {
+                check_proof_debt(&extraction.functions, max);
+            }
+            if let Some(max) = args.fail_if_termination_unchecked {
+                check_termination_unchecked(&extraction.functions, max);
+            }
+            if let Some(ref path) = args.check_baseline {
+                check_baseline(&extraction.functions, path);
+            }
+        }
+        (None, None) => unreachable!("clap requires PATH without a subcommand"),
+    }
+    finish_output();
+}
@@ -0,0 +1,116 @@
+//! Markdown tables for pasting function inventories into issues and PRs
+//!
+//! One table per module (or per file), with the function's qualified name,
+//! kind, lines and spec status, then the totals. Cells are escaped so that
+//! a `|` in a signature does not end the cell.
+
+use crate::{FunctionInfo, GroupBy, Summary};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Escape a cell: `|` would end it and a newline the row
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// `specified` or `unspecified` for exec functions, the mode for spec and
+/// proof functions, plus the reasons a function is trusted
+fn spec_status(func: &FunctionInfo) -> String {
+    let mode = func.mode().unwrap_or("fn");
+    let mut status = match func.specified {
+        _ if func.is_external() => "external".to_string(),
+        Some(true) => "specified".to_string(),
+        Some(false) => "unspecified".to_string(),
+        None if mode.starts_with("spec") => "spec".to_string(),
+        None if mode.starts_with("proof") => "proof".to_string(),
+        None => "-".to_string(),
+    };
+    if !func.trusted.is_empty() {
+        let reasons: Vec<&str> = func.trusted.iter().map(String::as_str).collect();
+        write!(status, " (trusted: {})", reasons.join(", ")).unwrap();
+    }
+    status
+}
+
+/// Render the functions as tables grouped by module or file, followed by
+/// the totals of `summary`
+pub fn render(functions: &[FunctionInfo], summary: &Summary, group_by: &GroupBy) -> String {
+    let mut groups: BTreeMap<String, Vec<&FunctionInfo>> = BTreeMap::new();
+    for func in functions {
+        let key = match group_by {
+            GroupBy::Module if func.module_path.is_empty() => "crate".to_string(),
+            GroupBy::Module => func.module_path.clone(),
+            GroupBy::File => func.file.clone().unwrap_or_else(|| "<input>".to_string()),
+        };
+        groups.entry(key).or_default().push(func);
+    }
+    let signatures = functions.iter().any(|f| f.signature.is_some());
+
+    let mut out = String::new();
+    for (key, mut funcs) in groups {
+        funcs.sort_by(|a, b| (&a.file, a.start_line).cmp(&(&b.file, b.start_line)));
+        writeln!(out, "## `{}`\n", cell(&key)).unwrap();
+        if signatures {
+            writeln!(out, "| Function | Kind | Lines | Spec status | Signature |").unwrap();
+            writeln!(out, "|---|---|---|---|---|").unwrap();
+        } else {
+            writeln!(out, "| Function | Kind | Lines | Spec status |").unwrap();
+            writeln!(out, "|---|---|---|---|").unwrap();
+        }
+        for func in funcs {
+            write!(
+                out,
+                "| `{}` | {} | {}-{} | {} |",
+                cell(&func.qualified_name),
+                cell(func.kind.as_deref().unwrap_or("fn")),
+                func.start_line,
+                func.end_line,
+                cell(&spec_status(func))
+            )
+            .unwrap();
+            if signatures {
+                match &func.signature {
+                    Some(signature) => write!(out, " `{}` |", cell(signature)).unwrap(),
+                    None => write!(out, " |").unwrap(),
+                }
+            }
+            writeln!(out).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+
+    writeln!(out, "## Totals\n").unwrap();
+    writeln!(out, "- Files: {}", summary.total_files).unwrap();
+    writeln!(out, "- Functions: {}", summary.total_functions).unwrap();
+    let mut kinds: BTreeMap<&str, usize> = BTreeMap::new();
+    for func in functions {
+        *kinds
+            .entry(func.kind.as_deref().unwrap_or("fn"))
+            .or_default() += 1;
+    }
+    let kinds: Vec<String> = kinds
+        .iter()
+        .map(|(kind, count)| format!("{} {}", count, kind))
+        .collect();
+    if !kinds.is_empty() {
+        writeln!(out, "- By kind: {}", kinds.join(", ")).unwrap();
+    }
+    let exec: usize = summary
+        .spec_coverage
+        .values()
+        .map(|c| c.exec_functions)
+        .sum();
+    let specified: usize = summary.spec_coverage.values().map(|c| c.specified).sum();
+    if exec > 0 {
+        writeln!(
+            out,
+            "- Specified exec functions: {}/{} ({:.1}%)",
+            specified,
+            exec,
+            100.0 * specified as f64 / exec as f64
+        )
+        .unwrap();
+    }
+    writeln!(out, "- Trusted functions: {}", summary.trusted_functions).unwrap();
+    out
+}
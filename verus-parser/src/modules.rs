@@ -0,0 +1,184 @@
+//! Module paths from the `mod` declarations of a crate
+//!
+//! Starting from each crate root (`src/lib.rs`, `src/main.rs`, the binaries,
+//! tests, examples and benches cargo finds, see `targets::is_root`), every
+//! `mod foo;` is followed to the file it loads, honoring `#[path = ".."]`,
+//! so each reachable file gets the module path the compiler gives it rather
+//! than one guessed from its location. Declarations inside inline modules,
+//! `verus!` and `cfg_if!` count too. `#[cfg]` is not evaluated, so every
+//! alternative backend is reachable, but the conditions on the way to each
+//! file are recorded, as are the `loop_isolation` and
+//! `exec_allows_no_decreases_clause` settings it inherits.
+
+use crate::{cfg, loops, specs, targets, CfgIfMacroBody, VerusMacroBody};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use verus_syn::{Attribute, Expr, ExprLit, Item, Lit, Meta};
+
+/// Module of every file reachable from a crate root, by canonical path
+pub type ModuleMap = HashMap<PathBuf, FileModule>;
+
+/// The module a file defines
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileModule {
+    pub path: Vec<String>,
+    /// `#[cfg]` conditions of the `mod` declarations and `cfg_if!`
+    /// branches leading to the file
+    pub cfg: Vec<String>,
+    /// `#[verifier::loop_isolation(..)]` of the innermost module declaring
+    /// one on the way to the file
+    pub loop_isolation: Option<bool>,
+    /// Whether one of them is `#[verifier::exec_allows_no_decreases_clause]`
+    pub termination_unchecked: bool,
+}
+
+/// The path a file is known by in a `ModuleMap`
+pub fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The crate roots among `files`, the roots of cargo targets; library roots
+/// come first, so a file a binary also declares a module for keeps the
+/// library's module path
+pub fn crate_roots(files: &[PathBuf]) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = files
+        .iter()
+        .filter(|file| targets::is_root(file))
+        .cloned()
+        .collect();
+    roots.sort_by_key(|root| {
+        !root.ends_with("src/lib.rs") && targets::target_of(root).as_deref() != Some("lib")
+    });
+    roots
+}
+
+/// Follow the `mod` declarations from each root
+pub fn build(roots: &[PathBuf]) -> ModuleMap {
+    let mut map = ModuleMap::new();
+    for root in roots {
+        load(&mut map, root, FileModule::default(), true);
+    }
+    map
+}
+
+/// Record `file` as module `module` and follow its declarations; a
+/// `mod.rs`-like file (crate roots, `mod.rs`, `#[path]` targets) looks for
+/// its submodules next to itself, any other in a directory named after it
+fn load(map: &mut ModuleMap, file: &Path, mut module: FileModule, mod_rs: bool) {
+    let key = canonical(file);
+    if map.contains_key(&key) {
+        return;
+    }
+    map.insert(key, module.clone());
+    let Ok(content) = fs::read_to_string(file) else {
+        return;
+    };
+    // Only the `mod` declarations matter here
+    if !content.contains("mod") {
+        return;
+    }
+    let Ok(syntax_tree) = verus_syn::parse_file(&content) else {
+        return;
+    };
+    // `#![cfg(..)]` at the top of the file holds for its submodules too
+    module.cfg.extend(cfg::conditions(&syntax_tree.attrs));
+    module.loop_isolation = loops::isolation(&syntax_tree.attrs).or(module.loop_isolation);
+    module.termination_unchecked |= specs::allows_no_decreases(&syntax_tree.attrs);
+    let dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+    let base = match file.file_stem() {
+        Some(stem) if !mod_rs => dir.join(stem),
+        _ => dir.clone(),
+    };
+    let location = Location {
+        dir: &dir,
+        base,
+        inline: false,
+    };
+    follow(map, &syntax_tree.items, &module, &location);
+}
+
+/// Where the submodules declared by some items are looked up
+struct Location<'a> {
+    /// Directory of the file containing the items, against which top-level
+    /// `#[path]`s resolve
+    dir: &'a Path,
+    /// Directory of the submodules without `#[path]`, also used for
+    /// `#[path]`s inside inline modules
+    base: PathBuf,
+    /// Whether the items are inside an inline `mod foo { .. }`
+    inline: bool,
+}
+
+/// Follow the `mod` declarations among the items of `module`
+fn follow(map: &mut ModuleMap, items: &[Item], module: &FileModule, location: &Location) {
+    for item in items {
+        match item {
+            Item::Mod(m) => {
+                let name = m.ident.to_string();
+                let mut submodule = module.clone();
+                submodule.path.push(name.clone());
+                submodule.cfg.extend(cfg::conditions(&m.attrs));
+                submodule.loop_isolation = loops::isolation(&m.attrs).or(module.loop_isolation);
+                submodule.termination_unchecked |= specs::allows_no_decreases(&m.attrs);
+                match &m.content {
+                    Some((_, content)) => {
+                        let nested = Location {
+                            dir: location.dir,
+                            base: location.base.join(&name),
+                            inline: true,
+                        };
+                        follow(map, content, &submodule, &nested);
+                    }
+                    None => match path_attr(&m.attrs) {
+                        Some(target) if location.inline => {
+                            load(map, &location.base.join(target), submodule, true)
+                        }
+                        Some(target) => load(map, &location.dir.join(target), submodule, true),
+                        None => {
+                            let file = location.base.join(format!("{}.rs", name));
+                            if file.exists() {
+                                load(map, &file, submodule, false);
+                            } else {
+                                load(
+                                    map,
+                                    &location.base.join(&name).join("mod.rs"),
+                                    submodule,
+                                    true,
+                                );
+                            }
+                        }
+                    },
+                }
+            }
+            Item::Macro(m) if m.mac.path.is_ident("verus") => {
+                if let Ok(body) = verus_syn::parse2::<VerusMacroBody>(m.mac.tokens.clone()) {
+                    follow(map, &body.items, module, location);
+                }
+            }
+            Item::Macro(m) if m.mac.path.is_ident("cfg_if") => {
+                if let Ok(branches) = verus_syn::parse2::<CfgIfMacroBody>(m.mac.tokens.clone()) {
+                    for (conditions, branch) in &branches.branches {
+                        let mut nested = module.clone();
+                        nested.cfg.extend(conditions.iter().cloned());
+                        follow(map, branch, &nested, location);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The value of a `#[path = ".."]` attribute
+fn path_attr(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(nv) if nv.path.is_ident("path") => match &nv.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(s), ..
+            }) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
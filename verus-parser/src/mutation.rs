@@ -0,0 +1,64 @@
+//! Functions that change the state of their caller
+//!
+//! A function mutates when it takes a `&mut` parameter or receiver
+//! (possibly inside `Tracked<..>`/`Ghost<..>`), or when its `ensures`
+//! clauses relate the result to the `old(..)` value of an argument.
+
+use verus_syn::visit::Visit;
+use verus_syn::{Expr, ExprCall, FnArgKind, GenericArgument, PathArguments, Signature, Type};
+
+/// Whether a type is a mutable reference, directly or as the argument of
+/// a `Tracked`/`Ghost` wrapper
+fn is_mut_ref(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(r) => r.mutability.is_some(),
+        Type::Paren(p) => is_mut_ref(&p.elem),
+        Type::Path(p) if p.qself.is_none() => {
+            let Some(last) = p.path.segments.last() else {
+                return false;
+            };
+            let PathArguments::AngleBracketed(args) = &last.arguments else {
+                return false;
+            };
+            matches!(last.ident.to_string().as_str(), "Tracked" | "Ghost")
+                && args
+                    .args
+                    .iter()
+                    .any(|arg| matches!(arg, GenericArgument::Type(ty) if is_mut_ref(ty)))
+        }
+        _ => false,
+    }
+}
+
+/// Whether a parameter or the receiver is taken by `&mut`
+pub fn takes_mut(sig: &Signature) -> bool {
+    sig.inputs.iter().any(|arg| match &arg.kind {
+        FnArgKind::Receiver(r) => is_mut_ref(&r.ty),
+        FnArgKind::Typed(t) => is_mut_ref(&t.ty),
+    })
+}
+
+/// Whether an `ensures` clause calls `old(..)`
+pub fn uses_old(sig: &Signature) -> bool {
+    let mut visitor = OldVisitor::default();
+    for clause in sig.spec.ensures.iter().flat_map(|e| e.exprs.exprs.iter()) {
+        visitor.visit_expr(clause);
+    }
+    visitor.found
+}
+
+#[derive(Default)]
+struct OldVisitor {
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for OldVisitor {
+    fn visit_expr_call(&mut self, node: &'ast ExprCall) {
+        if let Expr::Path(p) = &*node.func {
+            if p.qself.is_none() && p.path.is_ident("old") {
+                self.found = true;
+            }
+        }
+        verus_syn::visit::visit_expr_call(self, node);
+    }
+}
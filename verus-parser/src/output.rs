@@ -0,0 +1,152 @@
+//! `-o/--output`: writing the output to a file instead of stdout
+//!
+//! The output goes to a temporary file next to the target, renamed over it
+//! once everything is written, so a reader never sees a partial file. A run
+//! that fails first leaves no file behind: the temporary file is removed by
+//! [`exit`] on an error exit and by the [`Pending`] guard when a panic
+//! unwinds. Without `--output`, `out!` and `outln!` print to stdout.
+
+use serde::Serialize;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// The file being written
+struct Sink {
+    file: BufWriter<File>,
+    temp: PathBuf,
+    target: PathBuf,
+}
+
+static SINK: Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Whether [`json`] indents, set from `--pretty` and `--compact`
+static PRETTY: AtomicBool = AtomicBool::new(true);
+
+/// Print to the output: `--output`'s file, or stdout
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::output::write(format_args!($($arg)*))
+    };
+}
+
+/// Print a line to the output: `--output`'s file, or stdout
+macro_rules! outln {
+    () => {
+        $crate::output::write(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/// Removes the temporary file of an output not committed by the time it is
+/// dropped, as when a panic unwinds
+#[must_use]
+pub struct Pending;
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        discard();
+    }
+}
+
+/// Send the output to `target` from now on, until [`commit`]
+pub fn to_file(target: &Path) -> Result<Pending, String> {
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let name = target
+        .file_name()
+        .ok_or_else(|| format!("{} is not a file name", target.display()))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp = target.with_file_name(temp_name);
+    let file =
+        File::create(&temp).map_err(|e| format!("Failed to create {}: {}", temp.display(), e))?;
+    *SINK.lock().unwrap() = Some(Sink {
+        file: BufWriter::new(file),
+        temp,
+        target: target.to_path_buf(),
+    });
+    Ok(Pending)
+}
+
+/// Write to the output file, or print to stdout without one; a closed
+/// stdout ends the run quietly
+pub fn write(args: fmt::Arguments) {
+    let mut sink = SINK.lock().unwrap();
+    match sink.as_mut() {
+        Some(sink) => {
+            if let Err(e) = sink.file.write_fmt(args) {
+                let temp = sink.temp.clone();
+                eprintln!("Error: Failed to write {}: {}", temp.display(), e);
+                let _ = fs::remove_file(temp);
+                std::process::exit(1);
+            }
+        }
+        None => {
+            if let Err(e) = io::stdout().write_fmt(args) {
+                // A reader closing the pipe early, as `| head` does, has
+                // read all it wanted
+                if e.kind() == io::ErrorKind::BrokenPipe {
+                    std::process::exit(0);
+                }
+                eprintln!("Error: Failed to write to stdout: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Indent the JSON of [`json`], or minify it
+pub fn set_pretty(pretty: bool) {
+    PRETTY.store(pretty, Ordering::Relaxed);
+}
+
+/// `value` as JSON, indented or minified as set; newlines in strings are
+/// escaped either way, so minified JSON stays on one line
+pub fn json<T: Serialize + ?Sized>(value: &T) -> String {
+    if PRETTY.load(Ordering::Relaxed) {
+        serde_json::to_string_pretty(value).unwrap()
+    } else {
+        serde_json::to_string(value).unwrap()
+    }
+}
+
+/// Move the finished output file into place; stdout needs nothing
+pub fn commit() -> Result<(), String> {
+    let Some(mut sink) = SINK.lock().unwrap().take() else {
+        return Ok(());
+    };
+    let written = sink
+        .file
+        .flush()
+        .and_then(|()| sink.file.get_ref().sync_all())
+        .and_then(|()| fs::rename(&sink.temp, &sink.target));
+    written.map_err(|e: io::Error| {
+        let _ = fs::remove_file(&sink.temp);
+        format!("Failed to write {}: {}", sink.target.display(), e)
+    })
+}
+
+/// Drop the output written so far
+fn discard() {
+    // A panic while writing leaves the lock poisoned, the sink still valid
+    let sink = SINK.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(sink) = sink {
+        drop(sink.file);
+        let _ = fs::remove_file(sink.temp);
+    }
+}
+
+/// Exit with `code`, dropping an output file not committed
+pub fn exit(code: i32) -> ! {
+    discard();
+    std::process::exit(code)
+}
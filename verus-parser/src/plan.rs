@@ -0,0 +1,177 @@
+//! `emit-plan`: a command per function, as a shell script or a Makefile
+//!
+//! The template is a command line with placeholders, each replaced by a
+//! property of the function quoted as one shell word (see
+//! [`shell::quote`]): `{module}` (the module path, empty for the crate
+//! root), `{module_args}` (the Verus arguments selecting the module:
+//! `--verify-root` for the crate root, `--verify-only-module` and the module
+//! otherwise), `{function}` (the `--verify-function` name), `{qualified}`
+//! (the qualified name) and `{file}`. The rest of the template is copied as
+//! it is, so it may use the shell's own syntax; `${..}` is left to the shell.
+
+use crate::shell;
+use crate::FunctionInfo;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// How the commands are laid out (`--style`)
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum PlanStyle {
+    /// A `/bin/sh` script running the commands one after the other
+    Shell,
+    /// A Makefile with a phony target per function and `all` depending on
+    /// every one, for `make -j`
+    Makefile,
+}
+
+#[derive(Debug)]
+enum Piece {
+    Text(String),
+    Module,
+    ModuleArgs,
+    Function,
+    Qualified,
+    File,
+}
+
+/// A parsed `--template`
+#[derive(Debug)]
+pub struct Template {
+    pieces: Vec<Piece>,
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .map(|end| start + end)
+                .ok_or_else(|| format!("Unclosed `{{` in template: {}", template))?;
+            // A shell parameter, not a placeholder
+            if rest[..start].ends_with('$') {
+                text.push_str(&rest[..=end]);
+                rest = &rest[end + 1..];
+                continue;
+            }
+            text.push_str(&rest[..start]);
+            let piece = match &rest[start + 1..end] {
+                "module" => Piece::Module,
+                "module_args" => Piece::ModuleArgs,
+                "function" => Piece::Function,
+                "qualified" => Piece::Qualified,
+                "file" => Piece::File,
+                other => {
+                    return Err(format!(
+                        "Unknown placeholder {{{}}} in template; expected {{module}}, \
+                         {{module_args}}, {{function}}, {{qualified}} or {{file}}",
+                        other
+                    ))
+                }
+            };
+            if !text.is_empty() {
+                pieces.push(Piece::Text(std::mem::take(&mut text)));
+            }
+            pieces.push(piece);
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Template { pieces })
+    }
+
+    /// The command for `func`
+    pub fn render(&self, func: &FunctionInfo) -> String {
+        let mut command = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Text(text) => command.push_str(text),
+                Piece::Module => command.push_str(&shell::quote(&func.module_path)),
+                Piece::ModuleArgs if func.module_path.is_empty() => {
+                    command.push_str("--verify-root")
+                }
+                Piece::ModuleArgs => {
+                    command.push_str("--verify-only-module ");
+                    command.push_str(&shell::quote(&func.module_path));
+                }
+                Piece::Function => command.push_str(&shell::quote(&func.verify_function_arg)),
+                Piece::Qualified => command.push_str(&shell::quote(&func.qualified_name)),
+                Piece::File => command.push_str(&shell::quote(func.file.as_deref().unwrap_or(""))),
+            }
+        }
+        command
+    }
+}
+
+/// A Makefile target name for a qualified name: `::` becomes `.`, and
+/// characters make or the shell treat specially become `_`
+fn target_name(qualified_name: &str) -> String {
+    qualified_name
+        .replace("::", ".")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "_-+.,/@".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// The plan for the functions, one command each; functions rendering to
+/// the same command as an earlier one are left out
+pub fn render(template: &Template, style: &PlanStyle, functions: &[&FunctionInfo]) -> String {
+    let mut seen = HashSet::new();
+    let commands: Vec<(&FunctionInfo, String)> = functions
+        .iter()
+        .map(|&func| (func, template.render(func)))
+        .filter(|(_, command)| seen.insert(command.clone()))
+        .collect();
+
+    let mut out = String::new();
+    match style {
+        PlanStyle::Shell => {
+            writeln!(out, "#!/bin/sh").unwrap();
+            writeln!(out, "# Generated by verus-parser emit-plan").unwrap();
+            for (_, command) in &commands {
+                writeln!(out, "{}", command).unwrap();
+            }
+        }
+        PlanStyle::Makefile => {
+            // Targets differing only in the characters replaced get a suffix
+            let mut names = HashSet::new();
+            let targets: Vec<String> = commands
+                .iter()
+                .map(|(func, _)| {
+                    let base = target_name(&func.qualified_name);
+                    let mut name = base.clone();
+                    let mut n = 1;
+                    while !names.insert(name.clone()) {
+                        n += 1;
+                        name = format!("{}-{}", base, n);
+                    }
+                    name
+                })
+                .collect();
+            writeln!(out, "# Generated by verus-parser emit-plan").unwrap();
+            writeln!(out, ".PHONY: all").unwrap();
+            write!(out, "all:").unwrap();
+            for target in &targets {
+                write!(out, " \\\n\t{}", target).unwrap();
+            }
+            writeln!(out).unwrap();
+            for (target, (func, command)) in targets.iter().zip(&commands) {
+                writeln!(out, "\n# {}", func.qualified_name).unwrap();
+                writeln!(out, ".PHONY: {}", target).unwrap();
+                writeln!(out, "{}:", target).unwrap();
+                writeln!(out, "\t{}", command.replace('$', "$$")).unwrap();
+            }
+        }
+    }
+    out
+}
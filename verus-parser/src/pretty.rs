@@ -0,0 +1,268 @@
+//! Readable, deterministic rendering of token streams.
+//!
+//! `TokenStream::to_string()` separates every token with a space
+//! (`limbs_bounded (self , 51)`), which is hard to read and grep. The
+//! printer here applies the usual Rust/Verus spacing conventions instead
+//! (`limbs_bounded(self, 51)`), always producing the same text for the
+//! same tokens so the output can be diffed between runs.
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+
+/// Render any syntax node in expression position (`<` is a comparison
+/// unless it follows `::`)
+pub fn pretty<T: ToTokens>(node: &T) -> String {
+    pretty_tokens(node.to_token_stream(), false)
+}
+
+/// Render a type or generics list, where `<` and `>` are always brackets
+pub fn pretty_type<T: ToTokens>(node: &T) -> String {
+    pretty_tokens(node.to_token_stream(), true)
+}
+
+/// Render a token stream, collapsing all whitespace to single spaces
+pub fn pretty_tokens(tokens: TokenStream, type_mode: bool) -> String {
+    let toks = lex(tokens);
+    let mut out = String::new();
+    write_seq(&toks, type_mode, &mut out);
+    out
+}
+
+enum Tok {
+    /// Identifier, literal or lifetime
+    Word(String),
+    /// Operator, with joint punctuation merged (`::`, `==>`, `&&&`)
+    Op(String),
+    Group(Delimiter, Vec<Tok>),
+}
+
+fn lex(tokens: TokenStream) -> Vec<Tok> {
+    let mut toks = Vec::new();
+    let mut pending_op = String::new();
+    let mut lifetime = false;
+
+    for tree in tokens {
+        match tree {
+            TokenTree::Punct(p) => {
+                if p.as_char() == '\'' && pending_op.is_empty() && p.spacing() == Spacing::Joint {
+                    lifetime = true;
+                    continue;
+                }
+                pending_op.push(p.as_char());
+                if p.spacing() == Spacing::Alone {
+                    toks.push(Tok::Op(std::mem::take(&mut pending_op)));
+                }
+            }
+            other => {
+                if !pending_op.is_empty() {
+                    toks.push(Tok::Op(std::mem::take(&mut pending_op)));
+                }
+                match other {
+                    TokenTree::Ident(ident) if lifetime => {
+                        lifetime = false;
+                        toks.push(Tok::Word(format!("'{}", ident)));
+                    }
+                    TokenTree::Ident(ident) => toks.push(Tok::Word(ident.to_string())),
+                    TokenTree::Literal(lit) => toks.push(Tok::Word(lit.to_string())),
+                    TokenTree::Group(g) => {
+                        if g.delimiter() == Delimiter::None {
+                            toks.extend(lex(g.stream()));
+                        } else {
+                            toks.push(Tok::Group(g.delimiter(), lex(g.stream())));
+                        }
+                    }
+                    TokenTree::Punct(_) => unreachable!(),
+                }
+            }
+        }
+    }
+    if !pending_op.is_empty() {
+        toks.push(Tok::Op(pending_op));
+    }
+    toks
+}
+
+/// What the previously written token was, which decides the spacing
+/// before the next one
+#[derive(Clone, Copy, PartialEq)]
+enum Prev {
+    /// Start of a sequence: never a space
+    Start,
+    /// Identifier or literal
+    Word,
+    /// Keyword after which `(`/`[` and unary operators are spaced
+    Keyword,
+    /// End of an operand: `)`, `]`, `}`, `?`, `@`, closing `>`
+    Close,
+    /// Binary operator or separator: always followed by a space
+    Spaced,
+    /// Operator that binds to the next token: `.`, `::`, `#`, unary `-`
+    Tight,
+}
+
+const KEYWORDS: &[&str] = &[
+    "if",
+    "while",
+    "match",
+    "return",
+    "in",
+    "for",
+    "let",
+    "else",
+    "as",
+    "mut",
+    "ref",
+    "move",
+    "by",
+    "requires",
+    "ensures",
+    "recommends",
+    "decreases",
+    "invariant",
+    "when",
+    "via",
+    "dyn",
+    "impl",
+    "where",
+    "break",
+    "yield",
+    "forall",
+    "exists",
+    "choose",
+];
+
+fn write_seq(toks: &[Tok], type_mode: bool, out: &mut String) {
+    let mut prev = Prev::Start;
+    let mut last_word = "";
+    let mut generic_depth = 0usize;
+    let mut closure_params = false;
+
+    for tok in toks {
+        match tok {
+            Tok::Word(word) => {
+                if matches!(
+                    prev,
+                    Prev::Word | Prev::Keyword | Prev::Close | Prev::Spaced
+                ) {
+                    out.push(' ');
+                }
+                out.push_str(word);
+                last_word = word;
+                prev = if KEYWORDS.contains(&word.as_str()) {
+                    Prev::Keyword
+                } else {
+                    Prev::Word
+                };
+            }
+            Tok::Group(delim, inner) => {
+                let after_path = out.ends_with("::");
+                let space = match delim {
+                    Delimiter::Parenthesis | Delimiter::Bracket => {
+                        matches!(prev, Prev::Keyword | Prev::Spaced)
+                    }
+                    _ => out.ends_with('!') || !matches!(prev, Prev::Start | Prev::Tight),
+                };
+                if space {
+                    out.push(' ');
+                }
+                let (open, close) = match delim {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                let padded = *delim == Delimiter::Brace && !after_path && !inner.is_empty();
+                out.push_str(open);
+                if padded {
+                    out.push(' ');
+                }
+                write_seq(inner, type_mode, out);
+                if padded {
+                    out.push(' ');
+                }
+                out.push_str(close);
+                prev = Prev::Close;
+            }
+            Tok::Op(op) => {
+                let op = op.as_str();
+                let operand_before = matches!(prev, Prev::Word | Prev::Close);
+
+                match op {
+                    "," | ";" | ":" => {
+                        out.push_str(op);
+                        prev = Prev::Spaced;
+                    }
+                    "." | "::" | ".." | "..=" | "$" => {
+                        if op == "::" && matches!(prev, Prev::Spaced | Prev::Keyword) {
+                            out.push(' ');
+                        }
+                        out.push_str(op);
+                        prev = Prev::Tight;
+                    }
+                    "?" | "@" => {
+                        out.push_str(op);
+                        prev = Prev::Close;
+                    }
+                    "#" => {
+                        if matches!(
+                            prev,
+                            Prev::Word | Prev::Keyword | Prev::Close | Prev::Spaced
+                        ) {
+                            out.push(' ');
+                        }
+                        out.push('#');
+                        prev = Prev::Tight;
+                    }
+                    // macro invocation `name!` or inner attribute `#!`
+                    "!" if prev == Prev::Word || prev == Prev::Tight => {
+                        out.push('!');
+                        prev = Prev::Tight;
+                    }
+                    "|" if closure_params => {
+                        out.push('|');
+                        closure_params = false;
+                        prev = Prev::Spaced;
+                    }
+                    "|" if !operand_before => {
+                        // opening a closure; quantifiers are written `forall|x| ...`
+                        let quantifier = matches!(last_word, "forall" | "exists" | "choose");
+                        let attached = quantifier && prev == Prev::Keyword;
+                        if !attached && !matches!(prev, Prev::Start | Prev::Tight) {
+                            out.push(' ');
+                        }
+                        out.push('|');
+                        closure_params = true;
+                        prev = Prev::Tight;
+                    }
+                    "<" if type_mode || prev == Prev::Tight => {
+                        out.push('<');
+                        generic_depth += 1;
+                        prev = Prev::Tight;
+                    }
+                    _ if generic_depth > 0 && op.chars().all(|c| c == '>') => {
+                        for _ in op.chars() {
+                            out.push('>');
+                            generic_depth = generic_depth.saturating_sub(1);
+                        }
+                        prev = Prev::Close;
+                    }
+                    // unary operator: binds to its operand
+                    "&" | "*" | "-" | "!" | "&&" if !operand_before => {
+                        if !matches!(prev, Prev::Start | Prev::Tight) {
+                            out.push(' ');
+                        }
+                        out.push_str(op);
+                        prev = Prev::Tight;
+                    }
+                    _ => {
+                        if prev != Prev::Start {
+                            out.push(' ');
+                        }
+                        out.push_str(op);
+                        prev = Prev::Spaced;
+                    }
+                }
+            }
+        }
+    }
+}
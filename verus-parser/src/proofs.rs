@@ -0,0 +1,194 @@
+//! Proof code inside function bodies
+//!
+//! `proof { ... }` blocks are counted wherever they appear in the body
+//! (inside loops, match arms, closures); a proof block nested in another
+//! counts once, as part of the outer one. `assert`s, `assume`s and
+//! `admit()`s are counted the same way, including those inside proof
+//! blocks and `assert ... by` bodies. Prover annotations (`by (bit_vector)`,
+//! `by (nonlinear_arith)`, `by (compute)`) are counted by prover on
+//! asserts and on the function itself. `reveal` and `reveal_with_fuel`
+//! statements are counted too, with the highest literal fuel requested.
+//! `calc!` invocations are found among the tokens of every macro in the
+//! body, so those nested in another `calc!` count without parsing it.
+//! `assert ... by { ... }` sub-proofs are counted at every nesting level,
+//! but only the outermost ones add to the lines spanned.
+//! Closures are part of the function defining them, while nested items
+//! belong to those items.
+
+use crate::pretty::pretty;
+use proc_macro2::{Span, TokenStream, TokenTree};
+use std::collections::BTreeMap;
+use verus_syn::spanned::Spanned;
+use verus_syn::visit::Visit;
+use verus_syn::{
+    Assert, AssertForall, Assume, Block, Expr, ExprCall, ExprLit, ExprUnary, Item, Lit, Macro,
+    RevealHide, Signature, UnOp,
+};
+
+/// Proof code found in one function body
+#[derive(Debug, Default)]
+pub struct ProofFacts {
+    /// Outermost `proof { ... }` blocks
+    pub proof_blocks: usize,
+    /// Lines spanned by those blocks
+    pub proof_lines: usize,
+    /// `assert(..)`, `assert(..) by ..` and `assert forall .. by ..`
+    pub asserts: usize,
+    /// `assume(..)`s
+    pub assumes: usize,
+    /// `admit()` calls
+    pub admits: usize,
+    /// `by (prover)` annotations, by prover name
+    pub provers: BTreeMap<String, usize>,
+    /// `reveal(..)` and `reveal_with_fuel(..)` statements
+    pub reveals: usize,
+    /// Highest fuel of the `reveal_with_fuel`s with a literal fuel
+    pub max_fuel: Option<u64>,
+    /// Line of each `calc!` invocation
+    pub calcs: Vec<usize>,
+    /// `assert(..) by { .. }` and `assert forall .. by { .. }` sub-proofs
+    pub by_blocks: usize,
+    /// Lines spanned by the outermost of those
+    pub by_block_lines: usize,
+    /// The assertion each sub-proof proves, in source order
+    pub by_asserts: Vec<String>,
+}
+
+/// Proof blocks, asserts, assumes, admits and prover annotations of a
+/// function
+pub fn scan(sig: &Signature, body: Option<&Block>) -> ProofFacts {
+    let mut visitor = ProofVisitor::default();
+    if let Some(prover) = &sig.spec.prover {
+        visitor.count_prover(&prover.id);
+    }
+    if let Some(block) = body {
+        visitor.visit_block(block);
+    }
+    visitor.facts
+}
+
+/// Record the line of every `calc!` among `tokens`
+fn calcs_in(tokens: TokenStream, lines: &mut Vec<usize>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if ident == "calc" => {
+                if matches!(tokens.get(i + 1), Some(TokenTree::Punct(p)) if p.as_char() == '!') {
+                    lines.push(ident.span().start().line);
+                }
+            }
+            TokenTree::Group(group) => calcs_in(group.stream(), lines),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct ProofVisitor {
+    facts: ProofFacts,
+    in_proof: bool,
+    in_by_block: bool,
+}
+
+impl ProofVisitor {
+    /// Record an `assert ... by` sub-proof of `asserted`, then visit it
+    fn by_block(&mut self, span: Span, asserted: String, visit: impl FnOnce(&mut Self)) {
+        self.facts.by_blocks += 1;
+        self.facts.by_asserts.push(asserted);
+        if self.in_by_block {
+            visit(self);
+            return;
+        }
+        self.facts.by_block_lines += span.end().line - span.start().line + 1;
+        self.in_by_block = true;
+        visit(self);
+        self.in_by_block = false;
+    }
+
+    fn count_prover(&mut self, prover: &proc_macro2::Ident) {
+        *self.facts.provers.entry(prover.to_string()).or_default() += 1;
+    }
+}
+
+impl<'ast> Visit<'ast> for ProofVisitor {
+    fn visit_item(&mut self, _node: &'ast Item) {
+        // Nested items are reported as functions of their own
+    }
+
+    fn visit_expr_unary(&mut self, node: &'ast ExprUnary) {
+        if !matches!(node.op, UnOp::Proof(_)) || self.in_proof {
+            verus_syn::visit::visit_expr_unary(self, node);
+            return;
+        }
+        let span = node.span();
+        self.facts.proof_blocks += 1;
+        self.facts.proof_lines += span.end().line - span.start().line + 1;
+        self.in_proof = true;
+        verus_syn::visit::visit_expr_unary(self, node);
+        self.in_proof = false;
+    }
+
+    fn visit_assert(&mut self, node: &'ast Assert) {
+        self.facts.asserts += 1;
+        if let Some((_, prover)) = &node.prover {
+            self.count_prover(prover);
+        }
+        if node.body.is_none() {
+            verus_syn::visit::visit_assert(self, node);
+            return;
+        }
+        self.by_block(node.span(), pretty(&node.expr), |v| {
+            verus_syn::visit::visit_assert(v, node)
+        });
+    }
+
+    fn visit_assert_forall(&mut self, node: &'ast AssertForall) {
+        self.facts.asserts += 1;
+        let inputs: Vec<String> = node.inputs.iter().map(pretty).collect();
+        let mut asserted = format!("forall|{}| {}", inputs.join(", "), pretty(&node.expr));
+        if let Some((_, implies)) = &node.implies {
+            asserted.push_str(&format!(" implies {}", pretty(implies)));
+        }
+        self.by_block(node.span(), asserted, |v| {
+            verus_syn::visit::visit_assert_forall(v, node)
+        });
+    }
+
+    fn visit_assume(&mut self, node: &'ast Assume) {
+        self.facts.assumes += 1;
+        verus_syn::visit::visit_assume(self, node);
+    }
+
+    fn visit_reveal_hide(&mut self, node: &'ast RevealHide) {
+        if node.hide_token.is_none() {
+            self.facts.reveals += 1;
+        }
+        if let Some((_, fuel)) = &node.fuel {
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Int(n), ..
+            }) = &**fuel
+            {
+                if let Ok(n) = n.base10_parse::<u64>() {
+                    self.facts.max_fuel = self.facts.max_fuel.max(Some(n));
+                }
+            }
+        }
+        verus_syn::visit::visit_reveal_hide(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast Macro) {
+        if node.path.segments.last().is_some_and(|s| s.ident == "calc") {
+            self.facts.calcs.push(node.path.span().start().line);
+        }
+        calcs_in(node.tokens.clone(), &mut self.facts.calcs);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast ExprCall) {
+        if let Expr::Path(p) = &*node.func {
+            if p.path.segments.last().is_some_and(|s| s.ident == "admit") {
+                self.facts.admits += 1;
+            }
+        }
+        verus_syn::visit::visit_expr_call(self, node);
+    }
+}
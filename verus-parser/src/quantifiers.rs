@@ -0,0 +1,56 @@
+//! Quantifiers and manual triggers of a function
+//!
+//! `forall`, `exists` and `choose` are counted in the signature (spec
+//! clauses) and the body alike, each nested quantifier once, together with
+//! `assert forall ... by`. Triggers are the explicit `#[trigger]` and
+//! `#![trigger ...]` annotations; `#![auto]` and `#![all_triggers]` leave the
+//! choice to Verus and are not counted. Nested items belong to those items.
+
+use verus_syn::visit::Visit;
+use verus_syn::{AssertForall, Attribute, Block, ExprUnary, Item, Signature, UnOp};
+
+/// Quantifier statistics of one function
+#[derive(Debug, Default)]
+pub struct QuantifierFacts {
+    pub quantifiers: usize,
+    pub triggers: usize,
+}
+
+/// Count the quantifiers and triggers of a function's signature and body
+pub fn scan(sig: &Signature, body: Option<&Block>) -> QuantifierFacts {
+    let mut visitor = QuantifierVisitor::default();
+    visitor.visit_signature(sig);
+    if let Some(block) = body {
+        visitor.visit_block(block);
+    }
+    visitor.facts
+}
+
+#[derive(Default)]
+struct QuantifierVisitor {
+    facts: QuantifierFacts,
+}
+
+impl<'ast> Visit<'ast> for QuantifierVisitor {
+    fn visit_item(&mut self, _node: &'ast Item) {
+        // Nested items are reported as functions of their own
+    }
+
+    fn visit_expr_unary(&mut self, node: &'ast ExprUnary) {
+        if matches!(node.op, UnOp::Forall(_) | UnOp::Exists(_) | UnOp::Choose(_)) {
+            self.facts.quantifiers += 1;
+        }
+        verus_syn::visit::visit_expr_unary(self, node);
+    }
+
+    fn visit_assert_forall(&mut self, node: &'ast AssertForall) {
+        self.facts.quantifiers += 1;
+        verus_syn::visit::visit_assert_forall(self, node);
+    }
+
+    fn visit_attribute(&mut self, node: &'ast Attribute) {
+        if node.path().is_ident("trigger") {
+            self.facts.triggers += 1;
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Queries against the JSON output of an earlier run
+//!
+//! Parsing a large crate takes a while, so `verus-parser PATH --format json`
+//! can be run once and the saved output queried many times without reading
+//! the sources again.
+
+use crate::callgraph::{self, CallGraph};
+use crate::{
+    print_functions, Counts, FunctionInfo, GroupBy, OutputFormat, OutputOptions, ParsedOutput,
+    SortKey, SCHEMA_VERSION,
+};
+use regex::Regex;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(clap::Args, Debug)]
+pub struct QueryArgs {
+    /// JSON written by `verus-parser PATH --format json`
+    #[arg(long, value_name = "FILE")]
+    input: PathBuf,
+
+    /// Only functions with this name or qualified name (suffix)
+    #[arg(long)]
+    function: Option<String>,
+
+    /// Only functions in this module or its submodules
+    #[arg(long)]
+    module: Option<String>,
+
+    /// Only functions of this kind (`spec`, `proof fn`, `closed spec`, ...;
+    /// the input must have been written with `--show-kind`)
+    #[arg(long)]
+    kind: Option<String>,
+
+    /// Only functions whose name matches this regex
+    #[arg(long, value_name = "REGEX")]
+    name_regex: Option<String>,
+
+    /// Also list everything the selected functions transitively call (the
+    /// input must have been written with `--show-calls`)
+    #[arg(long)]
+    closure: bool,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "json")]
+    pub(crate) format: OutputFormat,
+
+    /// Write JSON minified, on one line; the default unless stdout is a
+    /// terminal
+    #[arg(long, conflicts_with = "pretty")]
+    pub(crate) compact: bool,
+
+    /// Write JSON indented even when piped or written to a file
+    #[arg(long)]
+    pub(crate) pretty: bool,
+
+    /// With `--format text`, print bare function names instead of
+    /// qualified names
+    #[arg(long)]
+    bare_names: bool,
+
+    /// With `--format text`, terminate each name with a NUL character, and
+    /// with `--format verus-args` each argument (for `xargs -0`); other
+    /// formats are rejected
+    #[arg(long, alias = "null")]
+    pub(crate) print0: bool,
+
+    /// Order the functions by name, file (then line), size in lines or
+    /// kind (with the file written with `--show-kind`), ties broken by
+    /// qualified name; as saved unless given
+    #[arg(long, value_enum, value_name = "KEY")]
+    sort: Option<SortKey>,
+
+    /// Reverse the order, e.g. `--sort size --reverse` for the largest
+    /// functions first
+    #[arg(long)]
+    reverse: bool,
+
+    /// Group the functions by module or by file: JSON lists them in
+    /// `functions_by_module` or `functions_by_file` besides `functions`, and
+    /// `--format markdown` makes a table per module (the default) or file
+    #[arg(long, value_enum)]
+    group_by: Option<GroupBy>,
+
+    /// Print only the number of selected functions and of their files, and
+    /// their number by kind when the input has kinds
+    #[arg(long)]
+    count_only: bool,
+
+    /// Write the output to FILE instead of stdout, in place only once it is
+    /// complete; with `--format sqlite`, the database to write
+    #[arg(
+        short = 'o',
+        long = "output",
+        value_name = "FILE",
+        required_if_eq("format", "sqlite")
+    )]
+    pub(crate) output_file: Option<PathBuf>,
+
+    /// With `--format sqlite`, keep the runs already in the database and add
+    /// this one under the next `run_id`, instead of replacing the tables
+    #[arg(long)]
+    append: bool,
+}
+
+/// Read a saved `ParsedOutput`, rejecting files written with another schema
+pub fn load(path: &Path) -> Result<ParsedOutput, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("{} is not valid JSON: {}", path.display(), e))?;
+
+    let Some(version) = value.get("schema_version").and_then(|v| v.as_u64()) else {
+        return Err(format!(
+            "{} has no schema_version but this verus-parser reads version {}; \
+             regenerate it with `verus-parser PATH --format json`",
+            path.display(),
+            SCHEMA_VERSION
+        ));
+    };
+    if version != u64::from(SCHEMA_VERSION) {
+        return Err(format!(
+            "{} has schema version {} but this verus-parser reads version {}; \
+             regenerate it with `verus-parser PATH --format json`",
+            path.display(),
+            version,
+            SCHEMA_VERSION
+        ));
+    }
+
+    serde_json::from_value(value)
+        .map_err(|e| format!("{} is not verus-parser output: {}", path.display(), e))
+}
+
+/// `kind` names the kind with or without ` fn`, or its first words
+/// (`broadcast`), optionally preceded by the spec openness (`closed spec`)
+pub(crate) fn matches_kind(func: &FunctionInfo, kind: &str) -> bool {
+    let Some(k) = func.kind.as_deref() else {
+        return false;
+    };
+    let kind = match (&func.spec_openness, kind.split_once(' ')) {
+        (Some(openness), Some((prefix, rest))) if prefix == openness => rest,
+        _ => kind,
+    };
+    k == kind || k.strip_suffix(" fn") == Some(kind) || k.starts_with(&format!("{} ", kind))
+}
+
+/// `query` subcommand
+pub fn run(args: &QueryArgs) -> Result<(), String> {
+    let saved = load(&args.input)?;
+    let functions = saved.functions;
+    // Globals hold for the whole crate and are kept regardless of --module
+    let mut declarations = saved.declarations;
+    if let Some(ref module) = args.module {
+        declarations.broadcast_uses.retain(|m, _| {
+            let module_path = if m == "crate" { "" } else { m.as_str() };
+            callgraph::module_within(module_path, module)
+        });
+        declarations
+            .assumed_specifications
+            .retain(|s| callgraph::module_within(&s.module_path, module));
+        declarations
+            .types
+            .retain(|t| callgraph::module_within(&t.module_path, module));
+        declarations
+            .type_aliases
+            .retain(|a| callgraph::module_within(&a.module_path, module));
+        declarations
+            .traits
+            .retain(|t| callgraph::module_within(&t.module_path, module));
+        declarations
+            .consts
+            .retain(|c| callgraph::module_within(&c.module_path, module));
+        declarations
+            .unparsed_macros
+            .retain(|m| callgraph::module_within(&m.module_path, module));
+        declarations
+            .state_machines
+            .retain(|m| callgraph::module_within(&m.module_path, module));
+    }
+
+    if args.kind.is_some() && functions.iter().any(|f| f.kind.is_none()) {
+        return Err(format!(
+            "{} has no function kinds; regenerate it with --show-kind",
+            args.input.display()
+        ));
+    }
+    if args.closure && functions.iter().any(|f| f.callees.is_none()) {
+        return Err(format!(
+            "{} has no callees; regenerate it with --show-calls",
+            args.input.display()
+        ));
+    }
+    let name_regex = args
+        .name_regex
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| format!("Invalid --name-regex: {}", e))?;
+
+    let graph = CallGraph::new(&functions, true);
+    let named: Option<HashSet<usize>> = args
+        .function
+        .as_deref()
+        .map(|name| graph.resolve(name).into_iter().collect());
+
+    let mut selected: Vec<usize> = (0..functions.len())
+        .filter(|idx| named.as_ref().is_none_or(|named| named.contains(idx)))
+        .filter(|&idx| {
+            let func = &functions[idx];
+            args.module
+                .as_deref()
+                .is_none_or(|m| callgraph::in_module(func, m))
+                && args.kind.as_deref().is_none_or(|k| matches_kind(func, k))
+                && name_regex.as_ref().is_none_or(|r| r.is_match(&func.name))
+        })
+        .collect();
+    if args.closure {
+        selected = graph.closure(&selected);
+    }
+
+    if args.count_only {
+        let mut counts = Counts::new(functions.iter().all(|f| f.kind.is_some()));
+        counts.add(selected.iter().map(|&idx| &functions[idx]));
+        counts.total_files = selected
+            .iter()
+            .filter_map(|&idx| functions[idx].file.as_deref())
+            .collect::<HashSet<_>>()
+            .len();
+        counts.print(&args.format);
+        return Ok(());
+    }
+
+    let result: Vec<FunctionInfo> = selected.iter().map(|&idx| functions[idx].clone()).collect();
+    let files: HashSet<&str> = result.iter().filter_map(|f| f.file.as_deref()).collect();
+    let output = OutputOptions {
+        format: args.format.clone(),
+        bare_names: args.bare_names,
+        print0: args.print0,
+        group_by: args.group_by.clone(),
+        sort: args.sort,
+        reverse: args.reverse,
+        database: args.output_file.clone(),
+        append: args.append,
+        listing: Some("query"),
+    };
+    print_functions(&output, &result, &declarations, files.len(), None);
+    Ok(())
+}
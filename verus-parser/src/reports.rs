@@ -0,0 +1,143 @@
+//! Findings-style reports built on the call graph
+
+use crate::callgraph::{CallGraph, Recursion};
+use crate::FunctionInfo;
+use regex::Regex;
+
+/// Functions selected by name, qualified name or regex (`--allow`, `--root`)
+pub struct FunctionPatterns {
+    patterns: Vec<Regex>,
+}
+
+impl FunctionPatterns {
+    /// Each pattern must match a whole name or qualified name; `flag` names
+    /// the option in error messages
+    pub fn new(patterns: &[String], flag: &str) -> Result<Self, String> {
+        let patterns = patterns
+            .iter()
+            .map(|p| {
+                Regex::new(&format!("^(?:{})$", p))
+                    .map_err(|e| format!("Invalid {} pattern {}: {}", flag, p, e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(FunctionPatterns { patterns })
+    }
+
+    pub fn matches(&self, func: &FunctionInfo) -> bool {
+        self.patterns
+            .iter()
+            .any(|p| p.is_match(&func.name) || p.is_match(&func.qualified_name))
+    }
+}
+
+fn is_proof(func: &FunctionInfo) -> bool {
+    func.mode().is_some_and(|k| k.starts_with("proof"))
+}
+
+fn is_exec(func: &FunctionInfo) -> bool {
+    func.is_verified_function()
+        && func
+            .mode()
+            .is_some_and(|k| !k.starts_with("spec") && !k.starts_with("proof"))
+}
+
+/// Proof functions not reachable from any exec function; functions must
+/// have been extracted with kinds and calls
+pub fn unused_lemmas(
+    functions: &[FunctionInfo],
+    graph: &CallGraph,
+    allow: &FunctionPatterns,
+) -> Vec<usize> {
+    let roots: Vec<usize> = (0..functions.len())
+        .filter(|&idx| is_exec(&functions[idx]))
+        .collect();
+    let mut used = vec![false; functions.len()];
+    for idx in graph.closure(&roots) {
+        used[idx] = true;
+    }
+
+    (0..functions.len())
+        .filter(|&idx| !used[idx] && is_proof(&functions[idx]) && !allow.matches(&functions[idx]))
+        .collect()
+}
+
+/// Whether a function is an entry point of the crate: `fn main`, a
+/// function exported with `#[no_mangle]` or `#[export_name]`, or a `pub`
+/// function at the library crate root; functions must have been extracted
+/// with visibility
+pub fn is_entry_point(func: &FunctionInfo) -> bool {
+    if !func.is_verified_function() || func.parent_function.is_some() {
+        return false;
+    }
+    let at_root = func.module_path.is_empty();
+    let standalone = func.impl_target.is_none();
+    let library = func.target.as_deref().is_none_or(|t| t == "lib");
+    (func.name == "main" && at_root && standalone)
+        || func.no_mangle
+        || func.export_name.is_some()
+        || (at_root && library && func.visibility.as_deref() == Some("pub"))
+}
+
+/// Functions not reachable from any of `roots`; the graph should include
+/// spec dependencies so spec functions used in `requires`/`ensures` count
+/// as reachable
+pub fn dead_functions(
+    functions: &[FunctionInfo],
+    graph: &CallGraph,
+    roots: &[usize],
+) -> Vec<usize> {
+    let mut live = vec![false; functions.len()];
+    for idx in graph.closure(roots) {
+        live[idx] = true;
+    }
+    (0..functions.len()).filter(|&idx| !live[idx]).collect()
+}
+
+/// Recursive functions without a `decreases` clause, with how they recurse;
+/// functions must have been extracted with specs, and `graph` should follow
+/// body calls only
+pub fn missing_decreases(functions: &[FunctionInfo], graph: &CallGraph) -> Vec<(usize, Recursion)> {
+    graph
+        .recursion()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, recursion)| {
+            let missing = functions[idx].decreases.as_ref().is_some_and(Vec::is_empty);
+            recursion.filter(|_| missing).map(|r| (idx, r))
+        })
+        .collect()
+}
+
+/// Every opaque function with the functions that reveal it (an empty list
+/// if none does); functions must have been extracted with calls
+pub fn opaque_reveals(functions: &[FunctionInfo], graph: &CallGraph) -> Vec<(usize, Vec<usize>)> {
+    let mut revealed_by = vec![Vec::new(); functions.len()];
+    for (idx, func) in functions.iter().enumerate() {
+        for path in func.reveals.iter().flatten() {
+            for target in graph.resolve(path) {
+                revealed_by[target].push(idx);
+            }
+        }
+    }
+    revealed_by
+        .into_iter()
+        .enumerate()
+        .filter(|&(idx, _)| functions[idx].opaque)
+        .collect()
+}
+
+/// For each function, the trusted functions (see `FunctionInfo::trusted`)
+/// it transitively depends on, itself included
+pub fn trusted_dependencies(functions: &[FunctionInfo], graph: &CallGraph) -> Vec<Vec<usize>> {
+    (0..functions.len())
+        .map(|idx| {
+            let mut leaves: Vec<usize> = graph
+                .closure(&[idx])
+                .into_iter()
+                .filter(|&dep| !functions[dep].trusted.is_empty())
+                .collect();
+            leaves.sort();
+            leaves
+        })
+        .collect()
+}
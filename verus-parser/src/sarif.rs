@@ -0,0 +1,176 @@
+//! SARIF 2.1.0 logs of the findings of the report modes
+//!
+//! Each finding is a `result` with the rule it reports, a level, a message,
+//! and the function's file and line span as its physical location, so code
+//! scanning tools can annotate the source with it. Every rule a mode can
+//! report is listed in the tool's driver, whether or not it was found.
+
+use crate::findings::{Finding, Level, Rule};
+use serde::Serialize;
+
+const SCHEMA: &str =
+    "https://docs.oasis-open.org/sarif/sarif/v2.1.0/errata01/os/schemas/sarif-schema-2.1.0.json";
+
+#[derive(Serialize)]
+struct Log {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: &'static str,
+    short_description: Message,
+    default_configuration: Configuration,
+}
+
+#[derive(Serialize)]
+struct Configuration {
+    level: Level,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    rule_index: usize,
+    level: Level,
+    message: Message,
+    locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    physical_location: Option<PhysicalLocation>,
+    logical_locations: Vec<LogicalLocation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    end_line: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogicalLocation {
+    fully_qualified_name: String,
+    kind: &'static str,
+}
+
+/// A file path as a URI reference: relative paths stay relative to where
+/// the tool ran, absolute ones become `file://` URIs
+fn uri(path: &str) -> String {
+    let mut uri = String::new();
+    for c in path.chars() {
+        match c {
+            '\\' => uri.push('/'),
+            ' ' | '%' | '#' | '?' => uri.push_str(&format!("%{:02X}", c as u32)),
+            c => uri.push(c),
+        }
+    }
+    if uri.starts_with('/') {
+        uri.insert_str(0, "file://");
+    }
+    uri
+}
+
+/// The SARIF log of one run reporting `findings`, with `rules` the rules
+/// the mode can report
+pub fn render(rules: &[&'static Rule], findings: &[Finding]) -> String {
+    let results = findings
+        .iter()
+        .map(|finding| SarifResult {
+            rule_id: finding.rule.id,
+            rule_index: rules
+                .iter()
+                .position(|rule| rule.id == finding.rule.id)
+                .expect("finding of a rule the mode does not list"),
+            level: finding.rule.level,
+            message: Message {
+                text: finding.message.clone(),
+            },
+            locations: vec![Location {
+                physical_location: finding.file.as_deref().map(|file| PhysicalLocation {
+                    artifact_location: ArtifactLocation { uri: uri(file) },
+                    region: Region {
+                        start_line: finding.start_line,
+                        end_line: finding.end_line,
+                    },
+                }),
+                logical_locations: vec![LogicalLocation {
+                    fully_qualified_name: finding.qualified_name.clone(),
+                    kind: "function",
+                }],
+            }],
+        })
+        .collect();
+    let log = Log {
+        schema: SCHEMA,
+        version: "2.1.0",
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: "https://github.com/beneficial-ai-foundation/dockerfile_verus",
+                    rules: rules
+                        .iter()
+                        .map(|rule| ReportingDescriptor {
+                            id: rule.id,
+                            short_description: Message {
+                                text: rule.description.to_string(),
+                            },
+                            default_configuration: Configuration { level: rule.level },
+                        })
+                        .collect(),
+                },
+            },
+            results,
+        }],
+    };
+    crate::output::json(&log)
+}
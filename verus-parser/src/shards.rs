@@ -0,0 +1,54 @@
+//! `--shard-count N --shard-index I`: splitting the functions between CI
+//! runners
+//!
+//! The functions are dealt out largest first, each to the shard with the
+//! least cost so far (the lowest-numbered one on a tie), which keeps the
+//! big functions apart. Ties between functions are broken by qualified
+//! name, file and line, so the same sources always give the same shards.
+
+use crate::FunctionInfo;
+use clap::ValueEnum;
+use std::cmp::Reverse;
+
+/// What a function costs to verify, as estimated for `--shard-by`
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ShardCost {
+    /// Its length in lines
+    Lines,
+    /// The same for every function, balancing the number of functions
+    Count,
+}
+
+impl ShardCost {
+    fn of(self, func: &FunctionInfo) -> usize {
+        match self {
+            ShardCost::Lines => func.end_line + 1 - func.start_line,
+            ShardCost::Count => 1,
+        }
+    }
+}
+
+/// The shard of each function, below `count`
+pub fn assign(functions: &[FunctionInfo], count: usize, cost: ShardCost) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..functions.len()).collect();
+    order.sort_by_key(|&idx| {
+        let func = &functions[idx];
+        (
+            Reverse(cost.of(func)),
+            &func.qualified_name,
+            &func.file,
+            func.start_line,
+            idx,
+        )
+    });
+    let mut loads = vec![0; count];
+    let mut shards = vec![0; functions.len()];
+    for idx in order {
+        let shard = (0..count)
+            .min_by_key(|&shard| loads[shard])
+            .expect("there is at least one shard");
+        loads[shard] += cost.of(&functions[idx]);
+        shards[idx] = shard;
+    }
+    shards
+}
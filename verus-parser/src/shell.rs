@@ -0,0 +1,27 @@
+//! Quoting for `--format shell`, and for the Verus arguments printed on one
+//! line
+//!
+//! Words made only of characters no POSIX shell treats specially are
+//! printed as they are; anything else is put in single quotes, with each
+//! `'` closing the quotes, escaped and reopened (`'\''`).
+
+use std::borrow::Cow;
+
+fn is_plain(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_-+=.,/:@%".contains(c)
+}
+
+/// `word` as a single shell word
+pub fn quote(word: &str) -> Cow<'_, str> {
+    if !word.is_empty() && word.chars().all(is_plain) {
+        Cow::Borrowed(word)
+    } else {
+        Cow::Owned(format!("'{}'", word.replace('\'', r"'\''")))
+    }
+}
+
+/// `words` quoted one by one and joined with spaces, to paste into a shell
+pub fn join<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    let quoted: Vec<_> = words.into_iter().map(quote).collect();
+    quoted.join(" ")
+}
@@ -0,0 +1,112 @@
+//! Signature text of a function, for comparing it with the upstream crate
+//! and generating stubs
+
+use crate::pretty::{pretty, pretty_tokens, pretty_type};
+use crate::specs;
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use verus_syn::{GenericParam, Generics, ReturnType, Signature};
+
+/// The return type as written, keeping the binder of a named return value
+/// (`(result: u64)`, `(tracked r: Token)`), since the specs refer to it;
+/// `None` for unit, whether written `-> ()` or left out
+pub fn return_type(sig: &Signature) -> Option<String> {
+    let ReturnType::Type(_, tracked, named, ty) = &sig.output else {
+        return None;
+    };
+    let mode = if tracked.is_some() { "tracked " } else { "" };
+    match named {
+        Some(named) => {
+            let (_, pat, _) = &**named;
+            Some(format!("({}{}: {})", mode, pretty(pat), pretty_type(ty)))
+        }
+        None if matches!(&**ty, verus_syn::Type::Tuple(t) if t.elems.is_empty()) => None,
+        None => Some(format!("{}{}", mode, pretty_type(ty))),
+    }
+}
+
+/// The generic parameter list as written, lifetimes and const generics
+/// included (`<'a, const N: usize, T: Copy>`); `None` when there is none
+pub fn generics(generics: &Generics) -> Option<String> {
+    (!generics.params.is_empty()).then(|| pretty_type(generics))
+}
+
+/// The `where` predicates joined with `, `, without the keyword
+pub fn where_clause(generics: &Generics) -> Option<String> {
+    let predicates: Vec<String> = generics
+        .where_clause
+        .as_ref()?
+        .predicates
+        .iter()
+        .map(pretty_type)
+        .collect();
+    (!predicates.is_empty()).then(|| predicates.join(", "))
+}
+
+/// Whether the parameters need monomorphizing: type or const parameters
+/// make a function generic, lifetimes alone do not
+pub fn has_type_params(generics: &Generics) -> bool {
+    generics
+        .params
+        .iter()
+        .any(|param| !matches!(param, GenericParam::Lifetime(_)))
+}
+
+/// The whole signature on one line, without the body or visibility: modes,
+/// qualifiers, name, generics, parameters (`&self` as written), return type,
+/// `where` clause and, unless `with_specs` is false, the spec clauses.
+/// Lists are always joined with `, ` and never end in a comma, so the text
+/// only changes when the signature does.
+pub fn signature(sig: &Signature, with_specs: bool) -> String {
+    let mut head = TokenStream::new();
+    sig.publish.to_tokens(&mut head);
+    sig.constness.to_tokens(&mut head);
+    sig.asyncness.to_tokens(&mut head);
+    sig.unsafety.to_tokens(&mut head);
+    sig.abi.to_tokens(&mut head);
+    sig.broadcast.to_tokens(&mut head);
+    sig.mode.to_tokens(&mut head);
+    sig.fn_token.to_tokens(&mut head);
+    sig.ident.to_tokens(&mut head);
+    sig.generics.to_tokens(&mut head);
+
+    let params: Vec<String> = sig.inputs.iter().map(pretty_type).collect();
+    let mut text = format!("{}({})", pretty_tokens(head, true), params.join(", "));
+    if let Some(return_type) = return_type(sig) {
+        text.push_str(&format!(" -> {}", return_type));
+    }
+    if let Some(predicates) = where_clause(&sig.generics) {
+        text.push_str(&format!(" where {}", predicates));
+    }
+    if !with_specs {
+        return text;
+    }
+
+    let lists = [
+        ("requires", specs::requires(sig)),
+        ("recommends", specs::recommends(sig)),
+        ("ensures", specs::ensures(sig)),
+    ];
+    for (keyword, clauses) in lists {
+        if !clauses.is_empty() {
+            text.push_str(&format!(" {} {}", keyword, clauses.join(", ")));
+        }
+    }
+    if let Some(returns) = specs::returns(sig) {
+        text.push_str(&format!(" returns {}", returns));
+    }
+    let measures = specs::decreases(sig);
+    if !measures.is_empty() {
+        text.push_str(&format!(" decreases {}", measures.join(", ")));
+    }
+    if let Some(opens) = specs::opens_invariants(sig) {
+        text.push_str(&format!(" opens_invariants {}", opens));
+    }
+    if sig.spec.unwind.is_some() {
+        text.push_str(" no_unwind");
+        if let Some(when) = specs::no_unwind_when(sig) {
+            text.push_str(&format!(" when {}", when));
+        }
+    }
+    text
+}
@@ -0,0 +1,130 @@
+//! Spec clauses of a signature as readable text
+//!
+//! Each clause of `requires x, y,` is rendered on its own with
+//! `pretty::pretty`, so line breaks inside a clause are collapsed and
+//! `forall|i: int| ...` keeps its usual spelling.
+
+use crate::body::verifier_attrs;
+use crate::pretty::pretty;
+use verus_syn::{AssumeSpecification, Attribute, FnMode, Signature, Specification};
+
+/// The clauses of a `requires`/`ensures`/... list, one string per clause
+pub fn clauses(spec: Option<&Specification>) -> Vec<String> {
+    spec.iter()
+        .flat_map(|s| s.exprs.iter().map(pretty))
+        .collect()
+}
+
+/// The `requires` clauses of a signature, one string per clause
+pub fn requires(sig: &Signature) -> Vec<String> {
+    clauses(sig.spec.requires.as_ref().map(|r| &r.exprs))
+}
+
+/// The `recommends` clauses of a signature, one string per clause, followed
+/// by `via f` when the recommendations are checked by `f`
+pub fn recommends(sig: &Signature) -> Vec<String> {
+    let recommends = sig.spec.recommends.as_ref();
+    let mut clauses = clauses(recommends.map(|r| &r.exprs));
+    if let Some((_, via)) = recommends.and_then(|r| r.via.as_ref()) {
+        clauses.push(format!("via {}", pretty(via)));
+    }
+    clauses
+}
+
+/// The `ensures` clauses of a signature, one string per clause
+pub fn ensures(sig: &Signature) -> Vec<String> {
+    clauses(sig.spec.ensures.as_ref().map(|e| &e.exprs))
+}
+
+/// The measures of the `decreases` clause of a signature, one string each
+pub fn decreases(sig: &Signature) -> Vec<String> {
+    clauses(sig.spec.decreases.as_ref().map(|d| &d.decreases.exprs))
+}
+
+/// Whether the attributes include `#[verifier::exec_allows_no_decreases_clause]`,
+/// letting exec functions and loops do without `decreases`
+pub fn allows_no_decreases(attrs: &[Attribute]) -> bool {
+    verifier_attrs(attrs)
+        .iter()
+        .any(|a| a == "exec_allows_no_decreases_clause")
+}
+
+/// The condition of `decreases .. when <expr>`
+pub fn decreases_when(sig: &Signature) -> Option<String> {
+    let (_, when) = sig.spec.decreases.as_ref()?.when.as_ref()?;
+    Some(pretty(when))
+}
+
+/// The proof function of `decreases .. via f`, as written
+pub fn decreases_via(sig: &Signature) -> Option<String> {
+    let (_, via) = sig.spec.decreases.as_ref()?.via.as_ref()?;
+    Some(pretty(via))
+}
+
+/// The invariants a function may open: `any`, `none`, `[a, b]` or a set
+/// expression, if declared with `opens_invariants`
+pub fn opens_invariants(sig: &Signature) -> Option<String> {
+    sig.spec.invariants.as_ref().map(|i| pretty(&i.set))
+}
+
+/// The `returns` expression of a signature, if any
+pub fn returns(sig: &Signature) -> Option<String> {
+    let returns = sig.spec.returns.as_ref()?;
+    Some(clauses(Some(&returns.exprs)).join(", "))
+}
+
+/// The condition of `no_unwind when <expr>`
+pub fn no_unwind_when(sig: &Signature) -> Option<String> {
+    let (_, when) = sig.spec.unwind.as_ref()?.when.as_ref()?;
+    Some(pretty(when))
+}
+
+/// The `requires`, `ensures` and `returns` of an `assume_specification`
+pub fn assumed(spec: &AssumeSpecification) -> (Vec<String>, Vec<String>, Option<String>) {
+    let requires = clauses(spec.requires.as_ref().map(|r| &r.exprs));
+    let ensures = clauses(spec.ensures.as_ref().map(|e| &e.exprs));
+    let returns = spec
+        .returns
+        .as_ref()
+        .map(|r| clauses(Some(&r.exprs)).join(", "));
+    (requires, ensures, returns)
+}
+
+/// The spec clauses a signature carries, by keyword, in the order Verus
+/// expects them
+pub fn clause_keywords(sig: &Signature) -> Vec<&'static str> {
+    let spec = &sig.spec;
+    [
+        ("requires", spec.requires.is_some()),
+        ("recommends", spec.recommends.is_some()),
+        ("ensures", spec.ensures.is_some()),
+        ("default_ensures", spec.default_ensures.is_some()),
+        ("returns", spec.returns.is_some()),
+        ("decreases", spec.decreases.is_some()),
+        ("opens_invariants", spec.invariants.is_some()),
+        ("no_unwind", spec.unwind.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(keyword, present)| present.then_some(keyword))
+    .collect()
+}
+
+/// Whether the function is exec code (`exec fn` or a plain `fn`)
+pub fn is_exec(sig: &Signature) -> bool {
+    matches!(sig.mode, FnMode::Exec(_) | FnMode::Default)
+}
+
+/// Whether a signature has a `requires`, `ensures` or `returns` clause
+pub fn is_specified(sig: &Signature) -> bool {
+    sig.spec.requires.is_some() || sig.spec.ensures.is_some() || sig.spec.returns.is_some()
+}
+
+/// Whether the postcondition promises nothing: `ensures true`, or no
+/// `ensures` (nor `returns`) at all on an exec function
+pub fn trivial_ensures(sig: &Signature, ensures: &[String]) -> bool {
+    match ensures {
+        [only] => only == "true",
+        [] => is_exec(sig) && sig.spec.returns.is_none(),
+        _ => false,
+    }
+}
@@ -0,0 +1,223 @@
+//! `--format sqlite`: the functions in an SQLite database for ad-hoc SQL
+//!
+//! The database is written by piping SQL to the `sqlite3` command-line
+//! tool, in one transaction. It has a `functions` table (one row per
+//! function, with the full JSON of the function in `json`), a `files` table
+//! (the files holding listed functions), a `calls` table when calls were
+//! extracted (one row per call and function it may resolve to), and a
+//! `summary` table. Every row carries the `run_id` of the run that wrote
+//! it: by default the tables are replaced and the run is 1, while
+//! `--append` keeps the earlier runs and adds the next one.
+
+use crate::callgraph::CallGraph;
+use crate::{FunctionInfo, Summary};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const TABLES: &str = "
+CREATE TABLE IF NOT EXISTS functions (
+    run_id INTEGER NOT NULL,
+    qualified_name TEXT NOT NULL,
+    name TEXT NOT NULL,
+    file TEXT,
+    module_path TEXT NOT NULL,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    kind TEXT,
+    visibility TEXT,
+    specified INTEGER,
+    trusted TEXT,
+    in_verus_macro INTEGER NOT NULL,
+    json TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS functions_qualified_name ON functions (qualified_name);
+CREATE INDEX IF NOT EXISTS functions_file ON functions (file);
+CREATE TABLE IF NOT EXISTS files (
+    run_id INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    target TEXT,
+    functions INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS files_path ON files (path);
+CREATE TABLE IF NOT EXISTS summary (
+    run_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    total_files INTEGER NOT NULL,
+    total_functions INTEGER NOT NULL,
+    trusted_functions INTEGER NOT NULL,
+    exec_functions INTEGER NOT NULL,
+    specified INTEGER NOT NULL,
+    json TEXT NOT NULL
+);
+";
+
+const CALLS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS calls (
+    run_id INTEGER NOT NULL,
+    caller TEXT NOT NULL,
+    callee_path TEXT NOT NULL,
+    callee TEXT,
+    in_spec INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS calls_caller ON calls (caller);
+CREATE INDEX IF NOT EXISTS calls_callee ON calls (callee);
+";
+
+/// The run being written, once its `summary` row is in
+const RUN_ID: &str = "(SELECT MAX(run_id) FROM summary)";
+
+/// An SQL string literal
+fn text(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// A nullable SQL string literal
+fn opt_text(s: Option<&str>) -> String {
+    s.map_or_else(|| "NULL".to_string(), text)
+}
+
+/// A nullable SQL boolean
+fn opt_bool(b: Option<bool>) -> &'static str {
+    match b {
+        Some(true) => "1",
+        Some(false) => "0",
+        None => "NULL",
+    }
+}
+
+/// The SQL writing the functions as a new run
+fn script(functions: &[FunctionInfo], summary: &Summary, append: bool) -> String {
+    let with_calls = functions.iter().any(|f| f.callees.is_some());
+    let mut sql = String::from("BEGIN;\n");
+    if !append {
+        sql.push_str("DROP TABLE IF EXISTS functions;\nDROP TABLE IF EXISTS files;\n");
+        sql.push_str("DROP TABLE IF EXISTS calls;\nDROP TABLE IF EXISTS summary;\n");
+    }
+    sql.push_str(TABLES);
+    if with_calls {
+        sql.push_str(CALLS_TABLE);
+    }
+
+    let exec: usize = summary
+        .spec_coverage
+        .values()
+        .map(|c| c.exec_functions)
+        .sum();
+    let specified: usize = summary.spec_coverage.values().map(|c| c.specified).sum();
+    writeln!(
+        sql,
+        "INSERT INTO summary SELECT COALESCE(MAX(run_id), 0) + 1, datetime('now'), {}, {}, {}, {}, {}, {} FROM summary;",
+        summary.total_files,
+        summary.total_functions,
+        summary.trusted_functions,
+        exec,
+        specified,
+        text(&serde_json::to_string(summary).unwrap())
+    )
+    .unwrap();
+
+    let mut files: BTreeMap<&str, (Option<&str>, usize)> = BTreeMap::new();
+    for func in functions {
+        let trusted = (!func.trusted.is_empty()).then(|| func.trusted.join(","));
+        writeln!(
+            sql,
+            "INSERT INTO functions VALUES ({}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {});",
+            RUN_ID,
+            text(&func.qualified_name),
+            text(&func.name),
+            opt_text(func.file.as_deref()),
+            text(&func.module_path),
+            func.start_line,
+            func.end_line,
+            opt_text(func.kind.as_deref()),
+            opt_text(func.visibility.as_deref()),
+            opt_bool(func.specified),
+            opt_text(trusted.as_deref()),
+            opt_bool(Some(func.in_verus_macro)),
+            text(&serde_json::to_string(func).unwrap())
+        )
+        .unwrap();
+        if let Some(file) = func.file.as_deref() {
+            let entry = files.entry(file).or_insert((func.target.as_deref(), 0));
+            entry.1 += 1;
+        }
+    }
+    for (path, (target, count)) in &files {
+        writeln!(
+            sql,
+            "INSERT INTO files VALUES ({}, {}, {}, {});",
+            RUN_ID,
+            text(path),
+            opt_text(*target),
+            count
+        )
+        .unwrap();
+    }
+
+    if with_calls {
+        let graph = CallGraph::new(functions, false);
+        for func in functions {
+            let body = func.callees.iter().flatten().map(|c| (c, false));
+            let spec = func.spec_deps.iter().flatten().map(|c| (c, true));
+            for (callee, in_spec) in body.chain(spec) {
+                let targets = graph.resolve(&callee.path);
+                let resolved: Vec<Option<&str>> = if targets.is_empty() {
+                    vec![None]
+                } else {
+                    targets
+                        .iter()
+                        .map(|&t| Some(functions[t].qualified_name.as_str()))
+                        .collect()
+                };
+                for target in resolved {
+                    writeln!(
+                        sql,
+                        "INSERT INTO calls VALUES ({}, {}, {}, {}, {});",
+                        RUN_ID,
+                        text(&func.qualified_name),
+                        text(&callee.path),
+                        opt_text(target),
+                        opt_bool(Some(in_spec))
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+    sql.push_str("COMMIT;\n");
+    sql
+}
+
+/// Write the functions to the database at `db`, replacing its tables or,
+/// with `append`, adding a run to them
+pub fn write(
+    db: &Path,
+    functions: &[FunctionInfo],
+    summary: &Summary,
+    append: bool,
+) -> Result<(), String> {
+    let sql = script(functions, summary, append);
+    let mut child = Command::new("sqlite3")
+        .arg("-bail")
+        .arg(db)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run sqlite3 (needed by --format sqlite): {}", e))?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(sql.as_bytes())
+        .map_err(|e| format!("Failed to write to sqlite3: {}", e))?;
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to run sqlite3: {}", e))?;
+    if !status.success() {
+        return Err(format!("sqlite3 failed to write {}", db.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,149 @@
+//! Bodies of `state_machine!` and `tokenized_state_machine!`
+//!
+//! The DSL is read only as far as needed to list what a machine declares:
+//! the `fields { .. }` block, the transitions of the `init!`, `transition!`,
+//! `readonly!` and `property!` blocks, and the functions between them, among
+//! which the `#[invariant]`s and the `#[inductive(..)]` lemmas. Transition
+//! bodies (`update`, `require`, `assert`, ...) are not parsed, and tokens
+//! that fit none of these are skipped.
+
+use crate::pretty::pretty_tokens;
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use verus_syn::parse::discouraged::Speculative;
+use verus_syn::parse::{Parse, ParseStream};
+use verus_syn::punctuated::Punctuated;
+use verus_syn::{braced, Attribute, Field, Generics, Ident, ItemFn, Meta, Result, Token};
+
+/// A machine: `Name<T> { fields { .. } transition!{ .. } fn .. }`
+pub struct StateMachine {
+    pub name: Ident,
+    pub generics: Generics,
+    pub fields: Vec<Field>,
+    /// Transitions and functions, in source order
+    pub members: Vec<Member>,
+}
+
+pub enum Member {
+    Transition(Transition),
+    Fn(Box<ItemFn>),
+}
+
+/// A named transition: `tr_inc(..) { .. }` in a `transition!` block
+pub struct Transition {
+    /// The block it is defined in: `init`, `transition`, `readonly` or
+    /// `property`
+    pub kind: String,
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+const TRANSITION_KINDS: [&str; 4] = ["init", "transition", "readonly", "property"];
+
+impl Parse for StateMachine {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.call(Attribute::parse_outer)?;
+        let name: Ident = input.parse()?;
+        let generics: Generics = input.parse()?;
+        let content;
+        braced!(content in input);
+        let mut fields = Vec::new();
+        let mut members = Vec::new();
+        while !content.is_empty() {
+            let fork = content.fork();
+            fork.call(Attribute::parse_outer)?;
+            if fork.peek(Ident) && fork.peek2(verus_syn::token::Brace) {
+                let keyword: Ident = fork.parse()?;
+                if keyword == "fields" {
+                    let inner;
+                    braced!(inner in fork);
+                    fields.extend(Punctuated::<Field, Token![,]>::parse_terminated_with(
+                        &inner,
+                        Field::parse_named,
+                    )?);
+                    content.advance_to(&fork);
+                    continue;
+                }
+            }
+            if fork.peek(Ident) && fork.peek2(Token![!]) {
+                let keyword: Ident = fork.parse()?;
+                fork.parse::<Token![!]>()?;
+                let block: proc_macro2::Group = fork.parse()?;
+                if TRANSITION_KINDS.iter().any(|k| keyword == k) {
+                    members.extend(
+                        transitions(&keyword.to_string(), block.stream())
+                            .into_iter()
+                            .map(Member::Transition),
+                    );
+                }
+                content.advance_to(&fork);
+                continue;
+            }
+            let fork = content.fork();
+            match fork.parse::<ItemFn>() {
+                Ok(item) => {
+                    content.advance_to(&fork);
+                    members.push(Member::Fn(Box::new(item)));
+                }
+                Err(_) => {
+                    content.parse::<TokenTree>()?;
+                }
+            }
+        }
+        Ok(StateMachine {
+            name,
+            generics,
+            fields,
+            members,
+        })
+    }
+}
+
+/// The transitions of a block: each is a name, a parenthesized parameter
+/// list and a braced body
+fn transitions(kind: &str, tokens: TokenStream) -> Vec<Transition> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut found = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let TokenTree::Ident(name) = token else {
+            continue;
+        };
+        let (Some(TokenTree::Group(params)), Some(TokenTree::Group(body))) =
+            (tokens.get(i + 1), tokens.get(i + 2))
+        else {
+            continue;
+        };
+        if params.delimiter() == Delimiter::Parenthesis && body.delimiter() == Delimiter::Brace {
+            found.push(Transition {
+                kind: kind.to_string(),
+                name: name.to_string(),
+                start_line: name.span().start().line,
+                end_line: body.span().end().line,
+            });
+        }
+    }
+    found
+}
+
+/// What a function of a machine is for: `invariant` for an
+/// `#[invariant]`, `inductive(tr)` for an `#[inductive(tr)]` lemma
+pub fn role(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::Path(path) if path.is_ident("invariant") => Some("invariant".to_string()),
+        Meta::List(list) if list.path.is_ident("inductive") => Some(format!(
+            "inductive({})",
+            pretty_tokens(list.tokens.clone(), false)
+        )),
+        _ => None,
+    })
+}
+
+/// The strategy of a `#[sharding(..)]` field attribute: `variable`, `map`, ...
+pub fn sharding(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::List(list) if list.path.is_ident("sharding") => {
+            Some(pretty_tokens(list.tokens.clone(), false))
+        }
+        _ => None,
+    })
+}
@@ -0,0 +1,227 @@
+//! `--stats`: distributions over the extracted functions
+//!
+//! Sizes are in lines, from the first line of the function to its last.
+//! Histograms have power-of-two buckets (`0`, `1`, `2-3`, `4-7`, ...) from
+//! the smallest value, so that a few huge functions do not flatten the rest, and percentiles take
+//! the nearest rank. Assert and quantifier counts are only known with
+//! `--show-specs`, and left out without it.
+
+use crate::FunctionInfo;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Statistics over a list of counts
+#[derive(Debug, Serialize)]
+pub struct Distribution {
+    pub count: usize,
+    pub total: usize,
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub p50: usize,
+    pub p90: usize,
+    pub p99: usize,
+    pub histogram: Vec<Bucket>,
+}
+
+/// The values from `from` to `to`, both included, and how many there are
+#[derive(Debug, Serialize)]
+pub struct Bucket {
+    pub from: usize,
+    pub to: usize,
+    pub count: usize,
+}
+
+impl Distribution {
+    fn of(mut values: Vec<usize>) -> Self {
+        values.sort_unstable();
+        let total: usize = values.iter().sum();
+        let percentile = |p: usize| {
+            let rank = (values.len() * p).div_ceil(100).max(1);
+            values.get(rank - 1).copied().unwrap_or(0)
+        };
+        let max = values.last().copied().unwrap_or(0);
+        let mut histogram: Vec<Bucket> = Vec::new();
+        let mut from = 0;
+        while from <= max {
+            let to = if from == 0 { 0 } else { from * 2 - 1 };
+            let count = values.iter().filter(|&&v| v >= from && v <= to).count();
+            histogram.push(Bucket { from, to, count });
+            from = to + 1;
+        }
+        // From the bucket of the smallest value
+        let first = histogram.iter().position(|b| b.count > 0).unwrap_or(0);
+        histogram.drain(..first);
+        Distribution {
+            count: values.len(),
+            total,
+            min: values.first().copied().unwrap_or(0),
+            max,
+            mean: if values.is_empty() {
+                0.0
+            } else {
+                total as f64 / values.len() as f64
+            },
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            histogram,
+        }
+    }
+}
+
+/// The functions of a module and their sizes
+#[derive(Debug, Serialize)]
+pub struct ModuleStats {
+    pub functions: usize,
+    pub mean_lines: f64,
+    pub max_lines: usize,
+    /// Qualified name of the largest function, the first one on a tie
+    pub largest: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub total_functions: usize,
+    pub lines: Distribution,
+    pub by_kind: BTreeMap<String, usize>,
+    pub by_visibility: BTreeMap<String, usize>,
+    /// By module (`crate` for the root)
+    pub by_module: BTreeMap<String, ModuleStats>,
+    /// `assert`s per function, with `--show-specs`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asserts: Option<Distribution>,
+    /// Quantifiers per function, with `--show-specs`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantifiers: Option<Distribution>,
+}
+
+fn lines(func: &FunctionInfo) -> usize {
+    func.end_line + 1 - func.start_line
+}
+
+/// The distribution of `count` over the functions having it, if any do
+fn counted(
+    functions: &[FunctionInfo],
+    count: impl Fn(&FunctionInfo) -> Option<usize>,
+) -> Option<Distribution> {
+    let values: Vec<usize> = functions.iter().filter_map(count).collect();
+    (!values.is_empty()).then(|| Distribution::of(values))
+}
+
+impl Stats {
+    pub fn of(functions: &[FunctionInfo]) -> Self {
+        let mut by_kind: BTreeMap<String, usize> = BTreeMap::new();
+        let mut by_visibility: BTreeMap<String, usize> = BTreeMap::new();
+        let mut modules: BTreeMap<String, Vec<&FunctionInfo>> = BTreeMap::new();
+        for func in functions {
+            if let Some(ref kind) = func.kind {
+                *by_kind.entry(kind.clone()).or_default() += 1;
+            }
+            if let Some(ref visibility) = func.visibility {
+                *by_visibility.entry(visibility.clone()).or_default() += 1;
+            }
+            let module = if func.module_path.is_empty() {
+                "crate".to_string()
+            } else {
+                func.module_path.clone()
+            };
+            modules.entry(module).or_default().push(func);
+        }
+        let by_module = modules
+            .into_iter()
+            .map(|(module, funcs)| {
+                let total: usize = funcs.iter().map(|f| lines(f)).sum();
+                let largest = funcs
+                    .iter()
+                    .copied()
+                    .reduce(|a, b| if lines(b) > lines(a) { b } else { a })
+                    .expect("a module has functions");
+                let stats = ModuleStats {
+                    functions: funcs.len(),
+                    mean_lines: total as f64 / funcs.len() as f64,
+                    max_lines: lines(largest),
+                    largest: largest.qualified_name.clone(),
+                };
+                (module, stats)
+            })
+            .collect();
+        Stats {
+            total_functions: functions.len(),
+            lines: Distribution::of(functions.iter().map(lines).collect()),
+            by_kind,
+            by_visibility,
+            by_module,
+            asserts: counted(functions, |f| f.assert_count),
+            quantifiers: counted(functions, |f| f.quantifier_count),
+        }
+    }
+
+    /// The statistics as aligned tables
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Functions: {}", self.total_functions).unwrap();
+        distribution(&mut out, "Lines per function", &self.lines);
+        if let Some(ref asserts) = self.asserts {
+            distribution(&mut out, "Asserts per function", asserts);
+        }
+        if let Some(ref quantifiers) = self.quantifiers {
+            distribution(&mut out, "Quantifiers per function", quantifiers);
+        }
+        counts(&mut out, "By kind", &self.by_kind);
+        counts(&mut out, "By visibility", &self.by_visibility);
+
+        writeln!(out, "\nBy module").unwrap();
+        let width = self.by_module.keys().map(String::len).fold(6, usize::max);
+        writeln!(
+            out,
+            "  {:width$}  {:>9}  {:>10}  {:>9}  largest",
+            "module", "functions", "mean lines", "max lines"
+        )
+        .unwrap();
+        for (module, stats) in &self.by_module {
+            writeln!(
+                out,
+                "  {:width$}  {:>9}  {:>10.1}  {:>9}  {}",
+                module, stats.functions, stats.mean_lines, stats.max_lines, stats.largest
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+/// A distribution's summary line and histogram, with bars scaled to the
+/// largest bucket
+fn distribution(out: &mut String, title: &str, dist: &Distribution) {
+    writeln!(out, "\n{}", title).unwrap();
+    writeln!(
+        out,
+        "  min {}  p50 {}  p90 {}  p99 {}  max {}  mean {:.1}",
+        dist.min, dist.p50, dist.p90, dist.p99, dist.max, dist.mean
+    )
+    .unwrap();
+    let largest = dist.histogram.iter().map(|b| b.count).max().unwrap_or(0);
+    for bucket in &dist.histogram {
+        let range = if bucket.from == bucket.to {
+            bucket.from.to_string()
+        } else {
+            format!("{}-{}", bucket.from, bucket.to)
+        };
+        let bar = "#".repeat((bucket.count * 40).div_ceil(largest.max(1)));
+        let row = format!("  {:>9}  {:>6}  {}", range, bucket.count, bar);
+        writeln!(out, "{}", row.trim_end()).unwrap();
+    }
+}
+
+fn counts(out: &mut String, title: &str, counts: &BTreeMap<String, usize>) {
+    if counts.is_empty() {
+        return;
+    }
+    writeln!(out, "\n{}", title).unwrap();
+    let width = counts.keys().map(String::len).max().unwrap_or(0);
+    for (key, count) in counts {
+        writeln!(out, "  {:width$}  {:>6}", key, count).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+//! The cargo target a source file belongs to
+//!
+//! The package of a file is the nearest enclosing directory whose
+//! `Cargo.toml` has a `[package]` section, so workspace members classify the
+//! same whether the workspace or the member is scanned. Targets declared in
+//! the manifest (`[lib]`, `[[bin]]`, `[[test]]`, ... with a `path`) are
+//! matched first, then cargo's conventional layout: `src/main.rs` and
+//! `src/bin/` are binaries, the rest of `src/` is the library (or the
+//! binary's modules when there is no `src/lib.rs`), and `tests/`,
+//! `examples/`, `benches/` and `build.rs` are the test, example, bench and
+//! build targets. The manifest is read line by line rather than parsed as
+//! TOML, which is enough for the sections and `path` keys needed here.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A package: its directory and the targets its manifest declares, as
+/// (kind, root file) pairs
+struct Package {
+    dir: PathBuf,
+    declared: Vec<(String, PathBuf)>,
+}
+
+/// The target kind of `file`: `lib`, `bin`, `test`, `example`, `bench` or
+/// `build`; `None` outside any package or its conventional directories
+pub fn target_of(file: &Path) -> Option<String> {
+    let file = fs::canonicalize(file).ok()?;
+    let package = file.ancestors().skip(1).find_map(package_at)?;
+    if let Some((kind, _)) = package.declared.iter().find(|(_, root)| *root == file) {
+        return Some(kind.clone());
+    }
+    let relative = file.strip_prefix(&package.dir).ok()?;
+    let mut components = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy());
+    let first = components.next()?;
+    let second = components.next();
+    let kind = match (first.as_ref(), second.as_deref()) {
+        ("src", Some("main.rs")) | ("src", Some("bin")) => "bin",
+        ("src", _) if package.dir.join("src/lib.rs").exists() => "lib",
+        ("src", _) if package.dir.join("src/main.rs").exists() => "bin",
+        ("src", _) => "lib",
+        ("tests", _) => "test",
+        ("examples", _) => "example",
+        ("benches", _) => "bench",
+        ("build.rs", None) => "build",
+        // Modules of a declared target living outside the conventional
+        // directories
+        _ => {
+            return package
+                .declared
+                .iter()
+                .filter(|(_, root)| root.parent().is_some_and(|dir| file.starts_with(dir)))
+                .max_by_key(|(_, root)| root.components().count())
+                .map(|(kind, _)| kind.clone())
+        }
+    };
+    Some(kind.to_string())
+}
+
+/// Whether `file` is the root of a cargo target: one the manifest declares,
+/// or in cargo's conventional layout `src/lib.rs`, `src/main.rs`,
+/// `src/bin/*.rs`, `src/bin/*/main.rs`, `build.rs`, and the `*.rs` and
+/// `*/main.rs` directly in `tests/`, `examples/` and `benches/`. Outside any
+/// package only the roots under a `src` directory are recognized.
+pub fn is_root(file: &Path) -> bool {
+    let file = fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+    let package = file.ancestors().skip(1).find_map(package_at);
+    if let Some(package) = &package {
+        if package.declared.iter().any(|(_, root)| *root == file) {
+            return true;
+        }
+    }
+    let relative = match &package {
+        Some(package) => file.strip_prefix(&package.dir).unwrap_or(&file),
+        None => &file,
+    };
+    let components: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    let components: Vec<&str> = components.iter().map(|c| c.as_ref()).collect();
+    if package.is_none() {
+        return match components.as_slice() {
+            [.., "src", "lib.rs" | "main.rs"] | [.., "src", "bin", _, "main.rs"] => true,
+            [.., "src", "bin", name] => name.ends_with(".rs"),
+            _ => false,
+        };
+    }
+    match components.as_slice() {
+        ["src", "lib.rs" | "main.rs"] | ["build.rs"] => true,
+        ["src", "bin", _, "main.rs"] | ["tests" | "examples" | "benches", _, "main.rs"] => true,
+        ["src", "bin", name] | ["tests" | "examples" | "benches", name] => name.ends_with(".rs"),
+        _ => false,
+    }
+}
+
+/// The package whose manifest is in `dir`, if it declares one
+fn package_at(dir: &Path) -> Option<Package> {
+    let manifest = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let mut is_package = false;
+    let mut section = String::new();
+    let mut declared = Vec::new();
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line
+                .trim_matches(|c| c == '[' || c == ']')
+                .trim()
+                .to_string();
+            is_package |= section == "package";
+            continue;
+        }
+        let kind = match section.as_str() {
+            "lib" => "lib",
+            "bin" => "bin",
+            "test" => "test",
+            "example" => "example",
+            "bench" => "bench",
+            _ => continue,
+        };
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if key.trim() == "path" {
+            let path = value.trim().trim_matches('"');
+            let root = fs::canonicalize(dir.join(path)).unwrap_or_else(|_| dir.join(path));
+            declared.push((kind.to_string(), root));
+        }
+    }
+    is_package.then(|| Package {
+        dir: dir.to_path_buf(),
+        declared,
+    })
+}
@@ -0,0 +1,34 @@
+//! `cargo verus verify` arguments selecting a set of functions
+//!
+//! Verus names a function by its path within the module passed to
+//! `--verify-only-module` (see `FunctionInfo::verify_function_arg`);
+//! functions of the crate root are selected with `--verify-root`.
+
+use crate::FunctionInfo;
+use std::collections::BTreeSet;
+
+/// Module flags first, then one `--verify-function` per distinct function,
+/// each flag and value as a separate argument
+pub fn verus_args<'a>(functions: impl IntoIterator<Item = &'a FunctionInfo>) -> Vec<String> {
+    let mut modules = BTreeSet::new();
+    let mut names = BTreeSet::new();
+    for func in functions.into_iter().filter(|f| f.is_verified_function()) {
+        modules.insert(func.module_path.as_str());
+        names.insert(func.verify_function_arg.as_str());
+    }
+
+    let mut args = Vec::new();
+    for module in modules {
+        if module.is_empty() {
+            args.push("--verify-root".to_string());
+        } else {
+            args.push("--verify-only-module".to_string());
+            args.push(module.to_string());
+        }
+    }
+    for name in names {
+        args.push("--verify-function".to_string());
+        args.push(name.to_string());
+    }
+    args
+}
@@ -133,12 +133,3875 @@ print(f"Found {data['summary']['total_functions']} functions in {data['summary']
 # Test function list
 names = parser.get_function_list(sys.argv[1])
 print(f"Function names: {', '.join(names)}")
+
+# Test functions by file
+by_file = parser.find_all_functions(sys.argv[1])
+assert sum(len(functions) for functions in by_file.values()) == data['summary']['total_functions']
+print(f"Functions in {len(by_file)} files")
 PYEOF
 
     python3 "$TEST_DIR/test_wrapper.py" "$TEST_DIR"
     echo
 fi
 
+echo "=== Test 9: Module paths and qualified names ==="
+mkdir -p "$TEST_DIR/src/backend/serial"
+cat > "$TEST_DIR/src/backend/serial/field.rs" << 'EOF'
+verus! {
+    mod inner {
+        pub mod deeper {
+            fn pow2k() {}
+        }
+    }
+
+    impl FieldElement51 {
+        pub fn pow2k(&self) {}
+    }
+}
+EOF
+$PARSER "$TEST_DIR/src" --format json | jq -r '.functions[].qualified_name' | sort > "$TEST_DIR/qualified.txt"
+cat "$TEST_DIR/qualified.txt"
+grep -qx "backend::serial::field::inner::deeper::pow2k" "$TEST_DIR/qualified.txt"
+grep -qx "backend::serial::field::FieldElement51::pow2k" "$TEST_DIR/qualified.txt"
+echo
+
+echo "=== Test 10: Callee extraction ==="
+cat > "$TEST_DIR/calls.rs" << 'EOF'
+verus! {
+    spec fn limbs_bounded(x: u64) -> bool { x < 10 }
+
+    proof fn lemma_mul(a: u64) { }
+
+    fn mul(a: u64, f: fn(u64) -> u64) -> u64
+        requires limbs_bounded(a),
+    {
+        proof { lemma_mul(a); }
+        field::mul(a, a).wrapping_add(f(a))
+    }
+}
+EOF
+$PARSER "$TEST_DIR/calls.rs" --show-calls --format json | jq -c '.functions[] | select(.name == "mul") | {callees, spec_deps, unresolved_calls}' | tee "$TEST_DIR/calls.json"
+jq -e '[.callees[].path] == ["calls::lemma_mul", "field::mul", "wrapping_add"] and .spec_deps == [{"path": "calls::limbs_bounded"}] and .unresolved_calls == ["f"]' "$TEST_DIR/calls.json" > /dev/null
+echo
+
+echo "=== Test 11: Transitive closure ==="
+mkdir -p "$TEST_DIR/closure/src"
+cat > "$TEST_DIR/closure/src/lib.rs" << 'EOF'
+verus! {
+    mod field;
+
+    pub fn pow2k(x: u64) -> u64 { field::square(x) }
+
+    fn even(n: u64) -> bool { if n == 0 { true } else { odd(n - 1) } }
+    fn odd(n: u64) -> bool { if n == 0 { false } else { even(n - 1) } }
+}
+EOF
+cat > "$TEST_DIR/closure/src/field.rs" << 'EOF'
+pub fn square(x: u64) -> u64 { mul(x, x) }
+fn mul(a: u64, b: u64) -> u64 { a.wrapping_mul(b) }
+fn unused() {}
+EOF
+$PARSER closure --function pow2k "$TEST_DIR/closure" --format json > "$TEST_DIR/closure.json"
+jq -c '[.functions[].qualified_name], .unresolved_callees' "$TEST_DIR/closure.json"
+jq -e '[.functions[].qualified_name] == ["pow2k", "field::square", "field::mul"]' "$TEST_DIR/closure.json" > /dev/null
+jq -e '.unresolved_callees == [{"caller": "field::mul", "callee": "wrapping_mul"}]' "$TEST_DIR/closure.json" > /dev/null
+echo "Mutual recursion:"
+$PARSER closure --function even "$TEST_DIR/closure" --format text
+echo
+
+echo "=== Test 12: Callers ==="
+cat > "$TEST_DIR/closure/src/point.rs" << 'EOF'
+impl Point {
+    fn double(&self) -> u64 { self.scale(2) }
+    fn scale(&self, k: u64) -> u64 { crate::field::square(k) }
+}
+EOF
+echo "All callers of field::mul:"
+$PARSER callers --function field::mul "$TEST_DIR/closure" --format text | tee "$TEST_DIR/callers.txt"
+[ "$(tr '\n' ' ' < "$TEST_DIR/callers.txt")" = "field::square point::Point::double point::Point::scale pow2k " ]
+echo "Direct callers of Point::scale:"
+$PARSER callers --function Point::scale --direct-only "$TEST_DIR/closure" --format json | jq -r '.functions[].qualified_name' | tee "$TEST_DIR/callers.txt"
+[ "$(cat "$TEST_DIR/callers.txt")" = "point::Point::double" ]
+echo
+
+echo "=== Test 13: Self:: and same-impl method calls ==="
+mkdir -p "$TEST_DIR/impls/src"
+cat > "$TEST_DIR/impls/src/lib.rs" << 'EOF'
+verus! {
+    pub struct FieldElement51 { limbs: [u64; 5] }
+    pub struct Scalar52 { limbs: [u64; 5] }
+
+    impl FieldElement51 {
+        fn reduce(limbs: [u64; 5]) -> FieldElement51 { FieldElement51 { limbs } }
+        fn square(&self) -> FieldElement51 { Self::reduce(self.limbs) }
+        pub fn pow2k(&self, k: u32) -> FieldElement51 {
+            let mut x = self.square();
+            x = x.square();
+            x
+        }
+    }
+
+    impl Scalar52 {
+        fn reduce(limbs: [u64; 5]) -> Scalar52 { Scalar52 { limbs } }
+        fn square(&self) -> Scalar52 { Self::reduce(self.limbs) }
+    }
+}
+EOF
+$PARSER "$TEST_DIR/impls" --show-calls --format json | jq -c '.functions[] | select(.name == "pow2k" or .name == "square") | {qualified_name, callees}' | tee "$TEST_DIR/impls.json"
+jq -se '.[0].callees == [{"path": "FieldElement51::reduce"}]' "$TEST_DIR/impls.json" > /dev/null
+jq -se '.[1].callees == [{"path": "FieldElement51::square"}, {"path": "square", "ambiguous": true}]' "$TEST_DIR/impls.json" > /dev/null
+echo "Closure of Scalar52::square (must not include FieldElement51::reduce):"
+$PARSER closure --function Scalar52::square "$TEST_DIR/impls" --format json | jq -r '.functions[].qualified_name' | tee "$TEST_DIR/impls.txt"
+[ "$(tr '\n' ' ' < "$TEST_DIR/impls.txt")" = "Scalar52::square Scalar52::reduce " ]
+echo
+
+echo "=== Test 14: Resolving callees through use imports ==="
+mkdir -p "$TEST_DIR/imports/src/backend"
+cat > "$TEST_DIR/imports/src/lib.rs" << 'EOF'
+mod backend;
+mod constants;
+
+use crate::backend::field::{self, FieldElement51 as FE};
+
+fn top() {
+    field::mul();
+    FE::square();
+    constants::d();
+    vstd::arithmetic::lemma_mul();
+}
+EOF
+cat > "$TEST_DIR/imports/src/constants.rs" << 'EOF'
+pub fn d() {}
+pub fn mul() {}
+EOF
+cat > "$TEST_DIR/imports/src/backend/mod.rs" << 'EOF'
+pub mod field;
+
+use crate::constants::*;
+use self::field::*;
+
+fn both_globs() { d(); mul(); }
+EOF
+cat > "$TEST_DIR/imports/src/backend/field.rs" << 'EOF'
+use super::super::constants::*;
+
+pub fn mul() { d(); }
+
+impl FieldElement51 {
+    pub fn square() { mul(); }
+}
+EOF
+$PARSER "$TEST_DIR/imports" --show-calls --format json > "$TEST_DIR/imports.json"
+jq -c '.functions[] | {qualified_name, callees}' "$TEST_DIR/imports.json"
+jq -e '.functions[] | select(.qualified_name == "top") | [.callees[].path] == ["backend::field::FieldElement51::square", "backend::field::mul", "constants::d", "vstd::arithmetic::lemma_mul"]' "$TEST_DIR/imports.json" > /dev/null
+jq -e '.functions[] | select(.qualified_name == "backend::field::FieldElement51::square") | .callees == [{"path": "backend::field::mul"}]' "$TEST_DIR/imports.json" > /dev/null
+jq -e '.functions[] | select(.qualified_name == "backend::both_globs") | .callees == [{"path": "constants::d"}, {"path": "mul", "ambiguous": true}]' "$TEST_DIR/imports.json" > /dev/null
+echo
+
+echo "=== Test 15: Recursion and strongly connected components ==="
+cat > "$TEST_DIR/closure/src/fact.rs" << 'EOF'
+fn fact(n: u64) -> u64 { if n == 0 { 1 } else { n * fact(n - 1) } }
+EOF
+$PARSER "$TEST_DIR/closure" --show-calls --format json | jq -c '[.functions[] | select(.is_recursive) | .qualified_name] | sort' | tee "$TEST_DIR/recursive.json"
+jq -e '. == ["even", "fact::fact", "odd"]' "$TEST_DIR/recursive.json" > /dev/null
+$PARSER call-graph --sccs "$TEST_DIR/closure" --format text
+$PARSER call-graph --sccs "$TEST_DIR/closure" --format json > "$TEST_DIR/sccs.json"
+jq -e '[.sccs[] | [.[].qualified_name]] == [["even", "odd"]]' "$TEST_DIR/sccs.json" > /dev/null
+$PARSER call-graph "$TEST_DIR/closure" --format json | jq -e '.edges.pow2k == ["field::square"] and .edges["fact::fact"] == ["fact::fact"]' > /dev/null
+echo
+
+echo "=== Test 16: DOT output ==="
+cat > "$TEST_DIR/closure/src/modes.rs" << 'EOF'
+verus! {
+    spec fn bounded(x: u64) -> bool { x < 10 }
+    proof fn lemma_bounded(x: u64) { }
+    fn checked(x: u64) requires bounded(x) { proof { lemma_bounded(x); } vstd::arithmetic::helper(x); }
+}
+EOF
+$PARSER call-graph --root modes::checked "$TEST_DIR/closure" --format dot | tee "$TEST_DIR/graph.dot"
+head -1 "$TEST_DIR/graph.dot" | grep -q "^digraph"
+grep -qF '"modes::bounded" [label="modes::bounded", fillcolor=lightblue];' "$TEST_DIR/graph.dot"
+grep -qF '"modes::lemma_bounded" [label="modes::lemma_bounded", fillcolor=palegreen];' "$TEST_DIR/graph.dot"
+grep -qF '"external:vstd::arithmetic::helper" [label="vstd::arithmetic::helper", shape=box, style=dashed];' "$TEST_DIR/graph.dot"
+grep -qF '"modes::checked" -> "external:vstd::arithmetic::helper";' "$TEST_DIR/graph.dot"
+[ "$(grep -c pow2k "$TEST_DIR/graph.dot")" = 0 ]
+$PARSER "$TEST_DIR/closure" --format dot | grep -qF '"pow2k" -> "field::square";'
+echo
+
+echo "=== Test 17: Module dependency graph ==="
+$PARSER module-graph "$TEST_DIR/closure" --format json | tee "$TEST_DIR/modules.json"
+jq -e '.modules.crate == ["field"] and .modules.point == ["field"] and .modules.field == [] and .modules.fact == []' "$TEST_DIR/modules.json" > /dev/null
+$PARSER module-graph "$TEST_DIR/closure" --format dot | grep -qF '"point" -> "field";'
+echo
+
+echo "=== Test 18: Spec dependencies from requires/ensures/decreases ==="
+mkdir -p "$TEST_DIR/specs/src"
+cat > "$TEST_DIR/specs/src/lib.rs" << 'EOF'
+verus! {
+    spec fn limbs_bounded(x: u64) -> bool { x < 10 }
+    spec fn spec_mul(a: u64, b: u64) -> nat { (a * b) as nat }
+    spec fn measure(n: u64) -> nat { n as nat }
+    proof fn lemma_mul(a: u64) { }
+
+    fn mul(a: u64, b: u64) -> (r: u64)
+        requires limbs_bounded(a), limbs_bounded(b),
+        ensures r == spec_mul(a, b),
+        decreases measure(a),
+    {
+        proof { lemma_mul(a); }
+        a * b
+    }
+}
+EOF
+$PARSER "$TEST_DIR/specs" --show-calls --format json | jq -c '.functions[] | select(.name == "mul") | {callees, spec_deps}' | tee "$TEST_DIR/specs.json"
+jq -e '.callees == [{"path": "lemma_mul"}] and .spec_deps == [{"path": "limbs_bounded"}, {"path": "measure"}, {"path": "spec_mul"}]' "$TEST_DIR/specs.json" > /dev/null
+$PARSER closure --function mul "$TEST_DIR/specs" --format text | tee "$TEST_DIR/specs.txt"
+[ "$(tr '\n' ' ' < "$TEST_DIR/specs.txt")" = "lemma_mul limbs_bounded measure mul spec_mul " ]
+$PARSER closure --function mul --no-spec-deps "$TEST_DIR/specs" --format text | tee "$TEST_DIR/specs.txt"
+[ "$(tr '\n' ' ' < "$TEST_DIR/specs.txt")" = "lemma_mul mul " ]
+echo
+
+echo "=== Test 19: Unused lemmas ==="
+mkdir -p "$TEST_DIR/lemmas/src"
+cat > "$TEST_DIR/lemmas/src/lib.rs" << 'EOF'
+verus! {
+    spec fn unused_spec() -> bool { true }
+
+    proof fn lemma_used() { lemma_used_transitively(); }
+    proof fn lemma_used_transitively() { }
+    proof fn lemma_orphan() { lemma_only_from_orphan(); }
+    proof fn lemma_only_from_orphan() { }
+    proof fn lemma_keep_for_users() { }
+
+    fn run() {
+        proof { lemma_used(); }
+    }
+}
+EOF
+$PARSER unused-lemmas "$TEST_DIR/lemmas" --allow 'lemma_keep_.*' --format text | tee "$TEST_DIR/lemmas.txt"
+grep -q "^lemma_orphan @ .*/lemmas/src/lib.rs:6$" "$TEST_DIR/lemmas.txt"
+grep -q "^lemma_only_from_orphan @ .*/lemmas/src/lib.rs:7$" "$TEST_DIR/lemmas.txt"
+$PARSER unused-lemmas "$TEST_DIR/lemmas" --allow 'lemma_keep_.*' --format json > "$TEST_DIR/lemmas.json"
+jq -e '[.unused_lemmas[].qualified_name] == ["lemma_orphan", "lemma_only_from_orphan"]' "$TEST_DIR/lemmas.json" > /dev/null
+$PARSER unused-lemmas "$TEST_DIR/lemmas" --allow lemma_orphan --format json | jq -e '[.unused_lemmas[].qualified_name] == ["lemma_only_from_orphan", "lemma_keep_for_users"]' > /dev/null
+echo
+
+echo "=== Test 20: Depth-limited closure and callers ==="
+$PARSER closure --function pow2k --depth 0 "$TEST_DIR/closure" --format json | jq -e '[.functions[] | {qualified_name, depth}] == [{"qualified_name": "pow2k", "depth": 0}]' > /dev/null
+$PARSER closure --function pow2k --depth 1 "$TEST_DIR/closure" --format json | jq -c '[.functions[] | {qualified_name, depth}]' | tee "$TEST_DIR/depth.json"
+jq -e '. == [{"qualified_name": "pow2k", "depth": 0}, {"qualified_name": "field::square", "depth": 1}]' "$TEST_DIR/depth.json" > /dev/null
+$PARSER closure --function pow2k "$TEST_DIR/closure" --format json | jq -e '[.functions[].depth] == [0, 1, 2]' > /dev/null
+$PARSER callers --function field::mul --depth 2 "$TEST_DIR/closure" --format detailed | tee "$TEST_DIR/depth.txt"
+grep -q "^square .* at depth 1$" "$TEST_DIR/depth.txt"
+[ "$(grep -c "at depth 2$" "$TEST_DIR/depth.txt")" = 2 ]
+[ "$(grep -c "at depth 3$" "$TEST_DIR/depth.txt")" = 0 ]
+echo
+
+echo "=== Test 21: Closure within a module ==="
+$PARSER closure --function Point::double --within-module point "$TEST_DIR/closure" --format json > "$TEST_DIR/within.json"
+jq -c '[.functions[].qualified_name], [.external_deps[].qualified_name]' "$TEST_DIR/within.json"
+jq -e '[.functions[].qualified_name] == ["point::Point::double", "point::Point::scale"]' "$TEST_DIR/within.json" > /dev/null
+jq -e '[.external_deps[] | {qualified_name, depth}] == [{"qualified_name": "field::square", "depth": 2}]' "$TEST_DIR/within.json" > /dev/null
+$PARSER closure --function Point::double --within-module crate::point "$TEST_DIR/closure" --format text 2> "$TEST_DIR/within.err"
+grep -q "^Note: depends on field::square outside the module$" "$TEST_DIR/within.err"
+echo
+
+echo "=== Test 22: Verus arguments for a closure ==="
+$PARSER closure --function Point::double "$TEST_DIR/closure" --format verus-args | tee "$TEST_DIR/verus_args.txt"
+[ "$(cat "$TEST_DIR/verus_args.txt")" = "--verify-only-module field --verify-only-module point --verify-function Point::double --verify-function Point::scale --verify-function mul --verify-function square" ]
+$PARSER closure --function pow2k --depth 0 "$TEST_DIR/closure" --format verus-args --null | xargs -0 printf '[%s]' | tee "$TEST_DIR/verus_args.txt"
+echo
+[ "$(cat "$TEST_DIR/verus_args.txt")" = "[--verify-root][--verify-function][pow2k]" ]
+echo
+
+echo "=== Test 23: Dead functions from a set of roots ==="
+mkdir -p "$TEST_DIR/dead/src"
+cat > "$TEST_DIR/dead/src/lib.rs" << 'EOF'
+mod util;
+
+verus! {
+    spec fn valid(x: u64) -> bool { x < 100 }
+
+    pub fn api_add(x: u64) -> u64
+        requires valid(x),
+    {
+        util::helper(x)
+    }
+
+    pub fn api_sub(x: u64) -> u64 { x }
+
+    fn ported_but_unused() { }
+}
+EOF
+cat > "$TEST_DIR/dead/src/util.rs" << 'EOF'
+pub fn helper(x: u64) -> u64 { x }
+
+pub fn old_helper(x: u64) -> u64 { x }
+
+fn older_helper(x: u64) -> u64 {
+    old_helper(x)
+}
+EOF
+$PARSER dead-functions --root 'api_.*' "$TEST_DIR/dead" --format text | tee "$TEST_DIR/dead.txt"
+$PARSER dead-functions --root 'api_.*' "$TEST_DIR/dead" --format json > "$TEST_DIR/dead.json"
+jq -e '.dead_functions | to_entries | map({file: (.key | split("/") | last), functions: [.value[] | [.qualified_name, .start_line, .end_line]]}) == [{"file": "lib.rs", "functions": [["ported_but_unused", 14, 14]]}, {"file": "util.rs", "functions": [["util::old_helper", 3, 3], ["util::older_helper", 5, 7]]}]' "$TEST_DIR/dead.json" > /dev/null
+jq -e '.summary == {"total_functions": 7, "roots": 2, "dead_functions": 3}' "$TEST_DIR/dead.json" > /dev/null
+$PARSER dead-functions --root api_add --root util::old_helper "$TEST_DIR/dead" --format json | jq -e '[.dead_functions[][].qualified_name] == ["api_sub", "ported_but_unused", "util::older_helper"]' > /dev/null
+echo
+
+echo "=== Test 24: Trusted dependencies ==="
+mkdir -p "$TEST_DIR/trusted/src"
+cat > "$TEST_DIR/trusted/src/lib.rs" << 'EOF'
+verus! {
+    #[verifier::external_body]
+    fn read_clock() -> u64 { 0 }
+
+    #[verifier(external_body)]
+    fn write_log(x: u64) { }
+
+    proof fn lemma_assumed(x: u64) ensures x < 100 { assume(x < 100); }
+    proof fn lemma_admitted() { admit(); }
+    proof fn lemma_clean() { }
+
+    fn uses_clock() -> u64 { read_clock() }
+    fn top(x: u64) { proof { lemma_assumed(x); lemma_clean(); } let t = uses_clock(); write_log(t); }
+    fn verified_only() { proof { lemma_clean(); } }
+}
+EOF
+$PARSER trusted "$TEST_DIR/trusted" --format json > "$TEST_DIR/trusted.json"
+jq -c '.trusted_leaves, .summary' "$TEST_DIR/trusted.json"
+jq -e '.trusted_leaves == {"lemma_admitted": ["admit"], "lemma_assumed": ["assume"], "read_clock": ["external_body"], "write_log": ["external_body"]}' "$TEST_DIR/trusted.json" > /dev/null
+jq -e '.functions.top == ["lemma_assumed", "read_clock", "write_log"] and .functions.uses_clock == ["read_clock"] and .functions.verified_only == []' "$TEST_DIR/trusted.json" > /dev/null
+jq -e '.summary == {"total_functions": 8, "trusted_leaves": 4, "functions_relying_on_trusted": 6}' "$TEST_DIR/trusted.json" > /dev/null
+$PARSER trusted "$TEST_DIR/trusted" --format text
+echo
+
+echo "=== Test 25: --verify-function names ==="
+mkdir -p "$TEST_DIR/verify_names/src"
+cat > "$TEST_DIR/verify_names/src/field.rs" << 'EOF'
+verus! {
+    pub fn free_function() { }
+
+    impl FieldElement51 {
+        pub fn pow2k(&self) { }
+    }
+
+    impl<'a> Mul<&'a FieldElement51> for &'a FieldElement51 {
+        fn mul(self, rhs: &'a FieldElement51) -> FieldElement51 { FieldElement51 { } }
+    }
+
+    impl<T: Copy> Wrapper<T> {
+        fn get(&self) -> T { self.0 }
+    }
+
+    impl<T> core::ops::Neg for Wrapper<T> {
+        fn neg(self) -> Wrapper<T> { self }
+    }
+
+    trait Square {
+        fn square(&self) -> Self;
+        fn square_twice(&self) -> Self { self.square().square() }
+    }
+}
+EOF
+$PARSER "$TEST_DIR/verify_names" --format json | jq -c '.functions[] | [.qualified_name, .verify_function_arg, .impl_target]' | tee "$TEST_DIR/verify_names.txt"
+jq -se 'map({(.[0]): .[1]}) | add == {
+    "field::free_function": "free_function",
+    "field::FieldElement51::pow2k": "FieldElement51::pow2k",
+    "field::<FieldElement51 as Mul>::mul": "<FieldElement51 as Mul>::mul",
+    "field::Wrapper::get": "Wrapper::get",
+    "field::<Wrapper as Neg>::neg": "<Wrapper as Neg>::neg",
+    "field::Square::square": "Square::square",
+    "field::Square::square_twice": "Square::square_twice"
+}' "$TEST_DIR/verify_names.txt" > /dev/null
+jq -se '.[2][2] == {"self_type": "FieldElement51", "trait": "Mul"} and .[5][2] == {"trait": "Square"} and .[0][2] == null' "$TEST_DIR/verify_names.txt" > /dev/null
+$PARSER closure --function FieldElement51::mul "$TEST_DIR/verify_names" --format verus-args | grep -qx -- "--verify-only-module field --verify-function '<FieldElement51 as Mul>::mul'"
+# The arguments are quoted, so the shell reads them back as printed
+eval "set -- $($PARSER closure --function FieldElement51::mul "$TEST_DIR/verify_names" --format verus-args)"
+[ "$#" = "4" ] && [ "$4" = "<FieldElement51 as Mul>::mul" ]
+echo
+
+echo "=== Test 26: Modules covering a closure ==="
+$PARSER module-cover --function Point::double "$TEST_DIR/closure" --format json > "$TEST_DIR/cover.json"
+jq -c '.modules[]' "$TEST_DIR/cover.json"
+jq -e '[.modules[] | [.module, .needed, .total, .few_functions]] == [["field", 2, 3, true], ["point", 2, 2, true]]' "$TEST_DIR/cover.json" > /dev/null
+jq -e '.modules[0].functions == ["mul", "square"]' "$TEST_DIR/cover.json" > /dev/null
+[ "$($PARSER module-cover --function Point::double "$TEST_DIR/closure" --format verus-args)" = "--verify-only-module field --verify-only-module point" ]
+$PARSER module-cover --function pow2k "$TEST_DIR/closure" --format text
+echo
+
+echo "=== Test 27: Qualified names in text output ==="
+cat > "$TEST_DIR/dup_methods.rs" << 'EOF'
+impl FieldElement51 {
+    fn mul(&self) { }
+}
+
+impl Scalar52 {
+    fn mul(&self) { }
+}
+
+impl Mul for FieldElement51 {
+    fn mul(self, rhs: FieldElement51) -> FieldElement51 { self }
+}
+EOF
+$PARSER "$TEST_DIR/dup_methods.rs" --format text | tee "$TEST_DIR/dup.txt"
+[ "$(tr '\n' ' ' < "$TEST_DIR/dup.txt")" = "dup_methods::<FieldElement51 as Mul>::mul dup_methods::FieldElement51::mul dup_methods::Scalar52::mul " ]
+$PARSER "$TEST_DIR/dup_methods.rs" --format text --bare-names 2> "$TEST_DIR/dup.err" | tee "$TEST_DIR/dup.txt"
+[ "$(cat "$TEST_DIR/dup.txt")" = "mul" ]
+grep -qx "Warning: mul is ambiguous (3 functions: dup_methods::<FieldElement51 as Mul>::mul, dup_methods::FieldElement51::mul, dup_methods::Scalar52::mul)" "$TEST_DIR/dup.err"
+echo
+
+echo "=== Test 28: Querying saved JSON output ==="
+$PARSER "$TEST_DIR/closure" --show-kind --show-calls --format json > "$TEST_DIR/saved.json"
+jq -e '.schema_version == 4' "$TEST_DIR/saved.json" > /dev/null
+$PARSER query --input "$TEST_DIR/saved.json" --function pow2k --closure --format text | tee "$TEST_DIR/query.txt"
+[ "$(tr '\n' ' ' < "$TEST_DIR/query.txt")" = "field::mul field::square pow2k " ]
+[ "$($PARSER query --input "$TEST_DIR/saved.json" --module field --name-regex '^(mul|unused)$' --format text | tr '\n' ' ')" = "field::mul field::unused " ]
+[ "$($PARSER query --input "$TEST_DIR/saved.json" --kind spec --format text)" = "modes::bounded" ]
+[ "$($PARSER query --input "$TEST_DIR/saved.json" --kind 'proof fn' --format json | jq -c '[.functions[].name]')" = '["lemma_bounded"]' ]
+jq 'del(.schema_version)' "$TEST_DIR/saved.json" > "$TEST_DIR/stale.json"
+if $PARSER query --input "$TEST_DIR/stale.json" --format text 2> "$TEST_DIR/stale.err"; then exit 1; fi
+cat "$TEST_DIR/stale.err"
+grep -q "has no schema_version but this verus-parser reads version 4" "$TEST_DIR/stale.err"
+jq '.schema_version = 3' "$TEST_DIR/saved.json" > "$TEST_DIR/older.json"
+if $PARSER query --input "$TEST_DIR/older.json" --format text 2> "$TEST_DIR/older.err"; then exit 1; fi
+grep -q "has schema version 3 but this verus-parser reads version 4" "$TEST_DIR/older.err"
+echo
+
+echo "=== Test 29: Requires clauses ==="
+cat > "$TEST_DIR/specs_text.rs" << 'EOF'
+verus! {
+    spec fn limbs_bounded(a: &[u64], bits: u64) -> bool { true }
+
+    fn mul(a: &[u64], b: &[u64]) -> u64
+        requires
+            limbs_bounded(a, 54),
+            limbs_bounded(b,
+                54),
+            forall|i: int|
+                0 <= i < a.len() ==> a[i] < (1u64 << 52),
+    {
+        0
+    }
+
+    fn no_requires() -> u64 { 0 }
+}
+EOF
+$PARSER "$TEST_DIR/specs_text.rs" --show-specs --format json | jq -c '.functions[] | {name, requires}' | tee "$TEST_DIR/requires.json"
+[ "$(jq -c 'select(.name == "mul") | .requires' "$TEST_DIR/requires.json")" = '["limbs_bounded(a, 54)","limbs_bounded(b, 54)","forall|i: int| 0 <= i < a.len() ==> a[i] < (1u64 << 52)"]' ]
+[ "$(jq -c 'select(.name == "no_requires") | .requires' "$TEST_DIR/requires.json")" = '[]' ]
+[ "$($PARSER "$TEST_DIR/specs_text.rs" --format json | jq '[.functions[] | has("requires")] | any')" = false ]
+$PARSER "$TEST_DIR/specs_text.rs" --show-specs --format detailed | grep "^    requires limbs_bounded(b, 54)$"
+echo
+
+echo "=== Test 30: Ensures clauses and trivial postconditions ==="
+cat > "$TEST_DIR/ensures.rs" << 'EOF'
+verus! {
+    spec fn spec_add(a: u64, b: u64) -> int { a + b }
+
+    fn add(a: u64, b: u64) -> (r: u64)
+        requires
+            a + b < u64::MAX,
+        ensures
+            r == spec_add(a, b),
+            r >= a,
+    {
+        a + b
+    }
+
+    fn hollow(a: u64) -> u64
+        ensures
+            true,
+    {
+        a
+    }
+
+    fn no_ensures(a: u64) -> u64 { a }
+
+    proof fn lemma_without_ensures() {}
+}
+EOF
+$PARSER "$TEST_DIR/ensures.rs" --show-specs --format json | jq -c '.functions[] | [.name, .ensures, .trivial_ensures]' | tee "$TEST_DIR/ensures.json"
+[ "$(jq -c 'select(.[0] == "add")' "$TEST_DIR/ensures.json")" = '["add",["r == spec_add(a, b)","r >= a"],false]' ]
+[ "$(jq -c 'select(.[2]) | .[0]' "$TEST_DIR/ensures.json" | tr '\n' ' ')" = '"hollow" "no_ensures" ' ]
+$PARSER "$TEST_DIR/ensures.rs" --show-specs --format detailed | tee "$TEST_DIR/ensures.txt"
+grep -q "^    ensures r >= a$" "$TEST_DIR/ensures.txt"
+grep -q "^hollow .* (trivial ensures)$" "$TEST_DIR/ensures.txt"
+echo
+
+echo "=== Test 31: Recursive functions missing decreases ==="
+cat > "$TEST_DIR/decreases.rs" << 'EOF'
+verus! {
+    spec fn fib(n: nat) -> nat
+        decreases n,
+    {
+        if n < 2 { n } else { fib((n - 1) as nat) + fib((n - 2) as nat) }
+    }
+
+    spec fn sum_to(n: nat) -> nat {
+        if n == 0 { 0 } else { n + sum_to((n - 1) as nat) }
+    }
+
+    proof fn lemma_even(n: nat)
+        decreases n, 0nat,
+    {
+        if n > 0 { lemma_odd((n - 1) as nat); }
+    }
+
+    proof fn lemma_odd(n: nat) {
+        if n > 0 { lemma_even((n - 1) as nat); }
+    }
+
+    fn not_recursive() {}
+}
+EOF
+[ "$($PARSER "$TEST_DIR/decreases.rs" --show-specs --format json | jq -c '[.functions[] | .decreases]')" = '[["n"],[],["n","0nat"],[],[]]' ]
+$PARSER missing-decreases "$TEST_DIR/decreases.rs" --format text | tee "$TEST_DIR/decreases.txt"
+grep -q "^decreases::sum_to @ .*decreases.rs:8 (direct recursion)$" "$TEST_DIR/decreases.txt"
+grep -q "^decreases::lemma_odd @ .*decreases.rs:18 (mutual recursion)$" "$TEST_DIR/decreases.txt"
+[ "$($PARSER missing-decreases "$TEST_DIR/decreases.rs" --format json | jq -c '[.missing_decreases[] | [.qualified_name, .recursion]]')" = '[["decreases::sum_to","direct"],["decreases::lemma_odd","mutual"]]' ]
+echo
+
+echo "=== Test 32: Recommends clauses ==="
+cat > "$TEST_DIR/recommends.rs" << 'EOF'
+verus! {
+    spec fn div_floor(a: int, b: int) -> int
+        recommends
+            b != 0,
+            a >= 0,
+    {
+        a / b
+    }
+
+    spec fn count_down(n: int) -> int
+        recommends
+            n >= 0,
+        decreases n
+        when n > 0
+        via count_down_decreases
+    {
+        if n > 0 { count_down(n - 1) } else { 0 }
+    }
+
+    proof fn count_down_decreases(n: int) {}
+
+    spec fn checked(a: int) -> bool
+        recommends a > 0 via checked_recommends
+    {
+        a > 0
+    }
+}
+EOF
+$PARSER "$TEST_DIR/recommends.rs" --show-specs --format json | jq -c '.functions[] | [.name, .recommends, .decreases]' | tee "$TEST_DIR/recommends.json"
+[ "$(jq -c 'select(.[0] == "div_floor") | .[1]' "$TEST_DIR/recommends.json")" = '["b != 0","a >= 0"]' ]
+[ "$(jq -c 'select(.[0] == "count_down") | .[1:]' "$TEST_DIR/recommends.json")" = '[["n >= 0"],["n"]]' ]
+[ "$(jq -c 'select(.[0] == "checked") | .[1]' "$TEST_DIR/recommends.json")" = '["a > 0","via checked_recommends"]' ]
+$PARSER "$TEST_DIR/recommends.rs" --show-specs --format detailed | grep "^    recommends " | tee "$TEST_DIR/recommends.txt"
+[ "$(grep -c "^    recommends " "$TEST_DIR/recommends.txt")" = 5 ]
+echo
+
+echo "=== Test 33: Loop invariants ==="
+cat > "$TEST_DIR/loops.rs" << 'EOF'
+verus! {
+    fn sum(v: &Vec<u64>) -> (total: u64)
+        requires
+            v.len() < 100,
+    {
+        let mut total: u64 = 0;
+        let mut i = 0;
+        while i < v.len()
+            invariant
+                i <= v.len(),
+                total <= i * 1000,
+            decreases v.len() - i,
+        {
+            i += 1;
+        }
+        loop
+            invariant_except_break
+                i <= v.len(),
+            ensures
+                i == v.len(),
+        {
+            break;
+        }
+        for j in 0..10 {
+            total = total;
+        }
+        total
+    }
+
+    proof fn lemma_loop(n: nat) {
+        proof {
+            let mut k: nat = 0;
+            while k < n
+                invariant
+                    k <= n,
+            {
+                k = k + 1;
+            }
+        }
+    }
+}
+EOF
+$PARSER "$TEST_DIR/loops.rs" --show-specs --format json > "$TEST_DIR/loops.json"
+jq -c '.functions[] | {name, loop_summary}' "$TEST_DIR/loops.json"
+[ "$(jq -c '.functions[0].loops | map([.kind, .start_line, .invariant, .invariant_except_break, .ensures])' "$TEST_DIR/loops.json")" = '[["while",8,["i <= v.len()","total <= i * 1000"],null,null],["loop",16,null,["i <= v.len()"],["i == v.len()"]],["for",24,null,null,null]]' ]
+[ "$(jq -c '.functions[0].loop_summary' "$TEST_DIR/loops.json")" = '{"with_invariants":2,"without_invariants":1}' ]
+[ "$(jq -c '.functions[1].loops[0].invariant' "$TEST_DIR/loops.json")" = '["k <= n"]' ]
+$PARSER "$TEST_DIR/loops.rs" --show-specs --format detailed > "$TEST_DIR/loops.txt"
+grep "^    loops: " "$TEST_DIR/loops.txt"
+grep -q "^    loops: 2 with invariants, 1 without$" "$TEST_DIR/loops.txt"
+grep -q "^        invariant_except_break i <= v.len()$" "$TEST_DIR/loops.txt"
+echo
+
+echo "=== Test 34: opens_invariants ==="
+cat > "$TEST_DIR/invariants.rs" << 'EOF'
+verus! {
+    fn open_any()
+        opens_invariants any
+    {
+    }
+
+    fn open_none()
+        opens_invariants none
+    {
+    }
+
+    fn open_set(credit: int)
+        opens_invariants [credit, credit + 1]
+    {
+    }
+
+    fn open_expr(ns: Set<int>)
+        opens_invariants ns
+    {
+    }
+
+    fn no_invariants() {}
+}
+EOF
+$PARSER "$TEST_DIR/invariants.rs" --show-specs --format json | jq -c '[.functions[] | [.name, .opens_invariants]]' | tee "$TEST_DIR/invariants.json"
+[ "$(cat "$TEST_DIR/invariants.json")" = '[["open_any","any"],["open_none","none"],["open_set","[credit, credit + 1]"],["open_expr","ns"],["no_invariants",null]]' ]
+[ "$($PARSER "$TEST_DIR/invariants.rs" --has-opens-invariants --format json | jq '.summary.total_functions')" = 4 ]
+$PARSER "$TEST_DIR/invariants.rs" --has-opens-invariants --show-kind --format detailed > "$TEST_DIR/invariants.txt"
+grep -q "^open_any \[fn\] \[opens_invariants any\] @" "$TEST_DIR/invariants.txt"
+grep -q "^open_set \[fn\] \[opens_invariants \[credit, credit + 1\]\] @" "$TEST_DIR/invariants.txt"
+echo
+
+echo "=== Test 35: no_unwind and returns ==="
+cat > "$TEST_DIR/unwind.rs" << 'EOF'
+verus! {
+    fn never_unwinds(x: u64) -> u64
+        no_unwind
+    {
+        x
+    }
+
+    fn unwinds_unless(x: u64) -> u64
+        requires x < 10,
+        no_unwind when x < 5
+    {
+        x
+    }
+
+    fn double(x: u32) -> (r: u64)
+        returns (x as u64) * 2,
+    {
+        (x as u64) * 2
+    }
+
+    fn may_unwind() {}
+
+    spec fn spec_only() -> bool { true }
+}
+EOF
+$PARSER "$TEST_DIR/unwind.rs" --show-specs --format json | jq -c '[.functions[] | [.name, .no_unwind, .no_unwind_when, .returns]]' | tee "$TEST_DIR/unwind.json"
+[ "$(cat "$TEST_DIR/unwind.json")" = '[["never_unwinds",true,null,null],["unwinds_unless",true,"x < 5",null],["double",false,null,"(x as u64) * 2"],["may_unwind",false,null,null],["spec_only",false,null,null]]' ]
+[ "$($PARSER "$TEST_DIR/unwind.rs" --unwinding-allowed --format text | tr '\n' ' ')" = "unwind::double unwind::may_unwind " ]
+$PARSER "$TEST_DIR/unwind.rs" --show-specs --format detailed > "$TEST_DIR/unwind.txt"
+grep -q "^    no_unwind when x < 5$" "$TEST_DIR/unwind.txt"
+grep -q "^    returns (x as u64) \* 2$" "$TEST_DIR/unwind.txt"
+echo
+
+echo "=== Test 36: Open, closed and uninterp spec functions ==="
+cat > "$TEST_DIR/openness.rs" << 'EOF'
+verus! {
+    pub open spec fn visible(x: int) -> int { x + 1 }
+
+    pub open(crate) spec fn crate_visible(x: int) -> int { x + 2 }
+
+    pub closed spec fn hidden(x: int) -> int { x + 3 }
+
+    spec fn private_default(x: int) -> int { x }
+
+    pub uninterp spec fn opaque(x: int) -> int;
+
+    proof fn lemma_visible() {}
+}
+EOF
+$PARSER "$TEST_DIR/openness.rs" --show-kind --format json > "$TEST_DIR/openness.json"
+[ "$(jq -c '[.functions[] | [.name, .spec_openness]]' "$TEST_DIR/openness.json")" = '[["visible","open"],["crate_visible","open"],["hidden","closed"],["private_default","closed"],["opaque","uninterp"],["lemma_visible",null]]' ]
+[ "$(jq -c '.summary.spec_openness' "$TEST_DIR/openness.json")" = '{"closed":2,"open":2,"uninterp":1}' ]
+[ "$($PARSER query --input "$TEST_DIR/openness.json" --kind "closed spec" --format text | tr '\n' ' ')" = "openness::hidden openness::private_default " ]
+[ "$($PARSER query --input "$TEST_DIR/openness.json" --kind "uninterp spec fn" --format text)" = "openness::opaque" ]
+[ "$($PARSER query --input "$TEST_DIR/openness.json" --kind spec --format json | jq '.summary.total_functions')" = 5 ]
+$PARSER "$TEST_DIR/openness.rs" --show-kind --format detailed | tee "$TEST_DIR/openness.txt" | tail -1
+grep -q "^hidden \[closed spec fn\] @" "$TEST_DIR/openness.txt"
+grep -q "^Spec functions: 2 closed, 2 open, 1 uninterp$" "$TEST_DIR/openness.txt"
+echo
+
+echo "=== Test 37: Broadcast proof functions and groups ==="
+mkdir -p "$TEST_DIR/broadcast/src"
+cat > "$TEST_DIR/broadcast/src/lib.rs" << 'EOF'
+mod arith;
+mod groups;
+EOF
+cat > "$TEST_DIR/broadcast/src/arith.rs" << 'EOF'
+verus! {
+    pub broadcast proof fn lemma_add_comm(a: int, b: int)
+        ensures
+            #[trigger] (a + b) == b + a,
+    {
+    }
+
+    pub broadcast proof fn lemma_mul_comm(a: int, b: int)
+        ensures
+            #[trigger] (a * b) == b * a,
+    {
+    }
+
+    pub proof fn lemma_plain() {}
+}
+EOF
+cat > "$TEST_DIR/broadcast/src/groups.rs" << 'EOF'
+use crate::arith::lemma_mul_comm;
+
+verus! {
+    pub broadcast group group_arith {
+        crate::arith::lemma_add_comm,
+        lemma_mul_comm,
+    }
+
+    fn uses_nothing() {}
+}
+EOF
+$PARSER "$TEST_DIR/broadcast" --show-kind --format json > "$TEST_DIR/broadcast.json"
+jq -c '.functions[] | [.qualified_name, .kind, .group_members]' "$TEST_DIR/broadcast.json"
+[ "$(jq -c '.functions[] | select(.name == "lemma_add_comm") | .kind' "$TEST_DIR/broadcast.json")" = '"broadcast proof fn"' ]
+[ "$(jq -c '.functions[] | select(.name == "group_arith") | [.qualified_name, .kind, .group_members]' "$TEST_DIR/broadcast.json")" = '["groups::group_arith","broadcast group",["arith::lemma_add_comm","arith::lemma_mul_comm"]]' ]
+[ "$($PARSER query --input "$TEST_DIR/broadcast.json" --kind broadcast --format text | tr '\n' ' ')" = "arith::lemma_add_comm arith::lemma_mul_comm groups::group_arith " ]
+$PARSER "$TEST_DIR/broadcast" --show-kind --format detailed | sed -n '/^Broadcast groups:/,/^$/p' | tee "$TEST_DIR/broadcast.txt"
+[ "$(sed -n 3p "$TEST_DIR/broadcast.txt")" = "    arith::lemma_add_comm" ]
+[ "$($PARSER closure --function group_arith "$TEST_DIR/broadcast" --format text | tr '\n' ' ')" = "arith::lemma_add_comm arith::lemma_mul_comm groups::group_arith " ]
+echo
+
+echo "=== Test 38: broadcast use statements per module ==="
+cat > "$TEST_DIR/broadcast/src/users.rs" << 'EOF'
+use crate::groups::group_arith;
+
+verus! {
+    broadcast use group_arith;
+
+    proof fn lemma_uses_comm(a: int, b: int) {
+        broadcast use crate::arith::lemma_mul_comm;
+        assert(a * b == b * a);
+    }
+
+    fn exec_with_proof() {
+        proof {
+            broadcast use {crate::arith::lemma_add_comm, vstd::arithmetic::group_mul_basics};
+        }
+    }
+}
+EOF
+$PARSER "$TEST_DIR/broadcast" --format json | jq -c '.broadcast_uses' | tee "$TEST_DIR/uses.json"
+[ "$(jq -c '.users | map([.path, .line, .function])' "$TEST_DIR/uses.json")" = '[["groups::group_arith",4,null],["arith::lemma_mul_comm",7,"users::lemma_uses_comm"],["arith::lemma_add_comm",13,"users::exec_with_proof"],["vstd::arithmetic::group_mul_basics",13,"users::exec_with_proof"]]' ]
+$PARSER "$TEST_DIR/broadcast" --format json > "$TEST_DIR/broadcast_saved.json"
+[ "$($PARSER query --input "$TEST_DIR/broadcast_saved.json" --module arith --format json | jq -c '.broadcast_uses')" = 'null' ]
+$PARSER "$TEST_DIR/broadcast" --format detailed | sed -n '/^Broadcast uses:/,/^$/p' | tee "$TEST_DIR/uses.txt"
+grep -q "^    arith::lemma_mul_comm @ .*users.rs:7 in users::lemma_uses_comm$" "$TEST_DIR/uses.txt"
+echo
+
+echo "=== Test 39: Auditing external_body functions ==="
+cat > "$TEST_DIR/external_body.rs" << 'EOF'
+verus! {
+    #[verifier::external_body]
+    fn read_clock() -> u64 {
+        0
+    }
+
+    #[verifier(external_body)]
+    pub fn write_log(x: u64) {
+    }
+
+    struct Device;
+
+    impl Device {
+        #[verifier::external_body]
+        fn reset(&self) {}
+
+        fn status(&self) -> u64 { 0 }
+    }
+
+    trait Port {
+        #[verifier::external_body]
+        fn open() {}
+    }
+
+    fn verified() -> u64 { 1 }
+}
+EOF
+$PARSER "$TEST_DIR/external_body.rs" --only-trusted --format detailed | tee "$TEST_DIR/external_body.txt"
+[ "$(grep -c "(trusted: external_body)" "$TEST_DIR/external_body.txt")" = 4 ]
+grep -q "^write_log @ .*external_body.rs:7:9 in standalone (trusted: external_body)$" "$TEST_DIR/external_body.txt"
+grep -q "^Trusted functions: 4$" "$TEST_DIR/external_body.txt"
+[ "$($PARSER "$TEST_DIR/external_body.rs" --format json | jq -c '[.summary.trusted_functions, [.functions[] | select(.trusted != null) | .qualified_name]]')" = '[4,["external_body::read_clock","external_body::write_log","external_body::Device::reset","external_body::Port::open"]]' ]
+echo
+
+echo "=== Test 40: External items and external specifications ==="
+cat > "$TEST_DIR/external.rs" << 'EOF'
+verus! {
+    #[verifier::external]
+    fn debug_dump(x: u64) {
+        println!("{}", x);
+    }
+
+    #[verifier::external]
+    impl std::fmt::Debug for Point {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
+    }
+
+    #[verifier(external)]
+    mod benches {
+        fn bench_mul() {}
+    }
+
+    #[verifier::external_type_specification]
+    #[verifier::external_body]
+    pub struct ExDuration(core::time::Duration);
+
+    #[verifier::external_trait_specification]
+    pub trait ExNeg {
+        type ExternalTraitSpecificationFor: core::ops::Neg;
+
+        fn neg(self) -> Self;
+    }
+
+    fn verified(x: u64) -> u64 { x }
+}
+EOF
+$PARSER "$TEST_DIR/external.rs" --format json | jq -c '[.functions[] | [.qualified_name, .kind, .external_target]]' | tee "$TEST_DIR/external.json"
+[ "$(cat "$TEST_DIR/external.json")" = '[["external::debug_dump","external",null],["external::Point","external",null],["external::benches","external",null],["external::ExDuration","external_spec","core::time::Duration"],["external::ExNeg","external_spec","core::ops::Neg"],["external::verified",null,null]]' ]
+[ "$($PARSER "$TEST_DIR/external.rs" --format verus-args)" = "--verify-only-module external --verify-function verified" ]
+$PARSER "$TEST_DIR/external.rs" --show-kind --format detailed > "$TEST_DIR/external.txt"
+grep -q "^ExDuration \[external_spec\] (specifies core::time::Duration) @" "$TEST_DIR/external.txt"
+echo
+
+echo "=== Test 41: Opaque spec functions and their reveals ==="
+cat > "$TEST_DIR/opaque.rs" << 'EOF'
+verus! {
+    #[verifier::opaque]
+    pub open spec fn is_small(x: u64) -> bool { x < 10 }
+
+    #[verifier(opaque)]
+    pub closed spec fn never_used(x: u64) -> bool { x > 100 }
+
+    #[verifier::opaque]
+    pub open spec fn fib(n: nat) -> nat decreases n {
+        if n < 2 { n } else { fib((n - 1) as nat) + fib((n - 2) as nat) }
+    }
+
+    proof fn lemma_small()
+        ensures is_small(3)
+    {
+        reveal(is_small);
+    }
+
+    proof fn lemma_fib()
+        ensures fib(2) == 1
+    {
+        reveal_with_fuel(fib, 3);
+    }
+
+    fn check(x: u64) {
+        proof {
+            reveal(is_small);
+            hide(fib);
+        }
+    }
+}
+EOF
+$PARSER opaque-reveals "$TEST_DIR/opaque.rs" --format json > "$TEST_DIR/opaque.json"
+jq -c '[.opaque_functions[] | [.qualified_name, .revealed_by]]' "$TEST_DIR/opaque.json" | tee "$TEST_DIR/opaque.out"
+[ "$(cat "$TEST_DIR/opaque.out")" = '[["opaque::is_small",["opaque::check","opaque::lemma_small"]],["opaque::never_used",[]],["opaque::fib",["opaque::lemma_fib"]]]' ]
+[ "$(jq -c .summary "$TEST_DIR/opaque.json")" = '{"opaque_functions":3,"never_revealed":1}' ]
+$PARSER opaque-reveals "$TEST_DIR/opaque.rs" --format text > "$TEST_DIR/opaque.txt"
+grep -A1 "^opaque::never_used @" "$TEST_DIR/opaque.txt" | grep -q "never revealed"
+grep -q "1 never revealed" "$TEST_DIR/opaque.txt"
+$PARSER "$TEST_DIR/opaque.rs" --show-kind --show-calls --format detailed > "$TEST_DIR/opaque_detailed.txt"
+grep -q "^is_small \[open spec fn\].* (opaque)$" "$TEST_DIR/opaque_detailed.txt"
+grep -A1 "^lemma_fib " "$TEST_DIR/opaque_detailed.txt" | grep -q "    reveals opaque::fib"
+echo
+
+echo "=== Test 42: assume_specification declarations ==="
+mkdir -p "$TEST_DIR/assumed/src"
+cat > "$TEST_DIR/assumed/src/lib.rs" << 'EOF'
+mod util;
+mod std_specs;
+EOF
+cat > "$TEST_DIR/assumed/src/util.rs" << 'EOF'
+verus! {
+    #[verifier::external_body]
+    pub fn clamp(x: u64, hi: u64) -> u64 { if x > hi { hi } else { x } }
+}
+EOF
+cat > "$TEST_DIR/assumed/src/std_specs.rs" << 'EOF'
+use crate::util::clamp;
+use core::cmp::min;
+
+verus! {
+    pub assume_specification[ clamp ](x: u64, hi: u64) -> (r: u64)
+        ensures
+            r <= hi,
+            x <= hi ==> r == x;
+
+    pub assume_specification<T>[ core::option::Option::<T>::unwrap ](option: Option<T>) -> (t: T)
+        requires option.is_some(),
+        ensures t == option.unwrap();
+
+    pub assume_specification[ min ](a: u64, b: u64) -> u64
+        returns if a < b { a } else { b };
+
+    pub assume_specification<T: Clone>[ <Vec<T> as Clone>::clone ](v: &Vec<T>) -> Vec<T>;
+
+    fn uses_clamp() -> u64 { clamp(5, 3) }
+}
+EOF
+$PARSER "$TEST_DIR/assumed" --format json > "$TEST_DIR/assumed.json"
+jq -c '[.assumed_specifications[] | [.target, .module_path, .line]]' "$TEST_DIR/assumed.json" | tee "$TEST_DIR/assumed.out"
+[ "$(cat "$TEST_DIR/assumed.out")" = '[["util::clamp","std_specs",5],["core::option::Option::unwrap","std_specs",10],["core::cmp::min","std_specs",14],["<Vec<T> as Clone>::clone","std_specs",17]]' ]
+[ "$(jq -c '.assumed_specifications[0].ensures' "$TEST_DIR/assumed.json")" = '["r <= hi","x <= hi ==> r == x"]' ]
+[ "$(jq -r '.assumed_specifications[1].requires[0]' "$TEST_DIR/assumed.json")" = "option.is_some()" ]
+[ "$(jq -r '.assumed_specifications[2].returns' "$TEST_DIR/assumed.json")" = "if a < b { a } else { b }" ]
+[ "$($PARSER "$TEST_DIR/assumed" --only-assumed-specs --format json | jq -c '[(.functions | length), (.assumed_specifications | length)]')" = "[0,4]" ]
+$PARSER "$TEST_DIR/assumed" --only-assumed-specs --format text > "$TEST_DIR/assumed.txt"
+[ "$(grep -c . "$TEST_DIR/assumed.txt")" = 4 ]
+grep -qx "core::cmp::min" "$TEST_DIR/assumed.txt"
+$PARSER "$TEST_DIR/assumed" --format detailed > "$TEST_DIR/assumed_detailed.txt"
+grep -A2 "^util::clamp @ .*std_specs.rs:5$" "$TEST_DIR/assumed_detailed.txt" | grep -q "    ensures r <= hi"
+echo
+
+echo "=== Test 43: global declarations ==="
+cat > "$TEST_DIR/globals.rs" << 'EOF'
+verus! {
+    global size_of usize == 8;
+
+    struct Pair { a: u32, b: u32 }
+
+    global layout Pair is size == 8, align == 4;
+
+    global size_of u128 == 16;
+
+    fn word() -> usize { 8 }
+}
+EOF
+$PARSER "$TEST_DIR/globals.rs" --format json > "$TEST_DIR/globals.json"
+jq -c '[.globals[] | [.declaration, .line]]' "$TEST_DIR/globals.json" | tee "$TEST_DIR/globals.out"
+[ "$(cat "$TEST_DIR/globals.out")" = '[["global size_of usize == 8;",2],["global layout Pair is size == 8, align == 4;",6],["global size_of u128 == 16;",8]]' ]
+[ "$(jq -r '.functions[].qualified_name' "$TEST_DIR/globals.json")" = "globals::word" ]
+$PARSER "$TEST_DIR/globals.rs" --format detailed > "$TEST_DIR/globals.txt"
+grep -A1 "^Globals:" "$TEST_DIR/globals.txt" | grep -q "^global size_of usize == 8; @ .*globals.rs:2$"
+# Globals hold crate-wide, so module queries keep them
+[ "$($PARSER query --input "$TEST_DIR/globals.json" --module other | jq '.globals | length')" = 3 ]
+echo
+
+echo "=== Test 44: Proof blocks in exec functions ==="
+cat > "$TEST_DIR/proof_blocks.rs" << 'EOF'
+verus! {
+    fn sum(v: &Vec<u64>) -> (s: u64)
+        requires v.len() < 10,
+    {
+        let mut s = 0;
+        let mut i = 0;
+        proof {
+            assert(s == 0);
+        }
+        while i < v.len()
+            invariant i <= v.len(),
+        {
+            proof {
+                assert(i < v.len());
+                proof { assert(true); }
+            }
+            i += 1;
+        }
+        match s {
+            0 => { proof { assert(s == 0); } }
+            _ => {}
+        }
+        s
+    }
+
+    fn plain() {}
+
+    proof fn lemma() {
+        assert(true);
+    }
+}
+EOF
+$PARSER "$TEST_DIR/proof_blocks.rs" --show-specs --format json > "$TEST_DIR/proof_blocks.json"
+jq -c '[.functions[] | [.name, .proof_blocks, .proof_lines]]' "$TEST_DIR/proof_blocks.json" | tee "$TEST_DIR/proof_blocks.out"
+# The nested proof block counts as part of the one inside the loop
+[ "$(cat "$TEST_DIR/proof_blocks.out")" = '[["sum",3,8],["plain",0,0],["lemma",null,null]]' ]
+$PARSER "$TEST_DIR/proof_blocks.rs" --show-specs --format detailed > "$TEST_DIR/proof_blocks.txt"
+grep -q "^    proof blocks: 3 spanning 8 lines$" "$TEST_DIR/proof_blocks.txt"
+[ "$(grep -c "proof blocks:" "$TEST_DIR/proof_blocks.txt")" = 1 ]
+echo
+
+echo "=== Test 45: assert counts ==="
+cat > "$TEST_DIR/asserts.rs" << 'EOF'
+verus! {
+    proof fn lemma_heavy(x: int)
+        ensures x + 0 == x,
+    {
+        assert(x + 0 == x);
+        assert(x * 1 == x) by (nonlinear_arith);
+        assert forall|i: int| i + 0 == i by {
+            assert(i == i);
+        }
+    }
+
+    fn exec_with_closure(v: u64) {
+        let f = |a: u64| {
+            proof { assert(true); }
+            a
+        };
+        proof {
+            assert(v == v);
+        }
+        fn nested() {
+            proof { assert(1 == 1); }
+        }
+    }
+
+    fn none() {}
+}
+EOF
+$PARSER "$TEST_DIR/asserts.rs" --show-specs --format json > "$TEST_DIR/asserts.json"
+jq -c '[.functions[] | [.name, .assert_count]]' "$TEST_DIR/asserts.json" | tee "$TEST_DIR/asserts.out"
+# The closure's assert belongs to exec_with_closure, the nested function's to nested
+[ "$(cat "$TEST_DIR/asserts.out")" = '[["lemma_heavy",4],["exec_with_closure",2],["nested",1],["none",0]]' ]
+[ "$($PARSER "$TEST_DIR/asserts.rs" --min-asserts 2 --format json | jq -c '[.functions[].name]')" = '["lemma_heavy","exec_with_closure"]' ]
+$PARSER "$TEST_DIR/asserts.rs" --min-asserts 4 --format detailed > "$TEST_DIR/asserts.txt"
+grep -A1 "^lemma_heavy " "$TEST_DIR/asserts.txt" | grep -q "^    asserts: 4$"
+grep -q "^Summary: 1 functions" "$TEST_DIR/asserts.txt"
+echo
+
+echo "=== Test 46: assume/admit proof debt ==="
+cat > "$TEST_DIR/debt.rs" << 'EOF'
+verus! {
+    proof fn lemma_todo(x: int)
+        ensures x * x >= 0,
+    {
+        assert(x * x >= 0) by {
+            assume(x * x >= 0);
+        }
+    }
+
+    fn exec_debt(v: u64) -> (r: u64)
+        ensures r == v,
+    {
+        proof {
+            assume(v < 100);
+            admit();
+        }
+        v
+    }
+
+    proof fn lemma_done() { assert(true); }
+}
+EOF
+$PARSER "$TEST_DIR/debt.rs" --show-specs --format json > "$TEST_DIR/debt.json"
+jq -c '[.functions[] | [.name, .assume_count, .admit_count]]' "$TEST_DIR/debt.json" | tee "$TEST_DIR/debt.out"
+[ "$(cat "$TEST_DIR/debt.out")" = '[["lemma_todo",1,0],["exec_debt",1,1],["lemma_done",0,0]]' ]
+if $PARSER "$TEST_DIR/debt.rs" --fail-if-assumes --format text > /dev/null 2> "$TEST_DIR/debt.err"; then
+    echo "--fail-if-assumes should fail with 3 occurrences"
+    exit 1
+fi
+grep -q "^Error: 3 assume/admit occurrences, more than the 0 allowed$" "$TEST_DIR/debt.err"
+grep -q "^  debt::exec_debt @ .*debt.rs:10: 1 assume, 1 admit$" "$TEST_DIR/debt.err"
+$PARSER "$TEST_DIR/debt.rs" --fail-if-assumes 3 --format text > /dev/null
+echo
+
+echo "=== Test 47: Quantifier and trigger statistics ==="
+cat > "$TEST_DIR/quantifiers.rs" << 'EOF'
+verus! {
+    spec fn is_sorted(s: Seq<int>) -> bool {
+        forall|i: int, j: int| 0 <= i < j < s.len() ==> #[trigger] s[i] <= #[trigger] s[j]
+    }
+
+    spec fn has_pair(s: Seq<int>) -> bool {
+        exists|i: int| 0 <= i < s.len() && forall|j: int| #![trigger s[j]] 0 <= j < s.len() ==> s[i] >= s[j]
+    }
+
+    proof fn lemma_sorted(s: Seq<int>)
+        requires is_sorted(s),
+        ensures forall|i: int| #![auto] 0 <= i < s.len() ==> s[i] == s[i],
+    {
+        assert forall|i: int| 0 <= i < s.len() implies s[i] == s[i] by {
+            let k = choose|k: int| k == i;
+        }
+    }
+
+    fn plain() {}
+}
+EOF
+$PARSER "$TEST_DIR/quantifiers.rs" --show-specs --format json > "$TEST_DIR/quantifiers.json"
+jq -c '[.functions[] | [.name, .quantifier_count, .trigger_count]]' "$TEST_DIR/quantifiers.json" | tee "$TEST_DIR/quantifiers.out"
+# The nested forall counts once, #![auto] is not an explicit trigger
+[ "$(cat "$TEST_DIR/quantifiers.out")" = '[["is_sorted",1,2],["has_pair",2,1],["lemma_sorted",3,0],["plain",0,0]]' ]
+[ "$($PARSER quantifiers "$TEST_DIR/quantifiers.rs" --format json | jq -c '[.functions[].qualified_name]')" = '["quantifiers::lemma_sorted","quantifiers::has_pair","quantifiers::is_sorted"]' ]
+$PARSER quantifiers "$TEST_DIR/quantifiers.rs" --format text > "$TEST_DIR/quantifiers.txt"
+head -1 "$TEST_DIR/quantifiers.txt" | grep -q "^quantifiers::lemma_sorted @ .*: 3 quantifiers, 0 triggers$"
+echo
+
+echo "=== Test 48: Ghost and tracked parameters ==="
+cat > "$TEST_DIR/ghost.rs" << 'EOF'
+verus! {
+    fn split(Tracked(perm): Tracked<PointsTo<u64>>, len: Ghost<nat>, x: u64) -> (r: Tracked<PointsTo<u64>>) {
+        Tracked(perm)
+    }
+
+    fn borrow(p: &Tracked<PointsTo<u64>>, g: &mut Ghost<int>) {}
+
+    proof fn lemma_tracked(tracked t: Token, n: nat) -> (tracked r: Token) {
+        t
+    }
+
+    fn view_len(v: &Vec<u64>) -> (n: Ghost<nat>) {
+        Ghost(v@.len())
+    }
+
+    fn plain(x: u64) -> u64 { x }
+}
+EOF
+$PARSER "$TEST_DIR/ghost.rs" --format json > "$TEST_DIR/ghost.json"
+jq -c '[.functions[] | [.name, .ghost_params, .tracked_params, .returns_ghost]]' "$TEST_DIR/ghost.json" | tee "$TEST_DIR/ghost.out"
+[ "$(cat "$TEST_DIR/ghost.out")" = '[["split",["len"],["Tracked(perm)"],true],["borrow",["g"],["p"],null],["lemma_tracked",null,["t"],true],["view_len",null,null,true],["plain",null,null,null]]' ]
+[ "$($PARSER "$TEST_DIR/ghost.rs" --has-tracked --format text | tr '\n' ' ')" = "ghost::borrow ghost::lemma_tracked ghost::split " ]
+$PARSER "$TEST_DIR/ghost.rs" --format detailed > "$TEST_DIR/ghost.txt"
+grep -A2 "^split " "$TEST_DIR/ghost.txt" | grep -q "^    tracked params: Tracked(perm)$"
+echo
+
+echo "=== Test 49: Return types ==="
+cat > "$TEST_DIR/return_types.rs" << 'EOF'
+verus! {
+    fn add(a: u64, b: u64) -> (result: u64)
+        requires a + b < 100,
+        ensures result == a + b,
+    {
+        a + b
+    }
+
+    fn unit_explicit() -> () {}
+
+    fn unit_implicit() {}
+
+    fn pair(x: u32) -> (u32, Vec<u64>) { (x, Vec::new()) }
+
+    proof fn lemma_tok(tracked t: Token) -> (tracked r: Token) { t }
+
+    spec fn double(x: int) -> int { 2 * x }
+}
+EOF
+$PARSER "$TEST_DIR/return_types.rs" --show-signature --format json > "$TEST_DIR/return_types.json"
+jq -c '[.functions[] | [.name, .return_type]]' "$TEST_DIR/return_types.json" | tee "$TEST_DIR/return_types.out"
+# Named returns keep their binder; unit is omitted however it is written
+[ "$(cat "$TEST_DIR/return_types.out")" = '[["add","(result: u64)"],["unit_explicit",null],["unit_implicit",null],["pair","(u32, Vec<u64>)"],["lemma_tok","(tracked r: Token)"],["double","int"]]' ]
+[ "$($PARSER "$TEST_DIR/return_types.rs" --show-kind --format json | jq -r '.functions[0].return_type')" = "(result: u64)" ]
+[ "$($PARSER "$TEST_DIR/return_types.rs" --format json | jq '[.functions[] | select(has("return_type"))] | length')" = 0 ]
+echo
+
+echo "=== Test 50: Signature text ==="
+cat > "$TEST_DIR/signatures.rs" << 'EOF'
+verus! {
+    pub open spec fn double(x: int,) -> int { 2 * x }
+
+    pub const fn width() -> usize { 64 }
+
+    fn pow<const N: usize, T: Copy>(base: T, exp: u32) -> (r: T)
+        where T: Clone,
+        requires
+            exp < 64,
+            N > 0,
+        ensures r == r,
+    {
+        base
+    }
+
+    pub broadcast proof fn lemma_pos(x: int)
+        requires x > 0,
+        ensures #[trigger] double(x) > x,
+        decreases x,
+    {}
+
+    impl Point {
+        pub fn scale(&mut self, k: u64) -> u64
+            no_unwind
+        { k }
+
+        fn consume(self) {}
+    }
+}
+EOF
+$PARSER "$TEST_DIR/signatures.rs" --show-signature --format json | jq -r '.functions[].signature' > "$TEST_DIR/signatures.out"
+cat "$TEST_DIR/signatures.out"
+cat > "$TEST_DIR/signatures.expected" << 'EOF'
+open spec fn double(x: int) -> int
+const fn width() -> usize
+fn pow<const N: usize, T: Copy>(base: T, exp: u32) -> (r: T) where T: Clone requires exp < 64, N > 0 ensures r == r
+broadcast proof fn lemma_pos(x: int) requires x > 0 ensures #[trigger] double(x) > x decreases x
+fn scale(&mut self, k: u64) -> u64 no_unwind
+fn consume(self)
+EOF
+diff "$TEST_DIR/signatures.expected" "$TEST_DIR/signatures.out"
+[ "$($PARSER "$TEST_DIR/signatures.rs" --show-signature --elide-specs --format json | jq -r '.functions[2].signature')" = "fn pow<const N: usize, T: Copy>(base: T, exp: u32) -> (r: T) where T: Clone" ]
+$PARSER "$TEST_DIR/signatures.rs" --show-signature --format detailed > "$TEST_DIR/signatures.txt"
+grep -A1 "^consume " "$TEST_DIR/signatures.txt" | grep -q "^    fn consume(self)$"
+echo
+
+echo "=== Test 51: Generic parameters ==="
+cat > "$TEST_DIR/generic.rs" << 'EOF'
+fn pow<const N: usize, T: Copy>(base: T) -> T where T: Clone { base }
+
+fn first<'a>(xs: &'a [u8]) -> &'a u8 { &xs[0] }
+
+fn plain(x: u8) -> u8 { x }
+
+impl<T> Wrapper<T> {
+    fn get(&self) -> u8 { 0 }
+}
+
+impl Point {
+    fn norm(&self) -> u64 { 0 }
+}
+EOF
+$PARSER "$TEST_DIR/generic.rs" --include-methods --format json > "$TEST_DIR/generic.json"
+jq -c '.functions[] | [.name, .generics, .where_clause, .is_generic]' "$TEST_DIR/generic.json" | tee "$TEST_DIR/generic.out"
+cat > "$TEST_DIR/generic.expected" << 'EOF'
+["pow","<const N: usize, T: Copy>","T: Clone",true]
+["first","<'a>",null,false]
+["plain",null,null,false]
+["get",null,null,true]
+["norm",null,null,false]
+EOF
+diff "$TEST_DIR/generic.expected" "$TEST_DIR/generic.out"
+[ "$($PARSER "$TEST_DIR/generic.rs" --include-methods --only-generic --format text | tr '\n' ' ')" = "generic::Wrapper::get generic::pow " ]
+[ "$($PARSER "$TEST_DIR/generic.rs" --include-methods --only-monomorphic --format text | tr '\n' ' ')" = "generic::Point::norm generic::first generic::plain " ]
+if $PARSER "$TEST_DIR/generic.rs" --only-generic --only-monomorphic > /dev/null 2>&1; then exit 1; fi
+echo
+
+echo "=== Test 52: Function attributes ==="
+cat > "$TEST_DIR/attributes.rs" << 'EOF'
+#[inline]
+/// Not an attribute
+#[cfg(feature = "simd")]
+fn outside() {}
+
+verus! {
+    #[verifier::rlimit(20)]
+    #[verifier(external_body)]
+    #[my_macro::instrument]
+    pub fn inside() {}
+
+    fn bare() {}
+}
+EOF
+$PARSER "$TEST_DIR/attributes.rs" --show-attributes --format json > "$TEST_DIR/attributes.json"
+jq -c '.functions[] | [.name, .attributes]' "$TEST_DIR/attributes.json" | tee "$TEST_DIR/attributes.out"
+cat > "$TEST_DIR/attributes.expected" << 'EOF'
+["outside",["#[inline]","#[cfg(feature = \"simd\")]"]]
+["inside",["#[verifier::rlimit(20)]","#[verifier(external_body)]","#[my_macro::instrument]"]]
+["bare",null]
+EOF
+diff "$TEST_DIR/attributes.expected" "$TEST_DIR/attributes.out"
+[ "$($PARSER "$TEST_DIR/attributes.rs" --format json | jq '[.functions[] | select(has("attributes"))] | length')" = "0" ]
+$PARSER "$TEST_DIR/attributes.rs" --show-attributes --format detailed > "$TEST_DIR/attributes.txt"
+grep -A1 "^outside " "$TEST_DIR/attributes.txt" | grep -q "^    #\[inline\]$"
+echo
+
+echo "=== Test 53: Doc comments ==="
+cat > "$TEST_DIR/docs.rs" << 'EOF'
+/// Adds two numbers.
+///
+///     indented example
+fn outside() {}
+
+verus! {
+    /**
+     * Block comment
+     *   nested
+     */
+    pub fn inside() {}
+
+    /** Block doc
+     *  second line
+     *    indented */
+    fn inline_block() {}
+
+    #[doc = "Attribute form"]
+    fn attr_form() {}
+
+    trait Tr {
+        /// Trait method
+        fn method(&self);
+    }
+
+    fn undocumented() {}
+}
+EOF
+$PARSER "$TEST_DIR/docs.rs" --show-docs --include-methods --format json > "$TEST_DIR/docs.json"
+jq -c '.functions[] | [.name, .doc]' "$TEST_DIR/docs.json" | tee "$TEST_DIR/docs.out"
+cat > "$TEST_DIR/docs.expected" << 'EOF'
+["outside","Adds two numbers.\n\n    indented example"]
+["inside","Block comment\n  nested"]
+["inline_block","Block doc\nsecond line\n  indented"]
+["attr_form","Attribute form"]
+["method","Trait method"]
+["undocumented",null]
+EOF
+diff "$TEST_DIR/docs.expected" "$TEST_DIR/docs.out"
+[ "$($PARSER "$TEST_DIR/docs.rs" --format json | jq '[.functions[] | select(has("doc"))] | length')" = "0" ]
+$PARSER "$TEST_DIR/docs.rs" --show-docs --format detailed > "$TEST_DIR/docs.txt"
+grep -A3 "^outside " "$TEST_DIR/docs.txt" | grep -q "^    ///$"
+echo
+
+echo "=== Test 54: Unsafe functions ==="
+cat > "$TEST_DIR/unsafety.rs" << 'EOF'
+unsafe fn raw_read(p: *const u8) -> u8 { *p }
+
+fn safe() {}
+
+impl Buffer {
+    pub unsafe fn get_unchecked(&self, i: usize) -> u8 { 0 }
+}
+
+unsafe trait Zeroable {
+    unsafe fn zeroed() -> Self;
+}
+
+verus! {
+    pub unsafe fn inside_verus() {}
+}
+EOF
+$PARSER "$TEST_DIR/unsafety.rs" --include-methods --format json > "$TEST_DIR/unsafety.json"
+[ "$(jq -c '[.functions[] | select(.is_unsafe) | .name]' "$TEST_DIR/unsafety.json")" = '["raw_read","get_unchecked","zeroed","inside_verus"]' ]
+[ "$(jq '.summary.unsafe_functions' "$TEST_DIR/unsafety.json")" = "4" ]
+[ "$($PARSER "$TEST_DIR/unsafety.rs" --include-methods --only-unsafe --format json | jq '.functions | length')" = "4" ]
+$PARSER "$TEST_DIR/unsafety.rs" --include-methods --show-kind --format detailed > "$TEST_DIR/unsafety.txt"
+cat "$TEST_DIR/unsafety.txt"
+grep -q "^raw_read \[fn\] \[unsafe\] @" "$TEST_DIR/unsafety.txt"
+grep -q "^safe \[fn\] @" "$TEST_DIR/unsafety.txt"
+grep -q "^Unsafe functions: 4$" "$TEST_DIR/unsafety.txt"
+echo
+
+echo "=== Test 55: Async functions ==="
+cat > "$TEST_DIR/asyncs.rs" << 'EOF'
+pub async fn fetch(url: u64) -> u64 { url }
+
+fn sync_helper() {}
+
+trait Service {
+    async fn call(&self) -> u8;
+    fn ready(&self) -> bool;
+}
+
+impl Client {
+    pub const fn new() -> Self { Client }
+    async fn send(&self) {}
+}
+
+verus! {
+    pub async exec fn verified_fetch() {}
+}
+EOF
+$PARSER "$TEST_DIR/asyncs.rs" --include-verus-constructs --include-methods --show-kind --format json > "$TEST_DIR/asyncs.json"
+jq -c '.functions[] | [.name, .kind, .is_async]' "$TEST_DIR/asyncs.json" | tee "$TEST_DIR/asyncs.out"
+cat > "$TEST_DIR/asyncs.expected" << 'EOF'
+["fetch","async fn",true]
+["sync_helper","fn",false]
+["call","async fn",true]
+["ready","fn",false]
+["new","const fn",false]
+["send","async fn",true]
+["verified_fetch","exec async fn",true]
+EOF
+diff "$TEST_DIR/asyncs.expected" "$TEST_DIR/asyncs.out"
+[ "$(jq '.summary.async_functions' "$TEST_DIR/asyncs.json")" = "4" ]
+[ "$($PARSER "$TEST_DIR/asyncs.rs" --include-methods --exclude-async --format text | tr '\n' ' ')" = "asyncs::Client::new asyncs::Service::ready asyncs::sync_helper " ]
+$PARSER "$TEST_DIR/asyncs.rs" --include-methods --format detailed > "$TEST_DIR/asyncs.txt"
+grep -q "^Async functions: 4$" "$TEST_DIR/asyncs.txt"
+echo
+
+echo "=== Test 56: Mutating functions ==="
+cat > "$TEST_DIR/mutation.rs" << 'EOF'
+verus! {
+    fn push(v: &mut Vec<u64>, x: u64)
+        ensures v@ == old(v)@.push(x),
+    {}
+
+    fn read(v: &Vec<u64>) -> u64 { 0 }
+
+    proof fn take(tracked perm: Tracked<&mut Perm>) {}
+
+    fn reset(c: &Counter) -> (r: Counter)
+        ensures r == old(c).zeroed(),
+    { *c }
+}
+
+impl Counter {
+    fn bump(&mut self) {}
+    fn get(&self) -> u64 { 0 }
+    fn into_inner(mut self) -> u64 { 0 }
+}
+EOF
+$PARSER "$TEST_DIR/mutation.rs" --include-verus-constructs --include-methods --format json > "$TEST_DIR/mutation.json"
+jq -c '.functions[] | [.name, .takes_mut, .uses_old]' "$TEST_DIR/mutation.json" | tee "$TEST_DIR/mutation.out"
+cat > "$TEST_DIR/mutation.expected" << 'EOF'
+["push",true,true]
+["read",false,false]
+["take",true,false]
+["reset",false,true]
+["bump",true,false]
+["get",false,false]
+["into_inner",false,false]
+EOF
+diff "$TEST_DIR/mutation.expected" "$TEST_DIR/mutation.out"
+[ "$($PARSER "$TEST_DIR/mutation.rs" --include-verus-constructs --include-methods --only-mutating --format text | tr '\n' ' ')" = "mutation::Counter::bump mutation::push mutation::reset mutation::take " ]
+echo
+
+echo "=== Test 57: Prover annotations ==="
+cat > "$TEST_DIR/provers.rs" << 'EOF'
+verus! {
+    proof fn lemma_mul(x: int, y: int) by (nonlinear_arith)
+        requires x > 0, y > 0,
+        ensures x * y > 0,
+    {}
+
+    fn shifts(x: u64) {
+        assert(x & 0 == 0) by (bit_vector);
+        proof {
+            assert(x * x >= 0) by (nonlinear_arith);
+            proof {
+                assert(x * 2 == x + x) by (nonlinear_arith);
+            }
+        }
+        assert(1 + 1 == 2) by (compute);
+        assert(x >= 0) by {
+            assert(x * 3 >= 0) by (nonlinear_arith);
+        }
+    }
+
+    fn plain() {}
+}
+EOF
+$PARSER "$TEST_DIR/provers.rs" --show-specs --format json > "$TEST_DIR/provers.json"
+jq -c '.functions[] | [.name, .provers]' "$TEST_DIR/provers.json" | tee "$TEST_DIR/provers.out"
+cat > "$TEST_DIR/provers.expected" << 'EOF'
+["lemma_mul",{"nonlinear_arith":1}]
+["shifts",{"bit_vector":1,"compute":1,"nonlinear_arith":3}]
+["plain",{}]
+EOF
+diff "$TEST_DIR/provers.expected" "$TEST_DIR/provers.out"
+$PARSER nonlinear-arith "$TEST_DIR/provers.rs" > "$TEST_DIR/nonlinear.json"
+[ "$(jq -c '[.functions[] | [.verify_function_arg, .nonlinear_arith]]' "$TEST_DIR/nonlinear.json")" = '[["shifts",3],["lemma_mul",1]]' ]
+$PARSER nonlinear-arith "$TEST_DIR/provers.rs" --format text > "$TEST_DIR/nonlinear.txt"
+grep -q "^Summary: 2 functions using nonlinear arithmetic$" "$TEST_DIR/nonlinear.txt"
+$PARSER "$TEST_DIR/provers.rs" --show-specs --format detailed > "$TEST_DIR/provers.txt"
+grep -q "^    provers: 1 bit_vector, 1 compute, 3 nonlinear_arith$" "$TEST_DIR/provers.txt"
+echo
+
+echo "=== Test 58: Reveal statements and fuel ==="
+cat > "$TEST_DIR/fuel.rs" << 'EOF'
+verus! {
+    #[verifier::opaque]
+    spec fn pow2(n: nat) -> nat { if n == 0 { 1 } else { 2 * pow2((n - 1) as nat) } }
+
+    proof fn lemma_small() {
+        reveal(pow2);
+        reveal_with_fuel(pow2, 3);
+        proof {
+            reveal_with_fuel(pow2, 10);
+        }
+        hide(pow2);
+    }
+
+    proof fn lemma_once() {
+        reveal(pow2);
+    }
+
+    fn plain() {}
+}
+EOF
+$PARSER "$TEST_DIR/fuel.rs" --show-specs --format json > "$TEST_DIR/fuel.json"
+jq -c '.functions[] | [.name, .reveal_count, .max_fuel]' "$TEST_DIR/fuel.json" | tee "$TEST_DIR/fuel.out"
+cat > "$TEST_DIR/fuel.expected" << 'EOF'
+["pow2",0,null]
+["lemma_small",3,10]
+["lemma_once",1,null]
+["plain",0,null]
+EOF
+diff "$TEST_DIR/fuel.expected" "$TEST_DIR/fuel.out"
+[ "$($PARSER "$TEST_DIR/fuel.rs" --min-fuel 5 --format text)" = "fuel::lemma_small" ]
+[ "$($PARSER "$TEST_DIR/fuel.rs" --min-fuel 11 --format json | jq '.functions | length')" = "0" ]
+$PARSER "$TEST_DIR/fuel.rs" --show-specs --format detailed > "$TEST_DIR/fuel.txt"
+grep -q "^    reveal statements: 3 (fuel up to 10)$" "$TEST_DIR/fuel.txt"
+echo
+
+echo "=== Test 59: Resource limits ==="
+cat > "$TEST_DIR/rlimits.rs" << 'EOF'
+verus! {
+    #[verifier::rlimit(20)]
+    proof fn lemma_big() {}
+
+    #[verifier::rlimit(infinity)]
+    #[verifier::spinoff_prover]
+    proof fn lemma_huge() {}
+
+    #[verifier(external_body, rlimit(5))]
+    fn small() {}
+
+    fn unlimited() {}
+}
+
+impl Point {
+    #[verifier::spinoff_prover]
+    #[verifier::rlimit(40)]
+    fn method(&self) {}
+}
+EOF
+$PARSER "$TEST_DIR/rlimits.rs" --format json > "$TEST_DIR/rlimits.json"
+jq -c '.functions[] | [.name, .rlimit, .spinoff_prover]' "$TEST_DIR/rlimits.json" | tee "$TEST_DIR/rlimits.out"
+cat > "$TEST_DIR/rlimits.expected" << 'EOF'
+["lemma_big","20",null]
+["lemma_huge","infinity",true]
+["small","5",null]
+["unlimited",null,null]
+["method","40",true]
+EOF
+diff "$TEST_DIR/rlimits.expected" "$TEST_DIR/rlimits.out"
+$PARSER rlimits "$TEST_DIR/rlimits.rs" > "$TEST_DIR/rlimits-report.json"
+[ "$(jq -c '[.functions[] | .rlimit]' "$TEST_DIR/rlimits-report.json")" = '["infinity","40","20"]' ]
+[ "$($PARSER rlimits "$TEST_DIR/rlimits.rs" --above 30 | jq '.functions | length')" = "2" ]
+$PARSER rlimits "$TEST_DIR/rlimits.rs" --format text > "$TEST_DIR/rlimits.txt"
+grep -q "^rlimits::Point::method @ .*: rlimit 40 (spinoff_prover)$" "$TEST_DIR/rlimits.txt"
+grep -q "^Summary: 3 functions with an rlimit above 10$" "$TEST_DIR/rlimits.txt"
+echo
+
+echo "=== Test 60: calc! invocations ==="
+cat > "$TEST_DIR/calcs.rs" << 'EOF'
+verus! {
+    proof fn lemma_calc(a: int, b: int) {
+        calc! {
+            (==)
+            a + b; {
+                calc! { (==) a; { } a; }
+            }
+            b + a;
+        }
+        assert(a * b == b * a) by {
+            vstd::calc! {
+                (==)
+                a * b;
+                {}
+                b * a;
+            }
+        }
+    }
+
+    proof fn none() {}
+}
+EOF
+$PARSER "$TEST_DIR/calcs.rs" --show-specs --format json > "$TEST_DIR/calcs.json"
+jq -c '.functions[] | [.name, .calc_count, .calc_lines]' "$TEST_DIR/calcs.json" | tee "$TEST_DIR/calcs.out"
+cat > "$TEST_DIR/calcs.expected" << 'EOF'
+["lemma_calc",3,[3,6,11]]
+["none",0,null]
+EOF
+diff "$TEST_DIR/calcs.expected" "$TEST_DIR/calcs.out"
+$PARSER "$TEST_DIR/calcs.rs" --show-specs --format detailed > "$TEST_DIR/calcs.txt"
+grep -q "^    calc!: 3 (lines 3, 6, 11)$" "$TEST_DIR/calcs.txt"
+echo
+
+echo "=== Test 61: assert-by sub-proofs ==="
+cat > "$TEST_DIR/byblocks.rs" << 'EOF'
+verus! {
+    proof fn lemma_structured(a: int, b: int)
+        ensures a + b == b + a,
+    {
+        assert(a + b == b + a) by {
+            assert(a + 0 == a) by {
+                assert(0 + a == a);
+            }
+        }
+        assert forall|x: int| x > a implies x + b > a + b by {
+            assert(b == b);
+        }
+        assert(a * a >= 0) by (nonlinear_arith);
+        assert(a == a);
+    }
+
+    proof fn flat() {
+        assert(true);
+    }
+}
+EOF
+$PARSER "$TEST_DIR/byblocks.rs" --show-specs --format json > "$TEST_DIR/byblocks.json"
+jq -c '.functions[] | [.name, .by_blocks, .by_block_lines, .by_asserts]' "$TEST_DIR/byblocks.json" | tee "$TEST_DIR/byblocks.out"
+cat > "$TEST_DIR/byblocks.expected" << 'EOF'
+["lemma_structured",3,8,null]
+["flat",0,0,null]
+EOF
+diff "$TEST_DIR/byblocks.expected" "$TEST_DIR/byblocks.out"
+$PARSER "$TEST_DIR/byblocks.rs" --show-by-asserts --format json > "$TEST_DIR/byasserts.json"
+[ "$(jq -c '.functions[0].by_asserts' "$TEST_DIR/byasserts.json")" = '["a + b == b + a","a + 0 == a","forall|x: int| x > a implies x + b > a + b"]' ]
+$PARSER "$TEST_DIR/byblocks.rs" --show-specs --format detailed > "$TEST_DIR/byblocks.txt"
+grep -q "^    by blocks: 3 spanning 8 lines$" "$TEST_DIR/byblocks.txt"
+echo
+
+echo "=== Test 62: Type definitions ==="
+cat > "$TEST_DIR/types.rs" << 'EOF'
+pub struct Plain { a: u64, b: u64 }
+
+verus! {
+    pub ghost struct Model { pub seq: Seq<int> }
+
+    pub tracked struct Token { id: nat }
+
+    pub struct Cell { value: u64, perm: Tracked<PointsTo>, ghost_len: Ghost<nat> }
+
+    pub enum Shape { Circle(u64), Rect { w: u64, h: Ghost<u64> }, Empty }
+
+    mod inner {
+        struct Pair(u8, u8);
+    }
+}
+
+union Bits { f: f32, u: u32 }
+
+fn uses() {}
+EOF
+$PARSER "$TEST_DIR/types.rs" --include-types > "$TEST_DIR/types.json"
+jq -c '.types[] | [.qualified_name, .kind, .mode, .field_count, .variant_count, .ghost_fields]' "$TEST_DIR/types.json" | tee "$TEST_DIR/types.out"
+cat > "$TEST_DIR/types.expected" << 'EOF'
+["types::Plain","struct","exec",2,null,null]
+["types::Model","struct","ghost",1,null,null]
+["types::Token","struct","tracked",1,null,null]
+["types::Cell","struct","exec",3,null,["perm","ghost_len"]]
+["types::Shape","enum","exec",3,3,["Rect::h"]]
+["types::inner::Pair","struct","exec",2,null,null]
+["types::Bits","union","exec",2,null,null]
+EOF
+diff "$TEST_DIR/types.expected" "$TEST_DIR/types.out"
+[ "$(jq '.summary.total_types' "$TEST_DIR/types.json")" = "7" ]
+[ "$($PARSER "$TEST_DIR/types.rs" | jq 'has("types")')" = "false" ]
+$PARSER query --input "$TEST_DIR/types.json" --module types::inner > "$TEST_DIR/types-inner.json"
+[ "$(jq -c '[.types[].name]' "$TEST_DIR/types-inner.json")" = '["Pair"]' ]
+$PARSER "$TEST_DIR/types.rs" --include-types --format detailed > "$TEST_DIR/types.txt"
+grep -q "^types::Shape \[exec enum\] @ .* (3 variants, 3 fields)$" "$TEST_DIR/types.txt"
+echo
+
+echo "=== Test 63: Trait definitions ==="
+cat > "$TEST_DIR/traits.rs" << 'EOF'
+trait Plain {
+    fn required(&self) -> u8;
+    fn provided(&self) -> u8 { 0 }
+}
+
+verus! {
+    pub trait Specified {
+        spec fn view(&self) -> int;
+
+        fn get(&self) -> (r: u64)
+            requires self.view() > 0,
+            ensures r == self.view();
+
+        fn reset(&mut self)
+            ensures self.view() == 0,
+        {
+            assume(false);
+        }
+
+        type Item;
+    }
+
+    mod inner {
+        trait Empty {}
+    }
+}
+EOF
+$PARSER "$TEST_DIR/traits.rs" --include-traits > "$TEST_DIR/traits.json"
+jq -c '.traits[] | [.qualified_name, [.methods[] | [.name, .has_default, .spec_clauses]]]' "$TEST_DIR/traits.json" | tee "$TEST_DIR/traits.out"
+cat > "$TEST_DIR/traits.expected" << 'EOF'
+["traits::Plain",[["required",false,null],["provided",true,null]]]
+["traits::Specified",[["view",false,null],["get",false,["requires","ensures"]],["reset",true,["ensures"]]]]
+["traits::inner::Empty",[]]
+EOF
+diff "$TEST_DIR/traits.expected" "$TEST_DIR/traits.out"
+[ "$(jq -c '[.functions[] | select(.impl_target.trait == "Specified") | .name]' "$TEST_DIR/traits.json")" = '["view","get","reset"]' ]
+[ "$($PARSER "$TEST_DIR/traits.rs" | jq 'has("traits")')" = "false" ]
+$PARSER "$TEST_DIR/traits.rs" --include-traits --format detailed > "$TEST_DIR/traits.txt"
+grep -q "^    reset (default) \[ensures\]$" "$TEST_DIR/traits.txt"
+echo
+
+echo "=== Test 64: Structured context ==="
+cat > "$TEST_DIR/context.rs" << 'EOF'
+impl FieldElement51 {
+    fn square(&self) -> Self { *self }
+}
+
+impl Mul for FieldElement51 {
+    fn mul(self, rhs: Self) -> Self { self }
+}
+
+impl<'a> core::ops::Mul<&'a FieldElement51> for &'a FieldElement51 {
+    fn mul(self, rhs: &'a FieldElement51) -> FieldElement51 { *self }
+}
+
+trait Tr<T> {
+    fn f(&self);
+}
+
+fn free() {}
+EOF
+$PARSER "$TEST_DIR/context.rs" > "$TEST_DIR/context.json"
+jq -c '.functions[] | .context' "$TEST_DIR/context.json" | tee "$TEST_DIR/context.out"
+cat > "$TEST_DIR/context.expected" << 'EOF'
+{"kind":"impl","self_ty":"FieldElement51"}
+{"kind":"impl","self_ty":"FieldElement51","trait":"Mul"}
+{"kind":"impl","self_ty":"&'a FieldElement51","trait":"core::ops::Mul<&'a FieldElement51>","generics":"<'a>"}
+{"kind":"trait","trait":"Tr","generics":"<T>"}
+{"kind":"standalone"}
+EOF
+diff "$TEST_DIR/context.expected" "$TEST_DIR/context.out"
+[ "$($PARSER "$TEST_DIR/context.rs" --context-string | jq -c '[.functions[].context]')" = '["impl","impl","impl","trait","standalone"]' ]
+$PARSER query --input "$TEST_DIR/context.json" --format detailed > "$TEST_DIR/context.txt"
+grep -q "^square @ .* in impl$" "$TEST_DIR/context.txt"
+echo
+
+echo "=== Test 65: Const and static items ==="
+cat > "$TEST_DIR/consts.rs" << 'EOF'
+const PLAIN: u8 = 1;
+
+verus! {
+    pub spec const SPEC_LIMIT: int = 10;
+    pub exec static COUNTER: u32 = 0;
+
+    impl S {
+        pub const ZERO: u64 = 0;
+    }
+
+    trait T {
+        const N: usize;
+    }
+
+    mod inner {
+        static mut FLAG: bool = false;
+    }
+}
+EOF
+$PARSER "$TEST_DIR/consts.rs" --include-consts > "$TEST_DIR/consts.json"
+jq -c '.consts[] | [.qualified_name, .kind, .mode, .type]' "$TEST_DIR/consts.json" | tee "$TEST_DIR/consts.out"
+cat > "$TEST_DIR/consts.expected" << 'EOF'
+["consts::PLAIN","const",null,"u8"]
+["consts::SPEC_LIMIT","const","spec","int"]
+["consts::COUNTER","static","exec","u32"]
+["consts::S::ZERO","const",null,"u64"]
+["consts::T::N","const",null,"usize"]
+["consts::inner::FLAG","static mut",null,"bool"]
+EOF
+diff "$TEST_DIR/consts.expected" "$TEST_DIR/consts.out"
+[ "$(jq -c '.summary.consts_by_module' "$TEST_DIR/consts.json")" = '{"consts":5,"consts::inner":1}' ]
+[ "$($PARSER "$TEST_DIR/consts.rs" | jq 'has("consts")')" = "false" ]
+$PARSER query --input "$TEST_DIR/consts.json" --module consts::inner > "$TEST_DIR/consts_inner.json"
+[ "$(jq -c '[.consts[].name]' "$TEST_DIR/consts_inner.json")" = '["FLAG"]' ]
+$PARSER "$TEST_DIR/consts.rs" --include-consts --format detailed > "$TEST_DIR/consts.txt"
+grep -q "^consts::SPEC_LIMIT \[spec const\]: int @ " "$TEST_DIR/consts.txt"
+grep -q "^Consts: 5 consts, 1 consts::inner$" "$TEST_DIR/consts.txt"
+echo
+
+echo "=== Test 66: Type aliases and associated types ==="
+cat > "$TEST_DIR/aliases.rs" << 'EOF'
+type Limbs = [u64; 5];
+
+verus! {
+    pub type Pair<T> = (T, T);
+
+    pub trait View {
+        type V: Copy;
+        type Default = u8;
+    }
+
+    impl View for FieldElement51 {
+        type V = Seq<u64>;
+    }
+
+    mod inner {
+        type Byte = u8;
+    }
+}
+EOF
+$PARSER "$TEST_DIR/aliases.rs" --include-types > "$TEST_DIR/aliases.json"
+jq -c '.type_aliases[] | [.qualified_name, .generics, .target, .bounds, .context]' "$TEST_DIR/aliases.json" | tee "$TEST_DIR/aliases.out"
+cat > "$TEST_DIR/aliases.expected" << 'EOF'
+["aliases::Limbs",null,"[u64; 5]",null,{"kind":"standalone"}]
+["aliases::Pair","<T>","(T, T)",null,{"kind":"standalone"}]
+["aliases::View::V",null,null,"Copy",{"kind":"trait","trait":"View"}]
+["aliases::View::Default",null,"u8",null,{"kind":"trait","trait":"View"}]
+["aliases::<FieldElement51 as View>::V",null,"Seq<u64>",null,{"kind":"impl","self_ty":"FieldElement51","trait":"View"}]
+["aliases::inner::Byte",null,"u8",null,{"kind":"standalone"}]
+EOF
+diff "$TEST_DIR/aliases.expected" "$TEST_DIR/aliases.out"
+[ "$($PARSER "$TEST_DIR/aliases.rs" | jq 'has("type_aliases")')" = "false" ]
+$PARSER "$TEST_DIR/aliases.rs" --include-types --format detailed > "$TEST_DIR/aliases.txt"
+grep -q "^aliases::Pair<T> = (T, T) \[standalone\] @ " "$TEST_DIR/aliases.txt"
+grep -q "^aliases::View::V: Copy \[trait\] @ " "$TEST_DIR/aliases.txt"
+echo
+
+echo "=== Test 67: macro_rules! definitions ==="
+cat > "$TEST_DIR/macros.rs" << 'EOF'
+macro_rules! lemma_pair {
+    ($a:expr) => { lemma_one($a) };
+    ($a:expr, $b:expr) => { lemma_one($a); lemma_one($b) };
+}
+
+verus! {
+    macro_rules! bounded {
+        ($x:expr) => { $x < 100 };
+    }
+
+    proof fn lemma_one(x: int) {}
+
+    proof fn uses_macros(x: int) {
+        lemma_pair!(x, x);
+        assert(bounded!(5));
+        assert(x == x) by {
+            lemma_pair!(x);
+        }
+    }
+
+    fn plain() {
+        println!("no local macros");
+    }
+}
+EOF
+$PARSER "$TEST_DIR/macros.rs" > "$TEST_DIR/macros.json"
+jq -c '.functions[] | [.name, .kind, .rule_count, .local_macros]' "$TEST_DIR/macros.json" | tee "$TEST_DIR/macros.out"
+cat > "$TEST_DIR/macros.expected" << 'EOF'
+["lemma_pair","macro_def",2,null]
+["bounded","macro_def",1,null]
+["lemma_one",null,null,null]
+["uses_macros",null,null,["bounded","lemma_pair"]]
+["plain",null,null,null]
+EOF
+diff "$TEST_DIR/macros.expected" "$TEST_DIR/macros.out"
+[ "$($PARSER "$TEST_DIR/macros.rs" --only-trusted | jq '[.functions[] | select(.kind == "macro_def")] | length')" = "0" ]
+$PARSER "$TEST_DIR/macros.rs" --format detailed > "$TEST_DIR/macros.txt"
+grep -q "^lemma_pair \[macro_def\] (2 rules) @ " "$TEST_DIR/macros.txt"
+grep -q "^    macros: bounded, lemma_pair$" "$TEST_DIR/macros.txt"
+echo
+
+echo "=== Test 68: Unparsed item-position macros ==="
+cat > "$TEST_DIR/unparsed.rs" << 'EOF'
+macro_rules! define_mul_variants {
+    ($t:ty) => {};
+}
+
+define_mul_variants!(FieldElement51);
+
+verus! {
+    fn seen() {}
+
+    impl FieldElement51 {
+        impl_helpers! { square }
+    }
+
+    mod inner {
+        crate::backend::define_ops!(Scalar);
+    }
+}
+EOF
+$PARSER "$TEST_DIR/unparsed.rs" > "$TEST_DIR/unparsed.json" 2> "$TEST_DIR/unparsed.err"
+jq -c '.unparsed_macros[] | [.path, .start_line, .module_path]' "$TEST_DIR/unparsed.json" | tee "$TEST_DIR/unparsed.out"
+cat > "$TEST_DIR/unparsed.expected" << 'EOF'
+["define_mul_variants",5,"unparsed"]
+["impl_helpers",11,"unparsed"]
+["crate::backend::define_ops",15,"unparsed::inner"]
+EOF
+diff "$TEST_DIR/unparsed.expected" "$TEST_DIR/unparsed.out"
+[ "$(jq '.summary.unparsed_macros' "$TEST_DIR/unparsed.json")" = "3" ]
+[ ! -s "$TEST_DIR/unparsed.err" ]
+$PARSER "$TEST_DIR/unparsed.rs" --warn-unparsed-macros > /dev/null 2> "$TEST_DIR/unparsed.err"
+grep -q "^Warning: define_mul_variants! at .*unparsed.rs:5 was not expanded" "$TEST_DIR/unparsed.err"
+[ "$(wc -l < "$TEST_DIR/unparsed.err")" -eq 3 ]
+$PARSER "$TEST_DIR/unparsed.rs" --format detailed > "$TEST_DIR/unparsed.txt"
+grep -q "^Unparsed macros: 3$" "$TEST_DIR/unparsed.txt"
+echo
+
+echo "=== Test 69: include! files ==="
+mkdir -p "$TEST_DIR/include_crate/src/tables"
+cat > "$TEST_DIR/include_crate/src/lib.rs" << 'EOF'
+mod tables {
+    include!("tables/generated.rs");
+}
+
+include!(concat!(env!("OUT_DIR"), "/tables.rs"));
+include!("missing.rs");
+
+fn top() {}
+EOF
+cat > "$TEST_DIR/include_crate/src/tables/generated.rs" << 'EOF'
+fn lookup(i: usize) -> u64 { 0 }
+
+verus! {
+    spec fn table_spec(i: nat) -> nat { i }
+}
+EOF
+$PARSER "$TEST_DIR/include_crate" > "$TEST_DIR/include.json"
+jq -c '.functions[] | [.qualified_name, (.file | sub(".*/src/"; "")), .start_line]' "$TEST_DIR/include.json" | tee "$TEST_DIR/include.out"
+cat > "$TEST_DIR/include.expected" << 'EOF'
+["top","lib.rs",8]
+["tables::lookup","tables/generated.rs",1]
+["tables::table_spec","tables/generated.rs",4]
+EOF
+diff "$TEST_DIR/include.expected" "$TEST_DIR/include.out"
+[ "$(jq -c '[.unparsed_macros[] | [.path, .start_line]]' "$TEST_DIR/include.json")" = '[["include",5],["include",6]]' ]
+echo
+
+echo "=== Test 70: Module paths from mod declarations ==="
+C="$TEST_DIR/path_crate/src"
+mkdir -p "$C/backend/u64" "$C/backend/scalar" "$C/outer"
+cat > "$C/lib.rs" << 'EOF'
+mod backend;
+verus! {
+    mod spec_mod;
+}
+mod outer {
+    mod nested;
+}
+EOF
+cat > "$C/backend/mod.rs" << 'EOF'
+#[cfg(feature = "u64")]
+#[path = "u64/field.rs"]
+mod field;
+mod scalar;
+EOF
+echo 'fn mul() {}' > "$C/backend/u64/field.rs"
+echo 'mod helpers;' > "$C/backend/scalar.rs"
+echo 'fn help() {}' > "$C/backend/scalar/helpers.rs"
+echo 'verus! { spec fn s() -> bool { true } }' > "$C/spec_mod.rs"
+echo 'fn n() {}' > "$C/outer/nested.rs"
+echo 'fn o() {}' > "$C/orphan.rs"
+$PARSER "$TEST_DIR/path_crate" > "$TEST_DIR/paths.json"
+jq -r '.functions[].qualified_name' "$TEST_DIR/paths.json" | sort | tee "$TEST_DIR/paths.out"
+cat > "$TEST_DIR/paths.expected" << 'EOF'
+backend::field::mul
+backend::scalar::helpers::help
+orphan::o
+outer::nested::n
+spec_mod::s
+EOF
+diff "$TEST_DIR/paths.expected" "$TEST_DIR/paths.out"
+[ "$(jq -c '[.unreachable_files[] | sub(".*/src/"; "")]' "$TEST_DIR/paths.json")" = '["orphan.rs"]' ]
+$PARSER "$TEST_DIR/path_crate" --format detailed > "$TEST_DIR/paths.txt"
+grep -q "^Unreachable files (module paths guessed):$" "$TEST_DIR/paths.txt"
+echo
+
+echo "=== Test 71: cfg conditions ==="
+C="$TEST_DIR/cfg_crate/src"
+mkdir -p "$C/backend"
+cat > "$C/lib.rs" << 'EOF'
+#[cfg(feature = "u64_backend")]
+mod backend;
+
+cfg_if! {
+    if #[cfg(feature = "simd")] {
+        fn simd_mul() {}
+    } else if #[cfg(target_pointer_width = "64")] {
+        fn wide_mul() {}
+    } else {
+        fn narrow_mul() {}
+    }
+}
+
+#[cfg(not(test))]
+fn always() {}
+
+fn plain() {}
+EOF
+cat > "$C/backend/mod.rs" << 'EOF'
+mod inner {
+    #[cfg(any(test, feature = "fiat"))]
+    impl Fiat {
+        fn reduce(&self) {}
+    }
+}
+EOF
+$PARSER "$TEST_DIR/cfg_crate" > "$TEST_DIR/cfg.json"
+jq -c '.functions[] | [.name, .cfg]' "$TEST_DIR/cfg.json" | tee "$TEST_DIR/cfg.out"
+cat > "$TEST_DIR/cfg.expected" << 'EOF'
+["simd_mul",["feature = \"simd\""]]
+["wide_mul",["not(feature = \"simd\")","target_pointer_width = \"64\""]]
+["narrow_mul",["not(feature = \"simd\")","not(target_pointer_width = \"64\")"]]
+["always",["not(test)"]]
+["plain",null]
+["reduce",["feature = \"u64_backend\"","any(test, feature = \"fiat\")"]]
+EOF
+diff <(sort "$TEST_DIR/cfg.expected") <(sort "$TEST_DIR/cfg.out")
+$PARSER "$TEST_DIR/cfg_crate" --cfg-filter 'feature = "u64_backend", feature = "fiat", target_pointer_width = "64"' | jq -r '.functions[].name' | sort | tr '\n' ' ' > "$TEST_DIR/cfg_filtered.out"
+[ "$(cat "$TEST_DIR/cfg_filtered.out")" = "always plain reduce wide_mul " ]
+$PARSER "$TEST_DIR/cfg_crate" --cfg-filter 'test' | jq -r '.functions[].name' | sort | tr '\n' ' ' > "$TEST_DIR/cfg_filtered.out"
+[ "$(cat "$TEST_DIR/cfg_filtered.out")" = "narrow_mul plain " ]
+if $PARSER "$TEST_DIR/cfg_crate" --cfg-filter 'all(test)' > /dev/null 2>&1; then exit 1; fi
+$PARSER "$TEST_DIR/cfg_crate" --format detailed > "$TEST_DIR/cfg.txt"
+grep -q '^    cfg: not(feature = "simd"), target_pointer_width = "64"$' "$TEST_DIR/cfg.txt"
+echo
+
+echo "=== Test 72: Excluding test code ==="
+C="$TEST_DIR/tests_crate/src"
+mkdir -p "$C"
+cat > "$C/lib.rs" << 'EOF'
+#[cfg(test)]
+mod file_tests;
+
+fn lib_fn() {}
+
+#[test]
+fn loose_test() {}
+
+#[bench]
+fn loose_bench() {}
+
+verus! {
+    proof fn lemma() {}
+
+    #[cfg(test)]
+    mod tests {
+        fn helper() {}
+        #[test]
+        fn check() {}
+        struct Fixture;
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_a(x in 0u64..10) {}
+    #[test]
+    fn prop_b(x in 0u64..10) {}
+}
+EOF
+echo 'fn in_test_file() {}' > "$C/file_tests.rs"
+$PARSER "$TEST_DIR/tests_crate" --include-types > "$TEST_DIR/tests_all.json"
+jq -c '[.functions[] | select(.is_test) | .name] | sort' "$TEST_DIR/tests_all.json" | tee "$TEST_DIR/tests_marked.out"
+[ "$(cat "$TEST_DIR/tests_marked.out")" = '["check","helper","in_test_file","loose_bench","loose_test"]' ]
+[ "$(jq '.summary.excluded_tests' "$TEST_DIR/tests_all.json")" = "0" ]
+$PARSER "$TEST_DIR/tests_crate" --include-types --exclude-tests > "$TEST_DIR/tests.json"
+[ "$(jq -c '[.functions[].name]' "$TEST_DIR/tests.json")" = '["lib_fn","lemma"]' ]
+[ "$(jq '.summary.excluded_tests' "$TEST_DIR/tests.json")" = "7" ]
+[ "$(jq 'has("types") or has("unparsed_macros")' "$TEST_DIR/tests.json")" = "false" ]
+$PARSER "$TEST_DIR/tests_crate" --exclude-tests --format detailed > "$TEST_DIR/tests.txt"
+grep -q "^Excluded test functions: 7$" "$TEST_DIR/tests.txt"
+echo
+
+echo "=== Test 73: Cargo targets ==="
+W="$TEST_DIR/workspace"
+mkdir -p "$W/core/src/bin" "$W/core/tests" "$W/core/examples" "$W/core/benches" "$W/app/src" "$W/app/harness"
+printf '[workspace]\nmembers = ["core", "app"]\n' > "$W/Cargo.toml"
+printf '[package]\nname = "core"\n' > "$W/core/Cargo.toml"
+printf '[package]\nname = "app"\n\n[[test]]\nname = "run"\npath = "harness/run.rs"\n' > "$W/app/Cargo.toml"
+echo 'mod field; fn lib_root() {}' > "$W/core/src/lib.rs"
+echo 'fn field_fn() {}' > "$W/core/src/field.rs"
+echo 'fn tool_main() {}' > "$W/core/src/bin/tool.rs"
+echo 'fn it_test() {}' > "$W/core/tests/it.rs"
+echo 'fn demo() {}' > "$W/core/examples/demo.rs"
+echo 'fn bench_fn() {}' > "$W/core/benches/b.rs"
+echo 'fn build_main() {}' > "$W/core/build.rs"
+echo 'mod cli; fn app_main() {}' > "$W/app/src/main.rs"
+echo 'fn cli_fn() {}' > "$W/app/src/cli.rs"
+echo 'mod util; fn run_test() {}' > "$W/app/harness/run.rs"
+echo 'fn util_fn() {}' > "$W/app/harness/util.rs"
+$PARSER "$W" > "$TEST_DIR/targets.json"
+jq -r '.functions[] | "\(.name) \(.target)"' "$TEST_DIR/targets.json" | sort | tee "$TEST_DIR/targets.out"
+cat > "$TEST_DIR/targets.expected" << 'EOF'
+app_main bin
+bench_fn bench
+build_main build
+cli_fn bin
+demo example
+field_fn lib
+it_test test
+lib_root lib
+run_test test
+tool_main bin
+util_fn test
+EOF
+diff "$TEST_DIR/targets.expected" "$TEST_DIR/targets.out"
+[ "$($PARSER "$W/core" --only-target lib | jq -c '[.functions[].name] | sort')" = '["field_fn","lib_root"]' ]
+[ "$($PARSER "$W/core/src/field.rs" | jq -r '.functions[0].target')" = "lib" ]
+if $PARSER "$W" --only-target library > /dev/null 2>&1; then exit 1; fi
+echo
+
+echo "=== Test 74: State machines ==="
+cat > "$TEST_DIR/counter.rs" << 'EOF'
+use vstd::prelude::*;
+use verus_state_machines_macros::tokenized_state_machine;
+
+verus! {
+
+tokenized_state_machine!{
+    X {
+        fields {
+            #[sharding(variable)]
+            pub counter: int,
+
+            #[sharding(count)]
+            pub inc_a: nat,
+        }
+
+        #[invariant]
+        pub fn main_inv(&self) -> bool {
+            self.counter == self.inc_a
+        }
+
+        init!{
+            initialize() {
+                init counter = 0;
+                init inc_a = 0;
+            }
+        }
+
+        transition!{
+            tr_inc_a() {
+                update counter = pre.counter + 1;
+                add inc_a += (1);
+            }
+        }
+
+        property!{
+            counter_nonneg() {
+                assert(pre.counter >= 0);
+            }
+        }
+
+        #[inductive(initialize)]
+        fn initialize_inductive(post: Self) { }
+
+        #[inductive(tr_inc_a)]
+        fn tr_inc_a_preserves(pre: Self, post: Self) {
+        }
+    }
+}
+
+} // verus!
+EOF
+cat > "$TEST_DIR/fifo.rs" << 'EOF'
+verus! {
+state_machine!{ Fifo<T> {
+    fields {
+        pub items: Seq<T>,
+    }
+
+    init!{
+        empty() {
+            init items = Seq::empty();
+        }
+    }
+
+    transition!{
+        push(t: T) {
+            update items = pre.items.push(t);
+        }
+    }
+
+    readonly!{
+        peek() {
+            require(pre.items.len() > 0);
+        }
+    }
+}}
+}
+EOF
+$PARSER "$TEST_DIR/counter.rs" > "$TEST_DIR/counter.json"
+jq -r '.functions[] | "\(.qualified_name) \(.kind) \(.state_machine_role) \(.context.kind) \(.start_line)-\(.end_line)"' "$TEST_DIR/counter.json" | tee "$TEST_DIR/counter.out"
+cat > "$TEST_DIR/counter.expected" << 'EOF'
+counter::X::main_inv null invariant state_machine 16-19
+counter::X::initialize init null state_machine 22-25
+counter::X::tr_inc_a transition null state_machine 29-32
+counter::X::counter_nonneg property null state_machine 36-38
+counter::X::initialize_inductive null inductive(initialize) state_machine 41-42
+counter::X::tr_inc_a_preserves null inductive(tr_inc_a) state_machine 44-46
+EOF
+diff "$TEST_DIR/counter.expected" "$TEST_DIR/counter.out"
+[ "$(jq -c '.state_machines[0].fields | map([.name, .type, .sharding])' "$TEST_DIR/counter.json")" = '[["counter","int","variable"],["inc_a","nat","count"]]' ]
+[ "$(jq '.summary.unparsed_macros' "$TEST_DIR/counter.json")" = "0" ]
+# Transitions are not functions Verus can be asked to verify
+$PARSER "$TEST_DIR/counter.rs" --format verus-args | grep -q "X::tr_inc_a_preserves"
+if $PARSER "$TEST_DIR/counter.rs" --format verus-args | grep -q "X::tr_inc_a "; then exit 1; fi
+[ "$($PARSER "$TEST_DIR/fifo.rs" | jq -c '[.functions[] | [.name, .kind]]')" = '[["empty","init"],["push","transition"],["peek","readonly"]]' ]
+[ "$($PARSER "$TEST_DIR/fifo.rs" | jq -r '.state_machines[0] | "\(.kind) \(.generics) \(.fields[0].type)"')" = "state_machine <T> Seq<T>" ]
+echo
+
+echo "=== Test 75: Unspecified exec functions ==="
+mkdir -p "$TEST_DIR/port/src"
+cat > "$TEST_DIR/port/src/lib.rs" << 'EOF'
+mod field;
+verus! {
+fn add(a: u8, b: u8) -> (r: u8)
+    requires a < 100, b < 100,
+{ a + b }
+
+fn sub(a: u8, b: u8) -> u8 { a - b }
+
+#[verifier::external_body]
+fn hash(a: u8) -> u8 { a }
+
+spec fn double(a: int) -> int { 2 * a }
+}
+EOF
+cat > "$TEST_DIR/port/src/field.rs" << 'EOF'
+verus! {
+fn mul(a: u8) -> (r: u8) ensures r == a { a }
+fn neg(a: u8) -> (r: u8) returns a { a }
+#[verifier::external]
+fn dump(a: u8) {}
+}
+EOF
+$PARSER "$TEST_DIR/port" --unspecified-only > "$TEST_DIR/unspecified.json"
+[ "$(jq -c '[.functions[] | [.qualified_name, .specified]]' "$TEST_DIR/unspecified.json")" = '[["sub",false]]' ]
+jq -c '.summary.spec_coverage' "$TEST_DIR/unspecified.json"
+[ "$(jq -c '.summary.spec_coverage.crate' "$TEST_DIR/unspecified.json")" = '{"exec_functions":2,"specified":1,"percent_specified":50,"external":1}' ]
+[ "$(jq -c '.summary.spec_coverage.field' "$TEST_DIR/unspecified.json")" = '{"exec_functions":2,"specified":2,"percent_specified":100,"external":1}' ]
+$PARSER "$TEST_DIR/port" --unspecified-only --format detailed | tee "$TEST_DIR/unspecified.out"
+grep -q "    crate: 1/2 (50.0%), 1 external" "$TEST_DIR/unspecified.out"
+# Spec functions get no `specified`, and it is only computed on request
+[ "$($PARSER "$TEST_DIR/port" --show-specs | jq -c '[.functions[] | select(.specified != null) | .name] | sort')" = '["add","dump","hash","mul","neg","sub"]' ]
+[ "$($PARSER "$TEST_DIR/port" | jq '[.functions[] | select(.specified != null)] | length')" = "0" ]
+echo
+
+echo "=== Test 76: decreases when/via ==="
+cat > "$TEST_DIR/termination.rs" << 'EOF'
+verus! {
+spec fn is_even(n: int) -> bool
+    decreases n when n >= 0 via even_decreases
+{
+    if n <= 0 { true } else { is_odd(n - 1) }
+}
+
+spec fn is_odd(n: int) -> bool
+    decreases n
+{
+    if n <= 0 { false } else { is_even(n - 1) }
+}
+
+#[via_fn]
+proof fn even_decreases(n: int) {
+    helper(n);
+}
+
+proof fn helper(n: int) {}
+}
+EOF
+$PARSER "$TEST_DIR/termination.rs" --show-specs | jq -c '.functions[0] | [.decreases, .decreases_when, .decreases_via]' | tee "$TEST_DIR/decreases.out"
+[ "$(cat "$TEST_DIR/decreases.out")" = '[["n"],"n >= 0","even_decreases"]' ]
+[ "$($PARSER "$TEST_DIR/termination.rs" --show-specs | jq -c '[.functions[1] | .decreases_when, .decreases_via]')" = '[null,null]' ]
+$PARSER closure --function is_odd "$TEST_DIR/termination.rs" --format json | jq -r '.functions[].name' | sort | tee "$TEST_DIR/via_closure.out"
+printf 'even_decreases\nhelper\nis_even\nis_odd\n' | diff - "$TEST_DIR/via_closure.out"
+$PARSER "$TEST_DIR/termination.rs" --show-specs --format detailed > "$TEST_DIR/decreases.txt"
+grep -q "    decreases n when n >= 0 via even_decreases" "$TEST_DIR/decreases.txt"
+echo
+
+echo "=== Test 77: Loop isolation ==="
+mkdir -p "$TEST_DIR/iso/src"
+cat > "$TEST_DIR/iso/src/lib.rs" << 'EOF'
+#[verifier::loop_isolation(false)]
+mod relaxed;
+mod strict;
+verus! {
+fn root_fn() {}
+}
+EOF
+cat > "$TEST_DIR/iso/src/relaxed.rs" << 'EOF'
+verus! {
+fn inherits() {}
+
+#[verifier::loop_isolation(true)]
+fn overrides() {}
+
+mod inner {
+    fn nested() {}
+}
+}
+EOF
+cat > "$TEST_DIR/iso/src/strict.rs" << 'EOF'
+verus! {
+struct S;
+
+#[verifier::loop_isolation(false)]
+impl S {
+    fn method(&self) {}
+}
+
+#[verifier(loop_isolation(false))]
+fn old_syntax() {}
+
+mod m {
+    #![verifier::loop_isolation(false)]
+    fn inner_attr() {}
+}
+}
+EOF
+$PARSER "$TEST_DIR/iso" | jq -r '.functions[] | "\(.qualified_name) \(.loop_isolation)"' | sort | tee "$TEST_DIR/iso.out"
+cat > "$TEST_DIR/iso.expected" << 'EOF'
+relaxed::inherits false
+relaxed::inner::nested false
+relaxed::overrides true
+root_fn null
+strict::S::method false
+strict::m::inner_attr false
+strict::old_syntax false
+EOF
+diff "$TEST_DIR/iso.expected" "$TEST_DIR/iso.out"
+[ "$($PARSER "$TEST_DIR/iso" --loop-isolation-disabled | jq -c '[.functions[].qualified_name] | sort')" = '["relaxed::inherits","relaxed::inner::nested","strict::S::method","strict::m::inner_attr","strict::old_syntax"]' ]
+echo
+
+echo "=== Test 78: Unchecked termination ==="
+mkdir -p "$TEST_DIR/term/src"
+cat > "$TEST_DIR/term/src/lib.rs" << 'EOF'
+#[verifier::exec_allows_no_decreases_clause]
+mod spin;
+mod checked;
+EOF
+cat > "$TEST_DIR/term/src/spin.rs" << 'EOF'
+verus! {
+fn wait_forever() { loop {} }
+proof fn lemma_not_exec() {}
+}
+EOF
+cat > "$TEST_DIR/term/src/checked.rs" << 'EOF'
+verus! {
+fn ok() {}
+
+#[verifier::exec_allows_no_decreases_clause]
+fn retry(n: u64) { retry(n) }
+
+fn caller() { retry(1) }
+}
+EOF
+$PARSER "$TEST_DIR/term" | jq -r '.functions[] | "\(.qualified_name) \(.termination_unchecked // false) \(.trusted)"' | sort | tee "$TEST_DIR/term.out"
+cat > "$TEST_DIR/term.expected" << 'EOF'
+checked::caller false null
+checked::ok false null
+checked::retry true ["termination_unchecked"]
+spin::lemma_not_exec false null
+spin::wait_forever true ["termination_unchecked"]
+EOF
+diff "$TEST_DIR/term.expected" "$TEST_DIR/term.out"
+[ "$($PARSER "$TEST_DIR/term" --only-trusted | jq -c '[.functions[].qualified_name] | sort')" = '["checked::retry","spin::wait_forever"]' ]
+$PARSER trusted "$TEST_DIR/term" --format json | jq -c '.trusted_leaves' | grep -q '"checked::retry":\["termination_unchecked"\]'
+$PARSER "$TEST_DIR/term" --fail-if-termination-unchecked 2 > /dev/null
+if $PARSER "$TEST_DIR/term" --fail-if-termination-unchecked > /dev/null 2> "$TEST_DIR/term.err"; then exit 1; fi
+cat "$TEST_DIR/term.err"
+grep -q "spin::wait_forever @ " "$TEST_DIR/term.err"
+echo
+
+echo "=== Test 79: Trait method bodies ==="
+cat > "$TEST_DIR/trait_bodies.rs" << 'EOF'
+trait Plain {
+    fn required(&self) -> u8;
+    fn provided(&self) -> u8 { 0 }
+}
+
+verus! {
+trait Verified {
+    fn spec_len(&self) -> nat;
+
+    fn len(&self) -> (r: usize)
+        ensures r == self.spec_len();
+
+    fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+uninterp spec fn opaque_fn(x: int) -> int;
+
+fn concrete() {}
+}
+EOF
+$PARSER "$TEST_DIR/trait_bodies.rs" | jq -r '.functions[] | "\(.name) \(.has_body)"' | tee "$TEST_DIR/bodies.out"
+cat > "$TEST_DIR/bodies.expected" << 'EOF'
+required false
+provided true
+spec_len false
+len false
+is_empty true
+opaque_fn false
+concrete true
+EOF
+diff "$TEST_DIR/bodies.expected" "$TEST_DIR/bodies.out"
+$PARSER "$TEST_DIR/trait_bodies.rs" --format detailed > "$TEST_DIR/bodies.txt"
+grep -q "^len \[declaration\] @ " "$TEST_DIR/bodies.txt"
+grep -q "^is_empty @ " "$TEST_DIR/bodies.txt"
+echo
+
+echo "=== Test 80: FFI boundary ==="
+cat > "$TEST_DIR/ffi.rs" << 'EOF'
+extern "C" {
+    fn memcpy(dst: *mut u8, src: *const u8, n: usize) -> *mut u8;
+}
+
+#[no_mangle]
+pub extern "C" fn ffi_add(a: u32, b: u32) -> u32 { a + b }
+
+#[export_name = "crypto_hash"]
+pub fn hash(x: u8) -> u8 { x }
+
+#[unsafe(no_mangle)]
+pub extern fn bare_extern() {}
+
+verus! {
+pub extern "system" fn callback() {}
+
+fn internal() {}
+}
+EOF
+$PARSER "$TEST_DIR/ffi.rs" | jq -r '.functions[] | "\(.name) \(.abi) \(.no_mangle // false) \(.export_name) \(.has_body)"' | tee "$TEST_DIR/ffi.out"
+cat > "$TEST_DIR/ffi.expected" << 'EOF'
+memcpy C false null false
+ffi_add C true null true
+hash null false crypto_hash true
+bare_extern C true null true
+callback system false null true
+internal null false null true
+EOF
+diff "$TEST_DIR/ffi.expected" "$TEST_DIR/ffi.out"
+[ "$($PARSER "$TEST_DIR/ffi.rs" --only-ffi | jq -c '[.functions[].name]')" = '["memcpy","ffi_add","hash","bare_extern","callback"]' ]
+$PARSER "$TEST_DIR/ffi.rs" --format detailed > "$TEST_DIR/ffi.txt"
+grep -q '^ffi_add \[extern "C"\] (no_mangle) @ ' "$TEST_DIR/ffi.txt"
+grep -q '^memcpy \[declaration\] \[extern "C"\] @ ' "$TEST_DIR/ffi.txt"
+echo
+
+echo "=== Test 81: Functions in const blocks ==="
+cat > "$TEST_DIR/anon.rs" << 'EOF'
+struct S;
+
+const _: () = {
+    fn helper() {}
+
+    impl Clone for S {
+        fn clone(&self) -> S { S }
+    }
+};
+
+static TABLE: [u8; 2] = {
+    fn gen() -> u8 { 1 }
+    [gen(), gen()]
+};
+
+impl S {
+    const C: u8 = {
+        fn in_assoc() -> u8 { 2 }
+        in_assoc()
+    };
+}
+
+mod inner {
+    const _: () = {
+        fn nested_helper() {}
+    };
+}
+
+fn outer() {
+    let _ = const {
+        fn in_inline() {}
+        1
+    };
+}
+EOF
+$PARSER "$TEST_DIR/anon.rs" | jq -r '.functions[] | "\(.qualified_name) \(.context.kind) \(.module_path) \(.start_line)-\(.end_line)"' | tee "$TEST_DIR/anon.out"
+cat > "$TEST_DIR/anon.expected" << 'EOF'
+anon::helper const-block anon 4-4
+anon::<S as Clone>::clone impl anon 7-7
+anon::gen const-block anon 12-12
+anon::in_assoc const-block anon 18-18
+anon::inner::nested_helper const-block anon::inner 25-25
+anon::outer standalone anon 29-34
+anon::outer::in_inline const-block anon 31-31
+EOF
+diff "$TEST_DIR/anon.expected" "$TEST_DIR/anon.out"
+[ "$($PARSER "$TEST_DIR/anon.rs" --context-string | jq -r '.functions[0].context')" = "const-block" ]
+echo
+
+echo "=== Test 82: Functions nested in function bodies ==="
+cat > "$TEST_DIR/nested.rs" << 'EOF'
+struct S;
+
+fn outer() {
+    fn inner() {
+        fn deepest() {}
+    }
+    inner();
+}
+
+impl S {
+    fn method(&self) {
+        fn helper() {}
+        helper();
+    }
+}
+EOF
+$PARSER "$TEST_DIR/nested.rs" --show-calls | jq -r '.functions[] | "\(.qualified_name) \(.parent_function) \(.context.kind)"' | tee "$TEST_DIR/nested.out"
+cat > "$TEST_DIR/nested.expected" << 'EOF'
+nested::outer null standalone
+nested::outer::inner nested::outer standalone
+nested::outer::inner::deepest nested::outer::inner standalone
+nested::S::method null impl
+nested::S::method::helper nested::S::method standalone
+EOF
+diff "$TEST_DIR/nested.expected" "$TEST_DIR/nested.out"
+# Without methods, functions nested in method bodies are still found
+[ "$($PARSER "$TEST_DIR/nested.rs" --include-methods false | jq -r '[.functions[].qualified_name] | join(",")')" = "nested::outer,nested::outer::inner,nested::outer::inner::deepest,nested::S::method::helper" ]
+$PARSER "$TEST_DIR/nested.rs" --format detailed > "$TEST_DIR/nested.txt"
+grep "helper.*(nested in nested::S::method)" "$TEST_DIR/nested.txt"
+echo
+
+echo "=== Test 83: verus! nested in function bodies and other macros ==="
+cat > "$TEST_DIR/embedded.rs" << 'EOF'
+#[test]
+fn test_lemma() {
+    verus! {
+        proof fn lemma_helper(x: int)
+            ensures x + 0 == x,
+        {
+        }
+    }
+}
+
+wrapper! {
+    verus! {
+        spec fn wrapped(x: int) -> int { x }
+    }
+}
+
+fn in_expression() {
+    let _ = run!(verus! {
+        fn from_expr() {}
+    });
+}
+EOF
+$PARSER "$TEST_DIR/embedded.rs" --show-kind | jq -r '.functions[] | "\(.qualified_name) \(.kind) \(.parent_function) \(.start_line)-\(.end_line)"' | tee "$TEST_DIR/embedded.out"
+cat > "$TEST_DIR/embedded.expected" << 'EOF'
+embedded::test_lemma fn null 1-9
+embedded::test_lemma::lemma_helper proof fn embedded::test_lemma 4-7
+embedded::wrapped spec fn null 13-13
+embedded::in_expression fn null 17-21
+embedded::in_expression::from_expr fn embedded::in_expression 19-19
+EOF
+diff "$TEST_DIR/embedded.expected" "$TEST_DIR/embedded.out"
+echo
+
+echo "=== Test 84: Functions inside verus! ==="
+mkdir -p "$TEST_DIR/porting/src"
+cat > "$TEST_DIR/porting/src/lib.rs" << 'EOF'
+mod field;
+
+fn plain_root() {}
+
+verus! {
+    fn ported_root() {}
+}
+EOF
+cat > "$TEST_DIR/porting/src/field.rs" << 'EOF'
+cfg_if! {
+    if #[cfg(feature = "u64")] {
+        verus! {
+            fn ported_u64() {}
+        }
+    } else {
+        fn plain_u32() {}
+    }
+}
+
+verus! {
+    cfg_if! {
+        if #[cfg(feature = "simd")] {
+            fn ported_simd() {}
+        }
+    }
+
+    fn ported() {
+        fn nested_ported() {}
+    }
+}
+
+fn plain() {}
+EOF
+$PARSER "$TEST_DIR/porting" --format json > "$TEST_DIR/porting.json"
+jq -r '.functions[] | "\(.qualified_name) \(.in_verus_macro)"' "$TEST_DIR/porting.json" | sort | tee "$TEST_DIR/porting.out"
+cat > "$TEST_DIR/porting.expected" << 'EOF'
+field::plain false
+field::plain_u32 false
+field::ported true
+field::ported::nested_ported true
+field::ported_simd true
+field::ported_u64 true
+plain_root false
+ported_root true
+EOF
+diff "$TEST_DIR/porting.expected" "$TEST_DIR/porting.out"
+[ "$(jq -c '.summary.verus_coverage' "$TEST_DIR/porting.json")" = '{"crate":{"functions":2,"in_verus_macro":1,"percent_in_verus_macro":50},"field":{"functions":6,"in_verus_macro":4,"percent_in_verus_macro":66.7}}' ]
+[ "$($PARSER "$TEST_DIR/porting" --only-plain --format text | sort | tr '\n' ' ')" = "field::plain field::plain_u32 plain_root " ]
+[ "$($PARSER "$TEST_DIR/porting" --only-verus --format json | jq '.summary.total_functions')" = "5" ]
+$PARSER "$TEST_DIR/porting" --format detailed > "$TEST_DIR/porting.txt"
+grep "^    field: 4/6 (66.7%)$" "$TEST_DIR/porting.txt"
+echo
+
+echo "=== Test 85: Ghost and tracked wrappers ==="
+mkdir -p "$TEST_DIR/wrappers/src"
+cat > "$TEST_DIR/wrappers/src/lib.rs" << 'EOF'
+mod perms;
+
+verus! {
+    fn in_vec(cells: Vec<Tracked<PointsTo<u64>>>) {}
+
+    fn through_alias(p: perms::Perm) {}
+
+    fn through_nested_alias() -> perms::Perms {}
+
+    fn with_locals(x: u64) {
+        let ghost g = x;
+        let tracked t = PermissionToken::new();
+        let w: Ghost<int> = Ghost(0);
+        let plain = x + 1;
+        let _ = || {
+            let tracked u = t;
+        };
+    }
+
+    fn tracked_mode(tracked p: PointsTo<u64>) {}
+
+    fn plain(x: u64) -> u64 { x }
+
+    proof fn lemma(t: Tracked<PointsTo<u64>>) {}
+}
+EOF
+cat > "$TEST_DIR/wrappers/src/perms.rs" << 'EOF'
+pub type Perm = Tracked<PointsTo<u64>>;
+pub type Perms = Vec<Perm>;
+pub type Count = usize;
+EOF
+$PARSER "$TEST_DIR/wrappers" --format json > "$TEST_DIR/wrappers.json"
+jq -r '.functions[] | "\(.name) \(.ghost_wrappers_used) \(.ghost_locals) \(.tracked_locals)"' "$TEST_DIR/wrappers.json" | tee "$TEST_DIR/wrappers.out"
+cat > "$TEST_DIR/wrappers.expected" << 'EOF'
+in_vec true null null
+through_alias true null null
+through_nested_alias true null null
+with_locals true 2 2
+tracked_mode true null null
+plain null null null
+lemma null null null
+EOF
+diff "$TEST_DIR/wrappers.expected" "$TEST_DIR/wrappers.out"
+$PARSER "$TEST_DIR/wrappers" --format detailed > "$TEST_DIR/wrappers.txt"
+grep "^with_locals (ghost wrappers)" "$TEST_DIR/wrappers.txt"
+grep "^    ghost locals: 2, tracked locals: 2$" "$TEST_DIR/wrappers.txt"
+echo
+
+echo "=== Test 86: Automatic roots ==="
+mkdir -p "$TEST_DIR/autoroots/src"
+printf '[package]\nname = "autoroots"\n' > "$TEST_DIR/autoroots/Cargo.toml"
+cat > "$TEST_DIR/autoroots/src/lib.rs" << 'EOF'
+mod util;
+
+pub fn api(x: u64) -> u64 { util::helper(x) }
+
+fn private_unused() {}
+
+pub(crate) fn crate_only() {}
+EOF
+cat > "$TEST_DIR/autoroots/src/util.rs" << 'EOF'
+pub fn helper(x: u64) -> u64 { x }
+
+pub fn unused_helper() {}
+
+#[no_mangle]
+pub extern "C" fn exported() { inner() }
+
+fn inner() {}
+EOF
+cat > "$TEST_DIR/autoroots/src/main.rs" << 'EOF'
+fn main() { run() }
+
+fn run() {}
+
+fn never_called() {}
+EOF
+$PARSER dead-functions --roots auto "$TEST_DIR/autoroots" --format json > "$TEST_DIR/autoroots.json"
+jq -c '.roots | sort' "$TEST_DIR/autoroots.json" | tee "$TEST_DIR/autoroots.out"
+[ "$(cat "$TEST_DIR/autoroots.out")" = '["api","main","util::exported"]' ]
+[ "$(jq -c '[.dead_functions[][].qualified_name] | sort' "$TEST_DIR/autoroots.json")" = '["crate_only","never_called","private_unused","util::unused_helper"]' ]
+$PARSER dead-functions --roots auto "$TEST_DIR/autoroots" --format text > "$TEST_DIR/autoroots.txt"
+grep -A3 "^Roots:$" "$TEST_DIR/autoroots.txt"
+# An explicit list of roots instead
+printf '# public entry points\napi\n\nutil::unused_.*\n' > "$TEST_DIR/roots.txt"
+[ "$($PARSER dead-functions --roots-file "$TEST_DIR/roots.txt" "$TEST_DIR/autoroots" --format json | jq -c '.roots | sort')" = '["api","util::unused_helper"]' ]
+# The closure of the automatic roots
+$PARSER closure --roots auto "$TEST_DIR/autoroots" --format text 2> "$TEST_DIR/closure_roots.err" | sort > "$TEST_DIR/closure_roots.out"
+[ "$(tr '\n' ' ' < "$TEST_DIR/closure_roots.out")" = "api main run util::exported util::helper util::inner " ]
+grep "^Roots (3):$" "$TEST_DIR/closure_roots.err"
+echo
+
+echo "=== Test 87: Streaming ndjson output ==="
+$PARSER "$TEST_DIR/porting" --format json > "$TEST_DIR/stream.json"
+$PARSER "$TEST_DIR/porting" --format ndjson > "$TEST_DIR/stream.ndjson"
+head -3 "$TEST_DIR/stream.ndjson"
+# Each function is one line, serialized as in the JSON output
+diff <(jq -c '.functions[]' "$TEST_DIR/stream.json" | sort) <(sed '$d' "$TEST_DIR/stream.ndjson" | sort)
+[ "$(tail -1 "$TEST_DIR/stream.ndjson" | jq -c '.summary')" = "$(jq -c '.summary' "$TEST_DIR/stream.json")" ]
+[ "$(tail -1 "$TEST_DIR/stream.ndjson" | jq '.schema_version')" = "$(jq '.schema_version' "$TEST_DIR/stream.json")" ]
+# A synthetic 10k-function tree: memory stays flat when streaming
+mkdir -p "$TEST_DIR/big/src"
+for f in $(seq 1 100); do
+    for g in $(seq 1 100); do
+        echo "pub fn f${f}_${g}(x: u64) -> u64 { x + ${g} }"
+    done > "$TEST_DIR/big/src/m$f.rs"
+done
+[ "$($PARSER "$TEST_DIR/big" --format ndjson | wc -l)" = "10001" ]
+if command -v python3 > /dev/null; then
+    max_rss() {
+        python3 -c 'import resource, subprocess, sys; subprocess.run(sys.argv[1:], stdout=subprocess.DEVNULL, check=True); print(resource.getrusage(resource.RUSAGE_CHILDREN).ru_maxrss)' "$@"
+    }
+    json_rss=$(max_rss $PARSER "$TEST_DIR/big" --format json)
+    ndjson_rss=$(max_rss $PARSER "$TEST_DIR/big" --format ndjson)
+    echo "max RSS: json ${json_rss} KB, ndjson ${ndjson_rss} KB"
+    [ "$((ndjson_rss * 2))" -lt "$json_rss" ]
+fi
+echo
+
+echo "=== Test 88: Markdown tables ==="
+mkdir -p "$TEST_DIR/md/src"
+cat > "$TEST_DIR/md/src/lib.rs" << 'EOF'
+mod field;
+
+verus! {
+    spec fn double(x: int) -> int { x * 2 }
+
+    fn a_very_long_function_name_that_must_not_be_truncated_in_any_way(x: u64) -> u64
+        requires x < 100,
+    {
+        x
+    }
+}
+EOF
+cat > "$TEST_DIR/md/src/field.rs" << 'EOF'
+verus! {
+    fn either(a: bool, b: bool) -> bool { a || b }
+
+    #[verifier::external_body]
+    fn opaque_io() {}
+
+    proof fn lemma_pipe() ensures true || false {}
+}
+EOF
+$PARSER "$TEST_DIR/md" --format markdown --show-signature | tee "$TEST_DIR/md.out"
+cat > "$TEST_DIR/md.expected" << 'EOF'
+## `crate`
+
+| Function | Kind | Lines | Spec status | Signature |
+|---|---|---|---|---|
+| `double` | spec fn | 4-4 | spec | `spec fn double(x: int) -> int` |
+| `a_very_long_function_name_that_must_not_be_truncated_in_any_way` | fn | 6-10 | specified | `fn a_very_long_function_name_that_must_not_be_truncated_in_any_way(x: u64) -> u64 requires x < 100` |
+
+## `field`
+
+| Function | Kind | Lines | Spec status | Signature |
+|---|---|---|---|---|
+| `field::either` | fn | 2-2 | unspecified | `fn either(a: bool, b: bool) -> bool` |
+| `field::opaque_io` | fn | 4-5 | unspecified (trusted: external_body) | `fn opaque_io()` |
+| `field::lemma_pipe` | proof fn | 7-7 | proof | `proof fn lemma_pipe() ensures true \|\| false` |
+
+## Totals
+
+- Files: 2
+- Functions: 5
+- By kind: 3 fn, 1 proof fn, 1 spec fn
+- Specified exec functions: 1/2 (50.0%)
+- Trusted functions: 1
+EOF
+diff "$TEST_DIR/md.expected" "$TEST_DIR/md.out"
+$PARSER "$TEST_DIR/md" --format markdown --group-by file > "$TEST_DIR/md_files.out"
+grep -c '^## `.*/md/src/\(lib\|field\)\.rs`$' "$TEST_DIR/md_files.out" | grep -qx 2
+echo
+
+echo "=== Test 89: SARIF output ==="
+mkdir -p "$TEST_DIR/sarif/src"
+cat > "$TEST_DIR/sarif/src/lib.rs" << 'EOF'
+verus! {
+    fn no_spec(x: u64) -> u64 { x }
+
+    fn with_spec(x: u64) -> (r: u64)
+        ensures r == x,
+    {
+        x
+    }
+
+    proof fn uses_assume(x: int) {
+        assume(x > 0);
+    }
+
+    fn count_down(n: u64) -> u64
+        requires n < 10,
+    {
+        if n == 0 { 0 } else { count_down(n - 1) }
+    }
+
+    #[verifier::external_body]
+    fn io() {}
+}
+EOF
+$PARSER "$TEST_DIR/sarif" --format sarif > "$TEST_DIR/findings.sarif"
+$PARSER missing-decreases "$TEST_DIR/sarif" --format sarif > "$TEST_DIR/decreases.sarif"
+jq -c '[.runs[0].results[] | [.ruleId, .level, .locations[0].logicalLocations[0].fullyQualifiedName, .locations[0].physicalLocation.region.startLine, .locations[0].physicalLocation.region.endLine]]' "$TEST_DIR/findings.sarif" | tee "$TEST_DIR/findings.out"
+[ "$(cat "$TEST_DIR/findings.out")" = '[["unspecified-exec-fn","warning","no_spec",2,2],["assume-or-admit","warning","uses_assume",10,12]]' ]
+jq -c '[.runs[0].results[] | [.ruleId, .level, .message.text]]' "$TEST_DIR/decreases.sarif" | tee "$TEST_DIR/decreases.out"
+[ "$(cat "$TEST_DIR/decreases.out")" = '[["missing-decreases","error","`count_down` has direct recursion but no decreases clause"]]' ]
+# The run names the tool and its version
+[ "$(jq -r '.runs[0].tool.driver | "\(.name) \(.version)"' "$TEST_DIR/decreases.sarif")" = "$($PARSER --version)" ]
+[ "$(jq -r '.runs[0].results[0].locations[0].physicalLocation.artifactLocation.uri' "$TEST_DIR/decreases.sarif")" = "file://$TEST_DIR/sarif/src/lib.rs" ]
+# Reports without findings refuse SARIF
+if $PARSER call-graph "$TEST_DIR/sarif" --format sarif 2> /dev/null; then exit 1; fi
+# Validate against the official SARIF 2.1.0 schema, the one the logs name
+# in $schema, once a copy is vendored in tests/fixtures; until then against
+# the constraints it puts on the objects written here
+SARIF_SCHEMA="$(dirname "$0")/tests/fixtures/sarif-schema-2.1.0.json"
+cat > "$TEST_DIR/sarif-subset.json" << 'EOF'
+{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "type": "object",
+  "required": ["version", "runs"],
+  "properties": {
+    "$schema": {"type": "string", "format": "uri"},
+    "version": {"enum": ["2.1.0"]},
+    "runs": {"type": ["array", "null"], "items": {"$ref": "#/definitions/run"}}
+  },
+  "definitions": {
+    "message": {
+      "type": "object",
+      "anyOf": [{"required": ["text"]}, {"required": ["id"]}],
+      "properties": {"text": {"type": "string"}, "id": {"type": "string"}}
+    },
+    "level": {"enum": ["none", "note", "warning", "error"]},
+    "run": {
+      "type": "object",
+      "required": ["tool"],
+      "properties": {
+        "tool": {
+          "type": "object",
+          "required": ["driver"],
+          "properties": {"driver": {"$ref": "#/definitions/toolComponent"}}
+        },
+        "results": {"type": ["array", "null"], "items": {"$ref": "#/definitions/result"}}
+      }
+    },
+    "toolComponent": {
+      "type": "object",
+      "required": ["name"],
+      "properties": {
+        "name": {"type": "string"},
+        "version": {"type": "string"},
+        "informationUri": {"type": "string", "format": "uri"},
+        "rules": {
+          "type": "array",
+          "uniqueItems": true,
+          "items": {"$ref": "#/definitions/reportingDescriptor"}
+        }
+      }
+    },
+    "reportingDescriptor": {
+      "type": "object",
+      "required": ["id"],
+      "properties": {
+        "id": {"type": "string"},
+        "shortDescription": {"$ref": "#/definitions/message"},
+        "defaultConfiguration": {
+          "type": "object",
+          "properties": {"level": {"$ref": "#/definitions/level"}}
+        }
+      }
+    },
+    "result": {
+      "type": "object",
+      "required": ["message"],
+      "properties": {
+        "ruleId": {"type": "string"},
+        "ruleIndex": {"type": "integer", "minimum": -1},
+        "level": {"$ref": "#/definitions/level"},
+        "message": {"$ref": "#/definitions/message"},
+        "locations": {"type": "array", "items": {"$ref": "#/definitions/location"}}
+      }
+    },
+    "location": {
+      "type": "object",
+      "properties": {
+        "physicalLocation": {
+          "type": "object",
+          "anyOf": [{"required": ["address"]}, {"required": ["artifactLocation"]}],
+          "properties": {
+            "artifactLocation": {
+              "type": "object",
+              "properties": {"uri": {"type": "string", "format": "uri-reference"}}
+            },
+            "region": {
+              "type": "object",
+              "properties": {
+                "startLine": {"type": "integer", "minimum": 1},
+                "endLine": {"type": "integer", "minimum": 1}
+              }
+            }
+          }
+        },
+        "logicalLocations": {
+          "type": "array",
+          "uniqueItems": true,
+          "items": {
+            "type": "object",
+            "properties": {
+              "fullyQualifiedName": {"type": "string"},
+              "kind": {"type": "string"}
+            }
+          }
+        }
+      }
+    }
+  }
+}
+EOF
+if [ ! -f "$SARIF_SCHEMA" ]; then
+    SARIF_SCHEMA="$TEST_DIR/sarif-subset.json"
+fi
+if ! python3 -c 'import jsonschema' 2> /dev/null; then
+    echo "FAIL: validating the SARIF logs needs the jsonschema Python package"
+    exit 1
+fi
+python3 - "$SARIF_SCHEMA" "$TEST_DIR/findings.sarif" "$TEST_DIR/decreases.sarif" << 'EOF'
+import json, sys, jsonschema
+schema = json.load(open(sys.argv[1]))
+for path in sys.argv[2:]:
+    jsonschema.validate(json.load(open(path)), schema)
+print("SARIF logs valid")
+EOF
+echo
+
+echo "=== Test 90: SQLite output ==="
+if command -v sqlite3 > /dev/null; then
+    $PARSER "$TEST_DIR/sarif" --format sqlite --output "$TEST_DIR/funcs.db" --show-calls
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT COUNT(*) FROM functions")" = "5" ]
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT COUNT(*) FROM files")" = "1" ]
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT caller, callee_path, callee FROM calls")" = "count_down|count_down|count_down" ]
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT run_id, total_functions, exec_functions, specified FROM summary")" = "1|5|3|2" ]
+    sqlite3 "$TEST_DIR/funcs.db" "SELECT qualified_name FROM functions WHERE specified = 0 ORDER BY start_line" | tee "$TEST_DIR/unspecified.out"
+    [ "$(tr '\n' ' ' < "$TEST_DIR/unspecified.out")" = "no_spec io " ]
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT json_extract(json, '$.end_line') FROM functions WHERE name = 'uses_assume'")" = "12" ]
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = 'functions' ORDER BY name" | tr '\n' ' ')" = "functions_file functions_qualified_name " ]
+    # --append adds a run; without it the tables are replaced
+    $PARSER "$TEST_DIR/sarif" --format sqlite --output "$TEST_DIR/funcs.db" --append
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT run_id, COUNT(*) FROM functions GROUP BY run_id" | tr '\n' ' ')" = "1|5 2|5 " ]
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT COUNT(*) FROM summary")" = "2" ]
+    $PARSER "$TEST_DIR/sarif" --format sqlite --output "$TEST_DIR/funcs.db"
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT DISTINCT run_id FROM functions")" = "1" ]
+    [ "$(sqlite3 "$TEST_DIR/funcs.db" "SELECT COUNT(*) FROM sqlite_master WHERE name = 'calls'")" = "0" ]
+    # The database file is required
+    if $PARSER "$TEST_DIR/sarif" --format sqlite 2> /dev/null; then exit 1; fi
+else
+    echo "sqlite3 not found, skipping"
+fi
+echo
+
+echo "=== Test 91: GitHub Actions annotations ==="
+mkdir -p "$TEST_DIR/gh/src"
+cat > "$TEST_DIR/gh/src/lib.rs" << 'EOF'
+verus! {
+    fn no_spec(x: u64) -> u64 { x }
+
+    proof fn uses_admit() {
+        admit();
+    }
+
+    spec fn all_pos(s: Seq<int>) -> bool {
+        forall|i: int| 0 <= i < s.len() ==> s[i] > 0
+    }
+
+    fn loop_forever(n: u64) -> u64
+        requires n < 10,
+    {
+        loop_forever(n)
+    }
+}
+EOF
+parser_path="$(pwd)/$PARSER"
+(cd "$TEST_DIR" && "$parser_path" gh --format github) | tee "$TEST_DIR/gh.out"
+cat > "$TEST_DIR/gh.expected" << 'EOF'
+::warning file=gh/src/lib.rs,line=2,endLine=2,title=unspecified-exec-fn::`no_spec` has no requires, ensures or returns clause
+::warning file=gh/src/lib.rs,line=4,endLine=6,title=assume-or-admit::`uses_admit` contains 0 assume and 1 admit()
+EOF
+diff "$TEST_DIR/gh.expected" "$TEST_DIR/gh.out"
+# Paths are made relative to the checkout
+GITHUB_WORKSPACE="$TEST_DIR/gh" $PARSER missing-decreases "$TEST_DIR/gh" --format github > "$TEST_DIR/gh_decreases.out"
+[ "$(cat "$TEST_DIR/gh_decreases.out")" = '::error file=src/lib.rs,line=12,endLine=16,title=missing-decreases::`loop_forever` has direct recursion but no decreases clause' ]
+# Plain listings are notices
+GITHUB_WORKSPACE="$TEST_DIR/gh" $PARSER quantifiers "$TEST_DIR/gh" --format github > "$TEST_DIR/gh_quantifiers.out"
+[ "$(cat "$TEST_DIR/gh_quantifiers.out")" = '::notice file=src/lib.rs,line=8,endLine=10,title=quantifiers::`all_pos`: 1 quantifiers, 0 triggers' ]
+GITHUB_WORKSPACE="$TEST_DIR/gh" $PARSER closure --function no_spec "$TEST_DIR/gh" --format github > "$TEST_DIR/gh_closure.out"
+[ "$(cat "$TEST_DIR/gh_closure.out")" = '::notice file=src/lib.rs,line=2,endLine=2,title=closure::`no_spec` is listed by closure' ]
+GITHUB_WORKSPACE="$TEST_DIR/gh" $PARSER callers --function loop_forever "$TEST_DIR/gh" --format github > "$TEST_DIR/gh_callers.out"
+[ "$(cat "$TEST_DIR/gh_callers.out")" = '::notice file=src/lib.rs,line=12,endLine=16,title=callers::`loop_forever` is listed by callers' ]
+# Reports that list no functions refuse it
+if $PARSER module-graph "$TEST_DIR/gh" --format github 2> /dev/null; then exit 1; fi
+echo
+
+echo "=== Test 92: ctags output ==="
+mkdir -p "$TEST_DIR/tags/src"
+cat > "$TEST_DIR/tags/src/lib.rs" << 'EOF'
+mod inner;
+
+pub struct Point { x: u64 }
+
+const LIMIT: u64 = 10;
+
+verus! {
+    pub open spec fn double(x: int) -> int { x * 2 }
+
+    #[verifier::external_body]
+    pub fn read_path(p: &str) -> u64 { 0 }
+
+    proof fn lemma_double(x: int)
+        ensures double(x) == x + x,
+    {}
+
+    impl Point {
+        pub fn get_x(&self) -> u64 { self.x }
+    }
+}
+EOF
+printf 'fn helper(p: &Path) -> u64 { 0 } // a/b\n' > "$TEST_DIR/tags/src/inner.rs"
+(cd "$TEST_DIR/tags" && "$parser_path" src --format ctags --include-types --include-consts) > "$TEST_DIR/tags/tags"
+grep -v '^!_TAG_' "$TEST_DIR/tags/tags" | tee "$TEST_DIR/tags.out"
+cat > "$TEST_DIR/tags.expected" << 'EOF'
+LIMIT	src/lib.rs	/^const LIMIT: u64 = 10;$/;"	C	line:5
+Point	src/lib.rs	/^pub struct Point { x: u64 }$/;"	s	line:3
+double	src/lib.rs	/^    pub open spec fn double(x: int) -> int { x * 2 }$/;"	x	line:8
+get_x	src/lib.rs	/^        pub fn get_x(&self) -> u64 { self.x }$/;"	f	line:18
+helper	src/inner.rs	/^fn helper(p: &Path) -> u64 { 0 } \/\/ a\/b$/;"	f	line:1
+lemma_double	src/lib.rs	/^    proof fn lemma_double(x: int)$/;"	p	line:13
+read_path	src/lib.rs	/^    pub fn read_path(p: &str) -> u64 { 0 }$/;"	f	line:11
+EOF
+diff "$TEST_DIR/tags.expected" "$TEST_DIR/tags.out"
+grep -qx '!_TAG_FILE_SORTED	1	/0=unsorted, 1=sorted, 2=foldcase/' "$TEST_DIR/tags/tags"
+[ "$(grep -v '^!_TAG_' "$TEST_DIR/tags/tags" | LC_ALL=C sort)" = "$(cat "$TEST_DIR/tags.out")" ]
+# The patterns still find definitions that moved
+if command -v vim > /dev/null; then
+    sed -i 's/^    proof fn lemma_double/\n\n&/' "$TEST_DIR/tags/src/lib.rs"
+    (cd "$TEST_DIR/tags" && vim -es -N -u NONE -c 'set tags=./tags' -c 'tag lemma_double' \
+        -c 'redir! > vim.out' -c 'echo line(".")' -c 'redir END' -c 'qa!')
+    [ "$(tr -d '\n' < "$TEST_DIR/tags/vim.out")" = "15" ]
+fi
+if $PARSER quantifiers "$TEST_DIR/tags" --format ctags 2> /dev/null; then exit 1; fi
+echo
+
+echo "=== Test 93: Writing the output to a file ==="
+mkdir -p "$TEST_DIR/out/src/nested"
+cat > "$TEST_DIR/out/src/lib.rs" << 'EOF'
+mod nested;
+
+verus! {
+    fn with_assume(x: u64) -> u64 {
+        assume(x > 0);
+        x
+    }
+}
+EOF
+printf 'mod inner;\nfn outer() {}\n' > "$TEST_DIR/out/src/nested/mod.rs"
+printf 'fn inner_one() {}\nfn inner_two() {}\n' > "$TEST_DIR/out/src/nested/inner.rs"
+$PARSER "$TEST_DIR/out" --format json > "$TEST_DIR/out_stdout.json"
+$PARSER "$TEST_DIR/out" --format json -o "$TEST_DIR/out.json" > "$TEST_DIR/out_log.txt"
+cmp "$TEST_DIR/out_stdout.json" "$TEST_DIR/out.json"
+[ ! -s "$TEST_DIR/out_log.txt" ]
+# Missing parent directories are created, and the file is replaced
+$PARSER "$TEST_DIR/out" --format text --output "$TEST_DIR/new/dir/out.txt"
+$PARSER "$TEST_DIR/out" --format text --output "$TEST_DIR/new/dir/out.txt"
+[ "$($PARSER "$TEST_DIR/out" --format text)" = "$(cat "$TEST_DIR/new/dir/out.txt")" ]
+# A failed check still writes the complete output
+if $PARSER "$TEST_DIR/out" --format json --fail-if-assumes 0 -o "$TEST_DIR/debt.json" 2> /dev/null; then exit 1; fi
+[ "$(jq '.summary.total_functions' "$TEST_DIR/debt.json")" = "4" ]
+# Reports write their output too
+$PARSER trusted "$TEST_DIR/out" --format json -o "$TEST_DIR/out_trusted.json"
+[ "$(jq -r '.trusted_leaves | keys[]' "$TEST_DIR/out_trusted.json")" = "with_assume" ]
+# A directory gets one file per input file, at its path under the directory
+mkdir -p "$TEST_DIR/per_file"
+$PARSER "$TEST_DIR/out" --format json -o "$TEST_DIR/per_file"
+(cd "$TEST_DIR/per_file" && find . -type f | sort) | tee "$TEST_DIR/per_file.out"
+cat > "$TEST_DIR/per_file.expected" << 'EOF'
+./src/lib.rs.json
+./src/nested/inner.rs.json
+./src/nested/mod.rs.json
+EOF
+diff "$TEST_DIR/per_file.expected" "$TEST_DIR/per_file.out"
+[ "$(jq -r '[.functions[].name] | join(" ")' "$TEST_DIR/per_file/src/nested/inner.rs.json")" = "inner_one inner_two" ]
+[ "$(jq '.summary.total_functions' "$TEST_DIR/per_file/src/nested/inner.rs.json")" = "2" ]
+$PARSER "$TEST_DIR/out" --format markdown -o "$TEST_DIR/per_file_md/"
+[ -f "$TEST_DIR/per_file_md/src/nested/mod.rs.md" ]
+# Only the default mode writes one file per input file
+if $PARSER trusted "$TEST_DIR/out" -o "$TEST_DIR/per_file" 2> /dev/null; then exit 1; fi
+# A run that fails leaves no file behind, partial or temporary
+if $PARSER dead-functions "$TEST_DIR/out" --root no_such_fn -o "$TEST_DIR/failed/dead.json" 2> /dev/null; then exit 1; fi
+[ -z "$(find "$TEST_DIR/failed" -type f 2> /dev/null)" ]
+# Nor does a write failing midway, here past a file size limit
+mkdir -p "$TEST_DIR/big/src"
+for i in $(seq 100); do echo "fn function_$i() {}"; done > "$TEST_DIR/big/src/lib.rs"
+if (trap '' XFSZ; ulimit -f 4; $PARSER "$TEST_DIR/big" --format json -o "$TEST_DIR/big/out/funcs.json" 2> /dev/null); then exit 1; fi
+[ -z "$(find "$TEST_DIR/big/out" -type f)" ]
+# Without --output, a reader closing the pipe early ends the run quietly;
+# here the pipe is closed before the first write
+python3 -c 'import os, subprocess, sys; r, w = os.pipe(); os.close(r); sys.exit(subprocess.call(sys.argv[1:], stdout=w))' \
+    $PARSER "$TEST_DIR/big" --format text 2> "$TEST_DIR/closed_pipe.err"
+[ ! -s "$TEST_DIR/closed_pipe.err" ]
+echo
+
+echo "=== Test 94: Compact and pretty JSON ==="
+mkdir -p "$TEST_DIR/compact/src"
+cat > "$TEST_DIR/compact/src/lib.rs" << 'EOF'
+/// Adds one.
+///
+/// Says "hi" \ too.
+fn add_one(x: u64) -> u64 { x + 1 }
+
+fn other() {}
+EOF
+# Piped output is minified by default, one line
+$PARSER "$TEST_DIR/compact" --format json --show-docs > "$TEST_DIR/compact.json"
+[ "$(wc -l < "$TEST_DIR/compact.json")" = "1" ]
+$PARSER "$TEST_DIR/compact" --format json --show-docs --compact | cmp - "$TEST_DIR/compact.json"
+$PARSER "$TEST_DIR/compact" --format json --show-docs --pretty > "$TEST_DIR/pretty.json"
+[ "$(sed -n 2p "$TEST_DIR/pretty.json")" = '  "schema_version": 4,' ]
+# Both hold the same JSON, newlines in strings escaped
+diff <(jq -S . "$TEST_DIR/compact.json") <(jq -S . "$TEST_DIR/pretty.json")
+[ "$(jq -r '.functions[0].doc' "$TEST_DIR/compact.json")" = "$(printf 'Adds one.\n\nSays "hi" \\ too.')" ]
+grep -qF '"doc":"Adds one.\n\nSays \"hi\" \\ too."' "$TEST_DIR/compact.json"
+compact_size=$(wc -c < "$TEST_DIR/compact.json")
+pretty_size=$(wc -c < "$TEST_DIR/pretty.json")
+echo "compact: $compact_size bytes, pretty: $pretty_size bytes"
+[ $((compact_size * 4)) -lt $((pretty_size * 3)) ]
+# Reports and queries follow, and a terminal gets pretty JSON
+[ "$($PARSER trusted "$TEST_DIR/compact" | wc -l)" = "1" ]
+$PARSER query --input "$TEST_DIR/compact.json" --pretty --name-regex "^other$" | jq -e '.functions[0].name == "other"' > /dev/null
+[ "$($PARSER query --input "$TEST_DIR/compact.json" --pretty --name-regex "^other$" | wc -l)" -gt 1 ]
+if command -v script > /dev/null; then
+    script -qc "$PARSER $TEST_DIR/compact --format json" /dev/null > "$TEST_DIR/tty.json"
+    [ "$(tr -d '\r' < "$TEST_DIR/tty.json" | sed -n 2p)" = '  "schema_version": 4,' ]
+fi
+if $PARSER "$TEST_DIR/compact" --compact --pretty 2> /dev/null; then exit 1; fi
+echo
+
+echo "=== Test 95: JSON Schema of the output ==="
+$PARSER --print-schema > "$TEST_DIR/schema.json"
+jq -e '.properties.schema_version.const == 4 and .["$schema"] == "http://json-schema.org/draft-07/schema#"' "$TEST_DIR/schema.json" > /dev/null
+[ "$(jq '.properties.schema_version.const' "$TEST_DIR/schema.json")" = "$(jq '.schema_version' "$TEST_DIR/out.json")" ]
+if $PARSER --print-schema "$TEST_DIR/out" 2> /dev/null; then exit 1; fi
+# Real output, with every field turned on, over all the sources written so
+# far; the schema allows no other properties, so a field added without
+# updating it fails here
+$PARSER "$TEST_DIR" --format json --show-visibility --show-kind --show-calls --show-signature \
+    --show-specs --show-attributes --show-by-asserts --show-docs \
+    --include-types --include-traits --include-consts > "$TEST_DIR/everything.json" 2> /dev/null
+$PARSER "$TEST_DIR" --format json --context-string > "$TEST_DIR/context_string.json" 2> /dev/null
+$PARSER query --input "$TEST_DIR/everything.json" --module nested > "$TEST_DIR/queried.json"
+[ "$(jq '.functions | length' "$TEST_DIR/everything.json")" -gt 100 ]
+if ! python3 -c 'import jsonschema' 2> /dev/null; then
+    echo "FAIL: validating the outputs needs the jsonschema Python package"
+    exit 1
+fi
+python3 - "$TEST_DIR/schema.json" "$TEST_DIR/everything.json" "$TEST_DIR/context_string.json" \
+    "$TEST_DIR/queried.json" "$TEST_DIR/closure.json" "$TEST_DIR/within.json" "$TEST_DIR/out.json" << 'EOF'
+import json, sys, jsonschema
+schema = json.load(open(sys.argv[1]))
+jsonschema.Draft7Validator.check_schema(schema)
+for path in sys.argv[2:]:
+    jsonschema.validate(json.load(open(path)), schema)
+print("outputs valid")
+EOF
+echo
+
+echo "=== Test 96: Grouping the output by module ==="
+mkdir -p "$TEST_DIR/by_module/src"
+cat > "$TEST_DIR/by_module/src/lib.rs" << 'EOF'
+#[cfg(feature = "simd")]
+#[path = "backend_simd.rs"]
+mod backend;
+#[cfg(not(feature = "simd"))]
+#[path = "backend_serial.rs"]
+mod backend;
+
+mod inline {
+    fn nested() {}
+}
+
+fn top() {}
+EOF
+echo 'pub fn add_simd() {}' > "$TEST_DIR/by_module/src/backend_simd.rs"
+echo 'pub fn add_serial() {}' > "$TEST_DIR/by_module/src/backend_serial.rs"
+(cd "$TEST_DIR" && "$parser_path" by_module --group-by module) > "$TEST_DIR/by_module.json"
+jq -c '.functions_by_module | map_values({files, functions: [.functions[].name]})' "$TEST_DIR/by_module.json" | tee "$TEST_DIR/by_module.out"
+[ "$(cat "$TEST_DIR/by_module.out")" = '{"backend":{"files":["by_module/src/backend_serial.rs","by_module/src/backend_simd.rs"],"functions":["add_serial","add_simd"]},"crate":{"files":["by_module/src/lib.rs"],"functions":["top"]},"inline":{"files":["by_module/src/lib.rs"],"functions":["nested"]}}' ]
+jq -e '.summary.total_modules == 3 and (has("functions_by_file") | not) and (.functions | length) == 4' "$TEST_DIR/by_module.json" > /dev/null
+# By file on request, without a module count
+$PARSER "$TEST_DIR/by_module" --group-by file > "$TEST_DIR/by_file.json"
+jq -e '(.functions_by_file | length) == 3 and (has("functions_by_module") | not) and (.summary | has("total_modules") | not)' "$TEST_DIR/by_file.json" > /dev/null
+# Saved output grouped by module can be queried and regrouped
+[ "$($PARSER query --input "$TEST_DIR/by_module.json" --module backend --group-by module | jq -c '.functions_by_module | keys')" = '["backend"]' ]
+[ "$($PARSER query --input "$TEST_DIR/by_module.json" --module backend --group-by file | jq -c '.functions_by_file | length')" = "2" ]
+if ! python3 -c 'import jsonschema' 2> /dev/null; then
+    echo "FAIL: validating the grouped output needs the jsonschema Python package"
+    exit 1
+fi
+python3 -c 'import json, sys, jsonschema; jsonschema.validate(json.load(open(sys.argv[2])), json.load(open(sys.argv[1])))' \
+    "$TEST_DIR/schema.json" "$TEST_DIR/by_module.json"
+# Markdown still makes a table per module by default
+[ "$($PARSER "$TEST_DIR/by_module" --format markdown | grep '^## ' | tr '\n' ' ')" = '## `backend` ## `crate` ## `inline` ## Totals ' ]
+echo
+
+echo "=== Test 97: Deterministic output ==="
+# The same sources created in opposite orders, which file systems listing
+# in creation order walk differently; the hash maps of one run differ from
+# those of the next
+for order in forward backward; do
+    mkdir -p "$TEST_DIR/order_$order/src"
+    files="a b c d e f g h"
+    [ "$order" = backward ] && files="h g f e d c b a"
+    for name in $files; do
+        mkdir -p "$TEST_DIR/order_$order/src/$name"
+        printf 'pub fn %s_one() {}\n\npub fn %s_two() { %s_one() }\n' "$name" "$name" "$name" > "$TEST_DIR/order_$order/src/$name/mod.rs"
+        printf 'fn %s_inner() {}\n' "$name" > "$TEST_DIR/order_$order/src/$name.rs"
+    done
+    printf 'mod a;\nmod b;\nmod c;\nmod d;\nmod e;\nmod f;\nmod g;\nmod h;\n' > "$TEST_DIR/order_$order/src/lib.rs"
+done
+for order in forward backward; do
+    for run in 1 2; do
+        (cd "$TEST_DIR/order_$order" && "$parser_path" src --pretty --show-calls --show-kind --show-specs --group-by file) > "$TEST_DIR/order_${order}_$run.json" 2> /dev/null
+        (cd "$TEST_DIR/order_$order" && "$parser_path" src --format ndjson) > "$TEST_DIR/order_${order}_$run.ndjson" 2> /dev/null
+    done
+done
+cmp "$TEST_DIR/order_forward_1.json" "$TEST_DIR/order_forward_2.json"
+cmp "$TEST_DIR/order_forward_1.json" "$TEST_DIR/order_backward_1.json"
+cmp "$TEST_DIR/order_backward_1.json" "$TEST_DIR/order_backward_2.json"
+cmp "$TEST_DIR/order_forward_1.ndjson" "$TEST_DIR/order_backward_2.ndjson"
+# Functions are sorted by file, then line, and so are the files
+jq -r '.functions[] | "\(.file):\(.start_line)"' "$TEST_DIR/order_forward_1.json" > "$TEST_DIR/order.out"
+LC_ALL=C sort -t: -k1,1 -k2,2n "$TEST_DIR/order.out" | cmp - "$TEST_DIR/order.out"
+[ "$(head -3 "$TEST_DIR/order.out" | tr '\n' ' ')" = "src/a.rs:1 src/a/mod.rs:1 src/a/mod.rs:3 " ]
+jq -r '.functions_by_file | keys[]' "$TEST_DIR/order_forward_1.json" | LC_ALL=C sort -c
+echo
+
+echo "=== Test 98: Sorting the functions ==="
+mkdir -p "$TEST_DIR/sort/src"
+cat > "$TEST_DIR/sort/src/lib.rs" << 'EOF'
+mod b_file;
+
+verus! {
+    spec fn zeta(x: int) -> int {
+        x
+    }
+
+    fn medium(x: u64) -> u64 {
+        let y = x;
+        y
+    }
+
+    proof fn alpha() {}
+}
+EOF
+cat > "$TEST_DIR/sort/src/b_file.rs" << 'EOF'
+pub fn big(x: u64) -> u64 {
+    let a = x;
+    let b = a;
+    let c = b;
+    c
+}
+
+pub fn tiny() {}
+
+pub fn also_tiny() {}
+EOF
+sorted() {
+    (cd "$TEST_DIR" && "$parser_path" sort --format text "$@") | tr '\n' ' '
+}
+# Text lists names in order unless sorted otherwise
+[ "$(sorted)" = "alpha b_file::also_tiny b_file::big b_file::tiny medium zeta " ]
+[ "$(sorted --sort file)" = "b_file::big b_file::tiny b_file::also_tiny zeta medium alpha " ]
+[ "$(sorted --sort name)" = "alpha b_file::also_tiny b_file::big medium b_file::tiny zeta " ]
+# Ties by size, and by kind, break by qualified name
+[ "$(sorted --sort size)" = "alpha b_file::also_tiny b_file::tiny zeta medium b_file::big " ]
+[ "$(sorted --sort size --reverse)" = "b_file::big medium zeta b_file::tiny b_file::also_tiny alpha " ]
+[ "$(sorted --sort kind)" = "b_file::also_tiny b_file::big b_file::tiny medium alpha zeta " ]
+[ "$(sorted --reverse)" = "zeta medium b_file::tiny b_file::big b_file::also_tiny alpha " ]
+[ "$(sorted --sort file --reverse)" = "alpha medium zeta b_file::also_tiny b_file::tiny b_file::big " ]
+# The functions array and the detailed format follow
+(cd "$TEST_DIR" && "$parser_path" sort --sort size --reverse) | jq -c '[.functions[] | .end_line - .start_line]' | tee "$TEST_DIR/sizes.out"
+[ "$(cat "$TEST_DIR/sizes.out")" = "[5,3,2,0,0,0]" ]
+(cd "$TEST_DIR" && "$parser_path" sort --sort kind --format ndjson) | head -1 | jq -e '.kind == "fn"' > /dev/null
+$PARSER "$TEST_DIR/sort" --format detailed --sort size --reverse | grep -o '^[a-z_]* @ [^ ]*' | sed 's|@ .*/src/|@ |' | tr '\n' ';' | tee "$TEST_DIR/detailed_sorted.out"
+[ "$(cat "$TEST_DIR/detailed_sorted.out")" = "big @ b_file.rs:1:6;medium @ lib.rs:8:11;zeta @ lib.rs:4:6;tiny @ b_file.rs:8:8;also_tiny @ b_file.rs:10:10;alpha @ lib.rs:13:13;" ]
+# The biggest gaps first in the unspecified functions
+[ "$(sorted --unspecified-only --sort size --reverse)" = "b_file::big medium b_file::tiny b_file::also_tiny " ]
+# Reports take it too, and keep their own order without it
+$PARSER "$TEST_DIR/sort" --format json > "$TEST_DIR/sort.json"
+[ "$($PARSER query --input "$TEST_DIR/sort.json" --sort name --format text | head -1)" = "alpha" ]
+# dead-functions groups by file, and sorts within each
+[ "$($PARSER dead-functions "$TEST_DIR/sort" --root medium --format json --sort size --reverse | jq -c '[.dead_functions[][] | .qualified_name]')" = '["b_file::big","b_file::tiny","b_file::also_tiny","zeta","alpha"]' ]
+[ "$($PARSER missing-decreases "$TEST_DIR/closure" --format json --sort name --reverse | jq -c '[.missing_decreases[].qualified_name]')" = '["odd","fact::fact","even"]' ]
+[ "$($PARSER closure --function pow2k "$TEST_DIR/closure" | jq -c '[.functions[].name]')" = '["pow2k","square","mul"]' ]
+[ "$($PARSER closure --function pow2k "$TEST_DIR/closure" --sort name | jq -c '[.functions[].name]')" = '["mul","pow2k","square"]' ]
+echo
+
+echo "=== Test 99: Counting the functions only ==="
+counts() {
+    (cd "$TEST_DIR" && "$parser_path" sort --count-only "$@")
+}
+[ "$(counts)" = '{"schema_version":4,"total_functions":6,"total_files":2}' ]
+[ "$(counts | jq .total_functions)" = "$(cd "$TEST_DIR" && "$parser_path" sort --format json | jq .summary.total_functions)" ]
+[ "$(counts --show-kind | jq -c .functions_by_kind)" = '{"fn":4,"proof fn":1,"spec fn":1}' ]
+# The filters apply before counting
+[ "$(counts --kind proof | jq -c '[.total_functions, .functions_by_kind]')" = '[1,{"proof fn":1}]' ]
+[ "$(counts --show-kind --format text)" = "$(printf '6 functions in 2 files\nfn: 4\nproof fn: 1\nspec fn: 1')" ]
+(cd "$TEST_DIR" && "$parser_path" sort --format json --show-kind -o sort_kinds.json)
+[ "$(cd "$TEST_DIR" && "$parser_path" query --input sort_kinds.json --kind spec --count-only | jq -c '[.total_functions, .total_files, .functions_by_kind]')" = '[1,1,{"spec fn":1}]' ]
+if counts --format dot 2> "$TEST_DIR/count_err.txt"; then
+    echo "FAIL: --count-only accepted --format dot"
+    exit 1
+fi
+grep -q "does not support --format dot" "$TEST_DIR/count_err.txt"
+echo "PASS"
+echo
+
+echo "=== Test 100: Statistics over the functions ==="
+stats() {
+    (cd "$TEST_DIR" && "$parser_path" sort --stats "$@")
+}
+stats | tee "$TEST_DIR/stats.json" | jq -e '.total_functions == 6 and .asserts == null' > /dev/null
+[ "$(jq -c '[.lines.min, .lines.p50, .lines.p90, .lines.max, .lines.total]' "$TEST_DIR/stats.json")" = "[1,1,6,6,16]" ]
+[ "$(jq -c '[.lines.histogram[] | [.from, .to, .count]]' "$TEST_DIR/stats.json")" = "[[1,1,3],[2,3,1],[4,7,2]]" ]
+[ "$(jq -c .by_kind "$TEST_DIR/stats.json")" = '{"fn":4,"proof fn":1,"spec fn":1}' ]
+[ "$(jq -c .by_visibility "$TEST_DIR/stats.json")" = '{"private":3,"pub":3}' ]
+[ "$(jq -c '.by_module.b_file | [.functions, .max_lines, .largest]' "$TEST_DIR/stats.json")" = '[3,6,"b_file::big"]' ]
+[ "$(stats --kind proof | jq .total_functions)" = "1" ]
+mkdir -p "$TEST_DIR/stats_specs/src"
+cat > "$TEST_DIR/stats_specs/src/lib.rs" << 'EOF'
+verus! {
+    proof fn one(x: int) {
+        assert(x == x);
+    }
+
+    proof fn three(x: int) {
+        assert(x == x);
+        assert(x + 0 == x);
+        assert(forall|y: int| y == y);
+    }
+
+    spec fn none() -> bool {
+        true
+    }
+}
+EOF
+$PARSER "$TEST_DIR/stats_specs" --stats --show-specs > "$TEST_DIR/stats_specs.json"
+[ "$(jq -c '[.asserts.total, .asserts.p50, .asserts.max, [.asserts.histogram[].count]]' "$TEST_DIR/stats_specs.json")" = "[4,1,3,[1,1,1]]" ]
+[ "$(jq .quantifiers.total "$TEST_DIR/stats_specs.json")" = "1" ]
+$PARSER "$TEST_DIR/stats_specs" --stats --show-specs --format text > "$TEST_DIR/stats.txt"
+grep -q "^Asserts per function$" "$TEST_DIR/stats.txt"
+grep -q "^  min 0  p50 1  p90 3  p99 3  max 3  mean 1.3$" "$TEST_DIR/stats.txt"
+grep -q "^  crate .* three$" "$TEST_DIR/stats.txt"
+if stats --format ctags 2> "$TEST_DIR/stats_err.txt"; then
+    echo "FAIL: --stats accepted --format ctags"
+    exit 1
+fi
+grep -q "does not support --format ctags" "$TEST_DIR/stats_err.txt"
+echo "PASS"
+echo
+
+echo "=== Test 101: Flat JSON output ==="
+$PARSER "$TEST_DIR/sort" --format json-flat > "$TEST_DIR/flat.json"
+jq -e 'type == "array" and length == 6 and all(has("file"))' "$TEST_DIR/flat.json" > /dev/null
+[ "$(jq -c '[.[].qualified_name]' "$TEST_DIR/flat.json")" = "$($PARSER "$TEST_DIR/sort" | jq -c '[.functions[].qualified_name]')" ]
+# functions_by_file only on request
+$PARSER "$TEST_DIR/sort" | jq -e 'has("functions_by_file") | not' > /dev/null
+$PARSER "$TEST_DIR/sort" --group-by file | jq -e '.functions_by_file | length == 2' > /dev/null
+# Listings and the counts take it, reports do not
+[ "$($PARSER query --input "$TEST_DIR/sort.json" --name-regex '^b' --format json-flat | jq -c '[.[].name]')" = '["big"]' ]
+[ "$($PARSER "$TEST_DIR/sort" --format json-flat --count-only | jq .total_functions)" = "6" ]
+if $PARSER unused-lemmas "$TEST_DIR/sort" --format json-flat 2> "$TEST_DIR/flat_err.txt"; then
+    echo "FAIL: unused-lemmas accepted --format json-flat"
+    exit 1
+fi
+grep -q "does not support --format json-flat" "$TEST_DIR/flat_err.txt"
+echo "PASS"
+echo
+
+echo "=== Test 102: NUL-delimited and shell-quoted names ==="
+mkdir -p "$TEST_DIR/spaced dir/src"
+cat > "$TEST_DIR/spaced dir/src/lib.rs" << 'EOF'
+use std::ops::Mul;
+
+pub struct Fe;
+
+impl<'a> Mul<&'a Fe> for &'a Fe {
+    type Output = Fe;
+
+    fn mul(self, other: &'a Fe) -> Fe {
+        Fe
+    }
+}
+
+pub fn free() {}
+
+pub fn other() {}
+EOF
+# Names with spaces, quotes and shell metacharacters, as a saved file
+$PARSER "$TEST_DIR/spaced dir" --include-methods \
+    | jq '.functions[0].qualified_name = "it'\''s a name" | .functions[1].qualified_name = "two  spaces \"q\" $HOME \\ *;"' \
+    > "$TEST_DIR/odd_names.json"
+jq -r '.functions[].qualified_name' "$TEST_DIR/odd_names.json" | LC_ALL=C sort > "$TEST_DIR/odd_names.expected"
+eval "set -- $($PARSER query --input "$TEST_DIR/odd_names.json" --format shell)"
+[ "$#" = "3" ]
+printf '%s\n' "$@" | cmp - "$TEST_DIR/odd_names.expected"
+$PARSER query --input "$TEST_DIR/odd_names.json" --format text --print0 | xargs -0 printf '%s\n' | cmp - "$TEST_DIR/odd_names.expected"
+# Plain names stay unquoted, trait impl methods are quoted
+[ "$($PARSER "$TEST_DIR/spaced dir" --format shell)" = "'<Fe as Mul>::mul' free other" ]
+# Verus arguments keep their spaces through xargs -0, --null being the old name
+$PARSER "$TEST_DIR/spaced dir" --include-methods --format verus-args --print0 | xargs -0 printf '%s\n' > "$TEST_DIR/print0_args.out"
+grep -qx "<Fe as Mul>::mul" "$TEST_DIR/print0_args.out"
+$PARSER "$TEST_DIR/spaced dir" --include-methods --format verus-args --null | cmp - <($PARSER "$TEST_DIR/spaced dir" --include-methods --format verus-args --print0)
+[ "$($PARSER module-cover --function free "$TEST_DIR/spaced dir" --format verus-args --print0 | tr '\0' '|')" = "--verify-root|" ]
+# Formats without names to terminate reject it
+for format in json detailed shell; do
+    if $PARSER "$TEST_DIR/spaced dir" --format "$format" --print0 > /dev/null 2> "$TEST_DIR/print0_err.txt"; then
+        echo "FAIL: --print0 accepted with --format $format"
+        exit 1
+    fi
+    grep -q -- "--print0 requires --format text or verus-args, not $format" "$TEST_DIR/print0_err.txt"
+done
+if $PARSER query --input "$TEST_DIR/odd_names.json" --print0 2> /dev/null; then exit 1; fi
+echo "PASS"
+echo
+
+echo "=== Test 103: Verification plans ==="
+mkdir -p "$TEST_DIR/plan dir/src"
+cat > "$TEST_DIR/plan dir/src/lib.rs" << 'EOF'
+mod inner;
+
+use std::ops::Mul;
+
+pub struct Fe;
+
+impl<'a> Mul<&'a Fe> for &'a Fe {
+    type Output = Fe;
+
+    fn mul(self, other: &'a Fe) -> Fe {
+        Fe
+    }
+}
+
+pub fn free() {}
+EOF
+echo 'pub fn deep() {}' > "$TEST_DIR/plan dir/src/inner.rs"
+# Placeholders are quoted as one word each, the rest is left to the shell
+$PARSER emit-plan "$TEST_DIR/plan dir" --template 'printf "%s|%s|%s|%s\n" {module} {function} {qualified} {file} ${PLAN_SUFFIX:-}' > "$TEST_DIR/plan.sh"
+[ "$(head -1 "$TEST_DIR/plan.sh")" = "#!/bin/sh" ]
+sh "$TEST_DIR/plan.sh" | sed "s|$TEST_DIR/||" > "$TEST_DIR/plan_sh.out"
+cat > "$TEST_DIR/plan.expected" << 'EOF'
+inner|deep|inner::deep|plan dir/src/inner.rs
+|<Fe as Mul>::mul|<Fe as Mul>::mul|plan dir/src/lib.rs
+|free|free|plan dir/src/lib.rs
+EOF
+cmp "$TEST_DIR/plan_sh.out" "$TEST_DIR/plan.expected"
+$PARSER emit-plan "$TEST_DIR/plan dir" --style makefile --template 'printf "%s|%s|%s|%s\n" {module} {function} {qualified} {file} > {qualified}.out' > "$TEST_DIR/Makefile"
+grep -qx ".PHONY: all" "$TEST_DIR/Makefile"
+grep -qx ".PHONY: _Fe_as_Mul_.mul" "$TEST_DIR/Makefile"
+if command -v make > /dev/null; then
+    (cd "$TEST_DIR" && make -s -j 3)
+    cat "$TEST_DIR/inner::deep.out" "$TEST_DIR/<Fe as Mul>::mul.out" "$TEST_DIR/free.out" | sed "s|$TEST_DIR/||" | cmp - "$TEST_DIR/plan.expected"
+    # Phony targets run again even though their output exists
+    [ "$(cd "$TEST_DIR" && make -n free)" != "" ]
+fi
+# The filters and --sort choose the functions and their order
+[ "$($PARSER emit-plan "$TEST_DIR/plan dir" --template 'echo {qualified}' --sort name --reverse | sed 1,2d | tr '\n' ';')" = "echo '<Fe as Mul>::mul';echo free;echo inner::deep;" ]
+if $PARSER emit-plan "$TEST_DIR/plan dir" --template 'echo {func}' 2> "$TEST_DIR/plan_err.txt"; then
+    echo "FAIL: emit-plan accepted an unknown placeholder"
+    exit 1
+fi
+grep -q "Unknown placeholder {func}" "$TEST_DIR/plan_err.txt"
+# {module_args} selects the crate root for its functions instead of an empty module
+$PARSER emit-plan "$TEST_DIR/plan dir" --template 'verus {module_args} --verify-function {function}' | sed 1,2d > "$TEST_DIR/plan_args.out"
+cat > "$TEST_DIR/plan_args.expected" << 'EOF'
+verus --verify-only-module inner --verify-function deep
+verus --verify-root --verify-function '<Fe as Mul>::mul'
+verus --verify-root --verify-function free
+EOF
+cmp "$TEST_DIR/plan_args.out" "$TEST_DIR/plan_args.expected"
+# The jobs of `plan --format text` quote their arguments the same way
+$PARSER plan "$TEST_DIR/plan dir" --format text | grep -q -- "--verify-function '<Fe as Mul>::mul' "
+echo "PASS"
+echo
+
+echo "=== Test 104: Sharding the functions ==="
+mkdir -p "$TEST_DIR/shards/src"
+{
+    echo 'pub fn huge() {'
+    for i in $(seq 1 30); do echo "    let _x$i = $i;"; done
+    echo '}'
+    for i in $(seq 1 9); do echo "pub fn small_$i() {}"; done
+    echo 'pub fn medium() {'
+    for i in $(seq 1 10); do echo "    let _y$i = $i;"; done
+    echo '}'
+} > "$TEST_DIR/shards/src/lib.rs"
+shard() {
+    $PARSER "$TEST_DIR/shards" --shard-count 3 --shard-index "$@"
+}
+# Every function in exactly one shard
+for i in 0 1 2; do shard "$i" --format text; done | LC_ALL=C sort > "$TEST_DIR/shards_all.out"
+$PARSER "$TEST_DIR/shards" --format text | cmp - "$TEST_DIR/shards_all.out"
+# Balanced by lines: the big functions land in different shards
+[ "$(shard 0 --format text)" = "huge" ]
+[ "$(shard 1 --format text | head -1)" = "medium" ]
+[ "$(shard 2 | jq '[.functions[] | .end_line - .start_line + 1] | add')" = "9" ]
+# By count instead, and the same shards on every run
+[ "$(shard 0 --shard-by count --count-only | jq .total_functions)" = "4" ]
+[ "$(shard 1 --format json-flat)" = "$(shard 1 --format json-flat)" ]
+# Any format, and emit-plan
+[ "$(shard 0 --format verus-args)" = "--verify-root --verify-function huge" ]
+[ "$($PARSER emit-plan "$TEST_DIR/shards" --template 'echo {function}' --shard-count 3 --shard-index 0 | tail -1)" = "echo huge" ]
+if shard 3 2> "$TEST_DIR/shard_err.txt"; then
+    echo "FAIL: --shard-index 3 accepted with --shard-count 3"
+    exit 1
+fi
+grep -q "out of range" "$TEST_DIR/shard_err.txt"
+echo "PASS"
+echo
+
+echo "=== Test 105: Cost-aware verification jobs ==="
+mkdir -p "$TEST_DIR/plan_jobs/src"
+cat > "$TEST_DIR/plan_jobs/src/lib.rs" << 'EOF'
+verus! {
+    proof fn nonlinear(x: int, y: int)
+        ensures
+            x * y == y * x,
+    {
+        assert(x * y == y * x) by (nonlinear_arith);
+    }
+
+    fn specified(x: u64) -> (r: u64)
+        ensures
+            r == x,
+    {
+        assert(x == x);
+        x
+    }
+
+    #[verifier::rlimit(20)]
+    proof fn limited() {
+        assert(true);
+    }
+
+    fn unspecified_a() {}
+
+    fn unspecified_b() {}
+}
+EOF
+jobs() {
+    $PARSER plan "$TEST_DIR/plan_jobs" --job-cost 10 "$@"
+}
+# Lines, asserts and nonlinear arithmetic add up, the rlimit scales
+jobs > "$TEST_DIR/jobs.json"
+[ "$(jq -c '[.[].functions[] | {(.qualified_name): .cost}] | add' "$TEST_DIR/jobs.json")" = '{"unspecified_a":1,"unspecified_b":1,"specified":9,"limited":12,"nonlinear":33}' ]
+# Unspecified functions first, cheapest first, packed up to the job cost
+[ "$(jq -c '[.[] | [.job, .cost, [.functions[].qualified_name]]]' "$TEST_DIR/jobs.json")" = '[[0,2,["unspecified_a","unspecified_b"]],[1,9,["specified"]],[2,12,["limited"]],[3,33,["nonlinear"]]]' ]
+[ "$(jq -c '.[0].verus_args' "$TEST_DIR/jobs.json")" = '["--verify-root","--verify-function","unspecified_a","--verify-function","unspecified_b"]' ]
+# Heavy functions alone, the dearest first, the rest by file
+[ "$(jobs --strategy isolate-heavy | jq -c '[.[] | [.functions[].qualified_name]]')" = '[["nonlinear"],["limited"],["specified","unspecified_a"],["unspecified_b"]]' ]
+[ "$(jobs --format ndjson | wc -l)" = "4" ]
+jobs --format text > "$TEST_DIR/jobs.txt"
+[ "$(head -1 "$TEST_DIR/jobs.txt")" = "$(printf '2\t--verify-root --verify-function unspecified_a --verify-function unspecified_b')" ]
+echo "PASS"
+echo
+
+echo "=== Test 106: Baselines of the function inventory ==="
+mkdir -p "$TEST_DIR/baseline/src"
+cat > "$TEST_DIR/baseline/src/lib.rs" << 'EOF'
+verus! {
+    fn kept(x: u64) -> u64 {
+        x
+    }
+
+    fn removed() {}
+
+    spec fn changed() -> bool {
+        true
+    }
+}
+EOF
+BASELINE="$TEST_DIR/baseline.json"
+$PARSER "$TEST_DIR/baseline" --write-baseline "$BASELINE" > /dev/null
+[ "$(jq -c '.functions[0]' "$BASELINE")" = '{"qualified_name":"changed","kind":"spec fn","signature":"spec fn changed() -> bool"}' ]
+[ "$(jq -r '[.functions[].qualified_name] | join(";")' "$BASELINE")" = "changed;kept;removed" ]
+$PARSER "$TEST_DIR/baseline" --check-baseline "$BASELINE" > /dev/null
+# Functions that only moved still match
+sed -i '1a\
+
+' "$TEST_DIR/baseline/src/lib.rs"
+$PARSER "$TEST_DIR/baseline" --check-baseline "$BASELINE" > /dev/null
+# Additions, removals and changes of kind or signature fail, listed
+cat > "$TEST_DIR/baseline/src/lib.rs" << 'EOF'
+verus! {
+    fn kept(x: u64) -> u64 {
+        x
+    }
+
+    proof fn changed() {}
+
+    fn added() {}
+}
+EOF
+if $PARSER "$TEST_DIR/baseline" --check-baseline "$BASELINE" > /dev/null 2> "$TEST_DIR/baseline.err"; then
+    echo "FAIL: the changed functions passed the baseline check"
+    exit 1
+fi
+cat > "$TEST_DIR/baseline.expected" << 'EOF'
++ added [fn]: fn added()
+~ changed
+    was changed [spec fn]: spec fn changed() -> bool
+    now changed [proof fn]: proof fn changed()
+- removed [fn]: fn removed()
+EOF
+tail -n +2 "$TEST_DIR/baseline.err" | diff "$TEST_DIR/baseline.expected" -
+# A baseline from another version is refused
+jq '.baseline_version = 99' "$BASELINE" > "$TEST_DIR/baseline_old.json"
+if $PARSER "$TEST_DIR/baseline" --check-baseline "$TEST_DIR/baseline_old.json" > /dev/null 2> "$TEST_DIR/baseline.err"; then
+    echo "FAIL: a baseline of another version was accepted"
+    exit 1
+fi
+grep -q "regenerate it with --write-baseline" "$TEST_DIR/baseline.err"
+echo "PASS"
+echo
+
+echo "=== Test 107: Diffing saved outputs ==="
+DIFF_DIR="$TEST_DIR/diff"
+write_version() {
+    rm -rf "$DIFF_DIR"
+    mkdir -p "$DIFF_DIR/src"
+    cat > "$DIFF_DIR/src/lib.rs"
+}
+save() {
+    $PARSER "$DIFF_DIR" --show-kind --show-signature --show-specs > "$TEST_DIR/$1"
+}
+write_version << 'EOF'
+mod a;
+verus! {
+    fn kept(x: u64) -> (r: u64)
+        ensures
+            r == x,
+    {
+        x
+    }
+
+    spec fn turned() -> bool {
+        true
+    }
+
+    fn gone() {}
+
+    fn same() {}
+}
+EOF
+echo 'verus! { proof fn lemma_moved() {} }' > "$DIFF_DIR/src/a.rs"
+save diff_old.json
+write_version << 'EOF'
+mod b;
+verus! {
+
+    fn kept(x: u64) -> u64 {
+        x
+    }
+
+    proof fn turned() {}
+
+    proof fn lemma_new() {}
+
+    proof fn lemma_newer() {}
+
+    fn same() {}
+}
+EOF
+echo 'verus! { proof fn lemma_moved() {} }' > "$DIFF_DIR/src/b.rs"
+save diff_new.json
+$PARSER diff "$TEST_DIR/diff_old.json" "$TEST_DIR/diff_new.json" > "$TEST_DIR/diff.json"
+[ "$(jq -c '.summary' "$TEST_DIR/diff.json")" = '{"added":2,"removed":1,"moved":1,"modified":2,"added_by_kind":{"proof fn":2},"removed_by_kind":{"fn":1},"lost_specs":1,"gained_specs":0}' ]
+[ "$(jq -r '[.added[].qualified_name] | join(";")' "$TEST_DIR/diff.json")" = "lemma_new;lemma_newer" ]
+[ "$(jq -r '.removed[0].qualified_name' "$TEST_DIR/diff.json")" = "gone" ]
+# A function in another module is a move, not a removal and an addition
+[ "$(jq -c '.moved[0] | [.old_qualified_name, .qualified_name, .new_file]' "$TEST_DIR/diff.json")" = "[\"a::lemma_moved\",\"b::lemma_moved\",\"$DIFF_DIR/src/b.rs\"]" ]
+[ "$(jq -c '[.modified[] | [.qualified_name, [.changes[].field]]]' "$TEST_DIR/diff.json")" = '[["kept",["signature","specified"]],["turned",["kind","signature"]]]' ]
+$PARSER diff "$TEST_DIR/diff_old.json" "$TEST_DIR/diff_new.json" --format text > "$TEST_DIR/diff.txt"
+head -4 "$TEST_DIR/diff.txt" | diff - <(printf '%s\n' "2 added, 1 removed, 1 moved, 2 modified" "2 new proof fns" "1 fn removed" "1 exec fn lost its specs")
+grep -qx "    kind was: spec fn" "$TEST_DIR/diff.txt"
+grep -qx "    kind now: proof fn" "$TEST_DIR/diff.txt"
+# Identical outputs differ in nothing
+[ "$($PARSER diff "$TEST_DIR/diff_new.json" "$TEST_DIR/diff_new.json" --format text)" = "0 added, 0 removed, 0 moved, 0 modified" ]
+# Formats other than json and text are rejected before reading the inputs
+for format in dot detailed; do
+    if $PARSER diff "$TEST_DIR/diff_old.json" "$TEST_DIR/diff_new.json" --format "$format" 2> "$TEST_DIR/diff_err.txt"; then
+        echo "FAIL: diff accepted --format $format"
+        exit 1
+    fi
+    grep -q "possible values: json, text" "$TEST_DIR/diff_err.txt"
+done
+echo "PASS"
+echo
+
+echo "=== Test 108: Diffing git revisions ==="
+GIT_REPO="$TEST_DIR/git_diff"
+mkdir -p "$GIT_REPO/crate/src"
+commit() {
+    git -C "$GIT_REPO" add -A
+    git -C "$GIT_REPO" -c user.name=test -c user.email=test@example.com commit -q -m "$1"
+}
+git -C "$GIT_REPO" init -q
+cat > "$GIT_REPO/crate/src/lib.rs" << 'EOF'
+mod shapes;
+mod untouched;
+verus! {
+    spec fn turned() -> bool {
+        true
+    }
+
+    fn gone() {}
+
+    fn specced(x: u64) -> (r: u64)
+        ensures
+            r == x,
+    {
+        x
+    }
+}
+EOF
+cat > "$GIT_REPO/crate/src/shapes.rs" << 'EOF'
+verus! {
+    struct A;
+    struct B;
+
+    impl Default for A {
+        fn default() -> Self {
+            A
+        }
+    }
+
+    impl Default for B {
+        fn default() -> Self {
+            B
+        }
+    }
+}
+EOF
+# Never parsed for functions, so its syntax error goes unreported
+echo 'verus! { proof fn lemma_same( }' > "$GIT_REPO/crate/src/untouched.rs"
+commit one
+git -C "$GIT_REPO" mv crate/src/shapes.rs crate/src/figures.rs
+cat > "$GIT_REPO/crate/src/lib.rs" << 'EOF'
+mod figures;
+mod untouched;
+verus! {
+    proof fn turned() {}
+
+    fn specced(x: u64) -> u64 {
+        x
+    }
+
+    proof fn lemma_new() {}
+}
+EOF
+commit two
+# The working tree is not read
+rm "$GIT_REPO/crate/src/lib.rs"
+git_diff() {
+    $PARSER diff --git-old HEAD~1 --git-new HEAD "$GIT_REPO/crate" "$@"
+}
+$PARSER diff --git-old HEAD~1 --git-new HEAD "$GIT_REPO/crate" > "$TEST_DIR/git_diff.json" 2> "$TEST_DIR/git_diff.err"
+[ ! -s "$TEST_DIR/git_diff.err" ]
+[ "$(jq -c '.summary' "$TEST_DIR/git_diff.json")" = '{"added":1,"removed":1,"moved":2,"modified":2,"added_by_kind":{"proof fn":1},"removed_by_kind":{"fn":1},"lost_specs":1,"gained_specs":0}' ]
+[ "$(jq -r '.added[0].file' "$TEST_DIR/git_diff.json")" = "$GIT_REPO/crate/src/lib.rs" ]
+# The functions of the renamed file moved, however alike
+[ "$(jq -c '[.moved[] | [.old_qualified_name, .qualified_name]]' "$TEST_DIR/git_diff.json")" = '[["shapes::<A as Default>::default","figures::<A as Default>::default"],["shapes::<B as Default>::default","figures::<B as Default>::default"]]' ]
+[ "$(jq -c '[.modified[] | [.qualified_name, [.changes[].field]]]' "$TEST_DIR/git_diff.json")" = '[["turned",["kind","signature"]],["specced",["signature","specified"]]]' ]
+[ "$(git_diff --format text | head -1)" = "1 added, 1 removed, 2 moved, 2 modified" ]
+[ "$($PARSER diff --git-old HEAD --git-new HEAD "$GIT_REPO/crate" --format text)" = "0 added, 0 removed, 0 moved, 0 modified" ]
+if $PARSER diff --git-old HEAD~1 --git-new no-such-rev "$GIT_REPO/crate" 2> "$TEST_DIR/git_diff.err"; then
+    echo "FAIL: an unknown revision was accepted"
+    exit 1
+fi
+grep -q "no-such-rev is not a git revision" "$TEST_DIR/git_diff.err"
+if $PARSER diff --git-old HEAD~1 --git-new HEAD "$GIT_REPO/crate" extra.json 2> /dev/null; then
+    echo "FAIL: --git-old accepted two inputs"
+    exit 1
+fi
+echo "PASS"
+echo
+
+echo "=== Test 109: Crate roots of every cargo target ==="
+C="$TEST_DIR/targets_crate"
+mkdir -p "$C/src/bin/server" "$C/tests" "$C/examples" "$C/tools"
+cat > "$C/Cargo.toml" << 'EOF'
+[package]
+name = "targets_crate"
+
+[[bin]]
+name = "gen"
+path = "tools/gen.rs"
+EOF
+echo 'mod shared; pub fn lib_fn() {}' > "$C/src/lib.rs"
+echo 'pub fn shared_fn() {}' > "$C/src/shared.rs"
+echo 'fn tool_main() {}' > "$C/src/bin/tool.rs"
+echo 'mod cli; fn server_main() {}' > "$C/src/bin/server/main.rs"
+echo 'fn parse_args() {}' > "$C/src/bin/server/cli.rs"
+echo 'fn it_works() {}' > "$C/tests/smoke.rs"
+echo 'fn demo() {}' > "$C/examples/demo.rs"
+echo 'mod gen_helpers; fn gen_main() {}' > "$C/tools/gen.rs"
+echo 'fn helper() {}' > "$C/tools/gen_helpers.rs"
+$PARSER "$C" > "$TEST_DIR/targets.json"
+jq -c '.functions[] | [.name, .module_path]' "$TEST_DIR/targets.json" | LC_ALL=C sort | tee "$TEST_DIR/targets.out"
+cat > "$TEST_DIR/targets.expected" << 'EOF'
+["demo",""]
+["gen_main",""]
+["helper","gen_helpers"]
+["it_works",""]
+["lib_fn",""]
+["parse_args","cli"]
+["server_main",""]
+["shared_fn","shared"]
+["tool_main",""]
+EOF
+diff "$TEST_DIR/targets.expected" "$TEST_DIR/targets.out"
+[ "$(jq -c '.unreachable_files // []' "$TEST_DIR/targets.json")" = '[]' ]
+# A binary's functions are verified at its crate root
+[ "$($PARSER "$C/src/bin/tool.rs" --format verus-args)" = "--verify-root --verify-function tool_main" ]
+echo "PASS"
+echo
+
 # Cleanup
 echo "Cleaning up test directory..."
 rm -rf "$TEST_DIR"
@@ -104,8 +104,9 @@ class VerusParser:
         data = self.parse_functions(path, include_verus_constructs=include_verus_constructs)
         
         result = {}
-        for file_path, functions in data["functions_by_file"].items():
-            result[file_path] = [(func["name"], func["start_line"]) for func in functions]
+        for func in data["functions"]:
+            if "file" in func:
+                result.setdefault(func["file"], []).append((func["name"], func["start_line"]))
         
         return result
     